@@ -1,17 +1,88 @@
-use super::*;
+use {
+  super::*, crate::sat_trait::TraitRanges, bitcoin::hashes::sha256, std::collections::BTreeSet,
+};
 
-#[derive(Deserialize, Default, PartialEq, Debug)]
+#[derive(Deserialize, Serialize, Default, PartialEq, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub(crate) struct Config {
+  #[serde(serialize_with = "serialize_sorted")]
   pub(crate) hidden: HashSet<InscriptionId>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) admin_token: Option<String>,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub(crate) api_keys: BTreeMap<String, ApiKey>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) audit_log: Option<PathBuf>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub(crate) bitcoin_rpc_pass: Option<String>,
+  #[serde(skip_serializing_if = "Option::is_none")]
   pub(crate) bitcoin_rpc_user: Option<String>,
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub(crate) hidden_content_hashes: BTreeSet<sha256::Hash>,
+  #[serde(default, skip_serializing_if = "BTreeSet::is_empty")]
+  pub(crate) hidden_sats: BTreeSet<Sat>,
+  #[serde(default, skip_serializing_if = "RateLimits::is_empty")]
+  pub(crate) rate_limits: RateLimits,
+  #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+  pub(crate) trait_ranges: TraitRanges,
+}
+
+#[derive(Deserialize, Serialize, Default, PartialEq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ApiKey {
+  pub(crate) requests_per_minute: u32,
+}
+
+#[derive(Deserialize, Serialize, Default, PartialEq, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RateLimits {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) content: Option<RateLimit>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) html: Option<RateLimit>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) json: Option<RateLimit>,
+}
+
+impl RateLimits {
+  fn is_empty(&self) -> bool {
+    self == &Self::default()
+  }
+}
+
+#[derive(Deserialize, Serialize, Default, PartialEq, Debug, Clone, Copy)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RateLimit {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) concurrency: Option<usize>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub(crate) requests_per_minute: Option<u32>,
 }
 
 impl Config {
   pub(crate) fn is_hidden(&self, inscription_id: InscriptionId) -> bool {
     self.hidden.contains(&inscription_id)
   }
+
+  pub(crate) fn is_hidden_content(&self, content_hash: sha256::Hash) -> bool {
+    self.hidden_content_hashes.contains(&content_hash)
+  }
+
+  pub(crate) fn is_hidden_sat(&self, sat: Sat) -> bool {
+    self.hidden_sats.contains(&sat)
+  }
+}
+
+fn serialize_sorted<S: Serializer>(
+  hidden: &HashSet<InscriptionId>,
+  serializer: S,
+) -> Result<S::Ok, S::Error> {
+  let mut hidden = hidden
+    .iter()
+    .map(ToString::to_string)
+    .collect::<Vec<String>>();
+  hidden.sort();
+  hidden.serialize(serializer)
 }
 
 #[cfg(test)]
@@ -37,6 +108,32 @@ mod tests {
     assert!(!config.is_hidden(b));
   }
 
+  #[test]
+  fn content_and_sats_can_be_hidden() {
+    let hash = sha256::Hash::hash(b"hello");
+
+    let config = Config {
+      hidden_content_hashes: iter::once(hash).collect(),
+      hidden_sats: iter::once(Sat(5)).collect(),
+      ..Default::default()
+    };
+
+    assert!(config.is_hidden_content(hash));
+    assert!(!config.is_hidden_content(sha256::Hash::hash(b"world")));
+    assert!(config.is_hidden_sat(Sat(5)));
+    assert!(!config.is_hidden_sat(Sat(6)));
+  }
+
+  #[test]
+  fn config_round_trips_through_yaml() {
+    let config: Config = serde_yaml::from_reader(File::open("ord.yaml").unwrap()).unwrap();
+
+    assert_eq!(
+      serde_yaml::from_str::<Config>(&serde_yaml::to_string(&config).unwrap()).unwrap(),
+      config
+    );
+  }
+
   #[test]
   fn example_config_file_is_valid() {
     let _: Config = serde_yaml::from_reader(File::open("ord.yaml").unwrap()).unwrap();
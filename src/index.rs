@@ -1,17 +1,19 @@
 use {
   self::{
+    block_source::{BlockSource, BlockSourceKind},
     entry::{
-      outpoint_prefix_end, BlockHashValue, Entry, InscriptionEntry, InscriptionEntryValue,
-      InscriptionIdValue, OutPointPrefix, OutPointPrefixValue, OutPointValue, SatPointValue,
-      SatRange,
+      outpoint_prefix_end, BlockHashValue, ContentHashValue, Entry, InscriptionEntry,
+      InscriptionEntryValue, InscriptionIdValue, OutPointPrefix, OutPointPrefixValue,
+      OutPointValue, SatPointValue, SatRange,
     },
     index::block_index::BlockIndex,
+    inscription_query::InscriptionQuery,
     reorg::*,
     updater::Updater,
   },
   super::*,
-  crate::wallet::Wallet,
-  bitcoin::block::Header,
+  crate::{sat_trait::TraitRanges, wallet::Wallet},
+  bitcoin::{block::Header, hashes::sha256},
   bitcoincore_rpc::{
     json::{GetBlockHeaderResult, GetRawTransactionResult},
     Client,
@@ -20,21 +22,25 @@ use {
   indicatif::{ProgressBar, ProgressStyle},
   log::log_enabled,
   redb::{
-    CompactionError, Database, MultimapTable, MultimapTableDefinition, ReadableMultimapTable,
-    ReadableTable, Table, TableDefinition, WriteTransaction,
+    CompactionError, Database, MultimapTable, MultimapTableDefinition, ReadTransaction,
+    ReadableMultimapTable, ReadableTable, Table, TableDefinition, WriteTransaction,
   },
   std::collections::HashMap,
   std::io::{BufWriter, Read, Write},
+  std::ops::Range,
 };
 
+mod blk_files;
 pub mod block_index;
+pub(crate) mod block_source;
 mod entry;
 mod fetcher;
+pub(crate) mod inscription_query;
 mod reorg;
 mod rtx;
 mod updater;
 
-const SCHEMA_VERSION: u64 = 5;
+const SCHEMA_VERSION: u64 = 7;
 
 macro_rules! define_table {
   ($name:ident, $key:ty, $value:ty) => {
@@ -49,18 +55,29 @@ macro_rules! define_multimap_table {
   };
 }
 
+define_table! { CONTENT_HASH_TO_BODY, &ContentHashValue, &[u8] }
+define_multimap_table! { CONTENT_HASH_TO_INSCRIPTION_ID, &ContentHashValue, &InscriptionIdValue }
+define_table! { CONTENT_TYPE_AND_INSCRIPTION_NUMBER, (&[u8], i64), () }
 define_table! { HEIGHT_TO_BLOCK_HASH, u64, &BlockHashValue }
 define_multimap_table! { HEIGHT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
 define_table! { INSCRIPTION_ID_TO_INSCRIPTION_ENTRY, &InscriptionIdValue, InscriptionEntryValue }
+define_table! { INSCRIPTION_ID_TO_CONTENT, &InscriptionIdValue, (Option<&[u8]>, Option<&ContentHashValue>) }
 define_table! { INSCRIPTION_ID_TO_SATPOINT, &InscriptionIdValue, &SatPointValue }
 define_table! { INSCRIPTION_NUMBER_TO_INSCRIPTION_ID, i64, &InscriptionIdValue }
+define_table! { LOST_SAT_OFFSET_TO_RANGE, u64, (u64, u64, u64, u8) }
 define_table! { OUTPOINT_TO_SAT_RANGES, &OutPointValue, &[u8] }
 define_table! { SAT_TO_OUTPOINT, u64, &OutPointPrefixValue }
+define_table! { SAT_RANGE_TO_HISTORY, (u64, u64, u32), (u64, u64, &OutPointValue) }
 define_table! { OUTPOINT_TO_VALUE, &OutPointValue, u64}
+define_table! { OUTPOINT_TO_TX_OUT, &OutPointValue, &[u8] }
+define_table! { RARITY_AND_INSCRIPTION_NUMBER, (u8, i64), () }
 define_table! { REINSCRIPTION_ID_TO_SEQUENCE_NUMBER, &InscriptionIdValue, u64 }
 define_multimap_table! { SATPOINT_TO_INSCRIPTION_ID, &SatPointValue, &InscriptionIdValue }
+define_multimap_table! { SCRIPT_PUBKEY_TO_OUTPOINT, &[u8], &OutPointValue }
 define_multimap_table! { SAT_TO_INSCRIPTION_ID, u64, &InscriptionIdValue }
 define_table! { SAT_TO_SATPOINT, u64, &SatPointValue }
+define_table! { SAT_TRAIT_RANGE_TO_END, (u8, u64), u64 }
+define_table! { SAT_TRAIT_TO_SATPOINT, (u8, u64), (u64, &SatPointValue) }
 define_table! { STATISTIC_TO_COUNT, u64, u64 }
 define_table! { WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP, u64, u128 }
 
@@ -83,9 +100,31 @@ pub(crate) enum Statistic {
 }
 
 impl Statistic {
+  const ALL: [Self; 7] = [
+    Self::Schema,
+    Self::Commits,
+    Self::LostSats,
+    Self::OutputsTraversed,
+    Self::SatRanges,
+    Self::UnboundInscriptions,
+    Self::LastSavePointHeight,
+  ];
+
   fn key(self) -> u64 {
     self.into()
   }
+
+  fn name(self) -> &'static str {
+    match self {
+      Self::Schema => "schema",
+      Self::Commits => "commits",
+      Self::LostSats => "lost_sats",
+      Self::OutputsTraversed => "outputs_traversed",
+      Self::SatRanges => "sat_ranges",
+      Self::UnboundInscriptions => "unbound_inscriptions",
+      Self::LastSavePointHeight => "last_save_point_height",
+    }
+  }
 }
 
 impl From<Statistic> for u64 {
@@ -94,6 +133,31 @@ impl From<Statistic> for u64 {
   }
 }
 
+/// Why sats ended up in the null outpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[repr(u8)]
+pub enum LostCause {
+  /// Fees and subsidy not claimed by the coinbase transaction.
+  Unclaimed = 0,
+  /// Outputs overwritten by a later transaction with the same txid.
+  DuplicateTxid = 1,
+}
+
+impl LostCause {
+  fn key(self) -> u8 {
+    self as u8
+  }
+
+  fn from_key(key: u8) -> Self {
+    match key {
+      0 => Self::Unclaimed,
+      1 => Self::DuplicateTxid,
+      _ => panic!("invalid lost cause key {key}"),
+    }
+  }
+}
+
 #[derive(Serialize)]
 pub(crate) struct Info {
   pub(crate) blocks_indexed: u64,
@@ -149,13 +213,14 @@ pub struct FindRangeOutput {
 }
 
 pub(crate) struct Index {
-  client: Client,
+  client: Mutex<Option<Arc<Client>>>,
   database: Database,
   path: PathBuf,
   first_inscription_height: u64,
   genesis_block_coinbase_transaction: Transaction,
   genesis_block_coinbase_txid: Txid,
   height_limit: Option<u64>,
+  metrics: Arc<Metrics>,
   no_progress_bar: bool,
   options: Options,
   unrecoverably_reorged: AtomicBool,
@@ -163,7 +228,16 @@ pub(crate) struct Index {
 
 impl Index {
   pub(crate) fn open(options: &Options) -> Result<Self> {
-    let client = options.bitcoin_rpc_client()?;
+    let metrics = Arc::new(Metrics::default());
+
+    // connect early, so that bad credentials or a chain mismatch are reported
+    // now, unless blocks come from a file and Bitcoin Core may not be running
+    let client = match options.block_source {
+      BlockSourceKind::File => None,
+      _ => Some(Arc::new(
+        options.bitcoin_rpc_client_with_metrics(Some(metrics.clone()))?,
+      )),
+    };
 
     let path = if let Some(path) = &options.index {
       path.clone()
@@ -222,6 +296,15 @@ impl Index {
           }
         }
 
+        if let Some(trait_ranges) = Self::stored_trait_ranges(&database.begin_read()?)? {
+          if trait_ranges != Self::trait_range_entries(&options.load_config()?.trait_ranges) {
+            bail!(
+              "index at `{}` was built with different `trait_ranges` than are configured, consider restoring them or deleting and rebuilding the index",
+              path.display()
+            );
+          }
+        }
+
         database
       }
       Err(_) => {
@@ -233,12 +316,15 @@ impl Index {
 
         tx.set_durability(redb::Durability::Immediate);
 
+        tx.open_multimap_table(CONTENT_HASH_TO_INSCRIPTION_ID)?;
+        tx.open_table(CONTENT_TYPE_AND_INSCRIPTION_NUMBER)?;
         tx.open_table(HEIGHT_TO_BLOCK_HASH)?;
         tx.open_multimap_table(HEIGHT_TO_INSCRIPTION_ID)?;
         tx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
         tx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
         tx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
         tx.open_table(OUTPOINT_TO_VALUE)?;
+        tx.open_table(RARITY_AND_INSCRIPTION_NUMBER)?;
         tx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
         tx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
         tx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
@@ -248,13 +334,39 @@ impl Index {
         tx.open_table(STATISTIC_TO_COUNT)?
           .insert(&Statistic::Schema.key(), &SCHEMA_VERSION)?;
 
-        if options.index_sats || options.index_utxos {
+        if options.index_sats
+          || options.index_utxos
+          || options.index_traits
+          || options.index_sat_history
+        {
           tx.open_table(OUTPOINT_TO_SAT_RANGES)?
             .insert(&OutPoint::null().store(), [].as_slice())?;
+          tx.open_table(LOST_SAT_OFFSET_TO_RANGE)?;
           if options.index_utxos {
             tx.open_table(SAT_TO_OUTPOINT)?
               .insert(0, &OutPoint::null().store().store())?;
+            tx.open_table(OUTPOINT_TO_TX_OUT)?;
           }
+          if options.index_traits {
+            tx.open_table(SAT_TRAIT_TO_SATPOINT)?;
+            let mut sat_trait_range_to_end = tx.open_table(SAT_TRAIT_RANGE_TO_END)?;
+            for (range, end) in Self::trait_range_entries(&options.load_config()?.trait_ranges) {
+              sat_trait_range_to_end.insert(range, end)?;
+            }
+          }
+          if options.index_sat_history {
+            tx.open_table(SAT_RANGE_TO_HISTORY)?;
+          }
+        }
+
+        if options.index_addresses {
+          tx.open_table(OUTPOINT_TO_TX_OUT)?;
+          tx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?;
+        }
+
+        if options.index_content {
+          tx.open_table(CONTENT_HASH_TO_BODY)?;
+          tx.open_table(INSCRIPTION_ID_TO_CONTENT)?;
         }
 
         tx.commit()?;
@@ -268,23 +380,43 @@ impl Index {
 
     Ok(Self {
       genesis_block_coinbase_txid: genesis_block_coinbase_transaction.txid(),
-      client,
+      client: Mutex::new(client),
       database,
       path,
       first_inscription_height: options.first_inscription_height(),
       genesis_block_coinbase_transaction,
       height_limit: options.height_limit,
+      metrics,
       no_progress_bar: options.no_progress_bar,
       options: options.clone(),
       unrecoverably_reorged: AtomicBool::new(false),
     })
   }
 
+  /// Bitcoin Core's RPC client, which is only connected on first use if the
+  /// index is updated from a file of blocks.
+  fn client(&self) -> Result<Arc<Client>> {
+    let mut client = self.client.lock().unwrap();
+
+    match client.as_ref() {
+      Some(client) => Ok(client.clone()),
+      None => {
+        let connected = Arc::new(
+          self
+            .options
+            .bitcoin_rpc_client_with_metrics(Some(self.metrics.clone()))?,
+        );
+        *client = Some(connected.clone());
+        Ok(connected)
+      }
+    }
+  }
+
   pub(crate) fn get_unspent_outputs(&self, _wallet: Wallet) -> Result<BTreeMap<OutPoint, Amount>> {
     let mut utxos = BTreeMap::new();
     utxos.extend(
       self
-        .client
+        .client()?
         .list_unspent(None, None, None, None, None)?
         .into_iter()
         .map(|utxo| {
@@ -302,12 +434,14 @@ impl Index {
     }
 
     for JsonOutPoint { txid, vout } in self
-      .client
+      .client()?
       .call::<Vec<JsonOutPoint>>("listlockunspent", &[])?
     {
       utxos.insert(
         OutPoint { txid, vout },
-        Amount::from_sat(self.client.get_raw_transaction(&txid, None)?.output[vout as usize].value),
+        Amount::from_sat(
+          self.client()?.get_raw_transaction(&txid, None)?.output[vout as usize].value,
+        ),
       );
     }
     let rtx = self.database.begin_read()?;
@@ -358,6 +492,114 @@ impl Index {
     }
   }
 
+  pub(crate) fn has_tx_out_index(&self) -> Result<bool> {
+    match self.begin_read()?.0.open_table(OUTPOINT_TO_TX_OUT) {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn has_trait_index(&self) -> Result<bool> {
+    match self.begin_read()?.0.open_table(SAT_TRAIT_TO_SATPOINT) {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  /// The `trait_ranges` the trait index was built with, which are fixed when
+  /// the index is created since sats already indexed can't be re-matched.
+  pub(crate) fn trait_ranges(&self) -> Result<Option<TraitRanges>> {
+    Ok(
+      Self::stored_trait_ranges(&self.database.begin_read()?)?.map(|entries| {
+        let mut trait_ranges = TraitRanges::new();
+        for ((key, start), end) in entries {
+          if let Some(sat_trait) = SatTrait::ALL
+            .into_iter()
+            .find(|sat_trait| sat_trait.key() == key)
+          {
+            trait_ranges
+              .entry(sat_trait)
+              .or_default()
+              .push((start, end));
+          }
+        }
+        trait_ranges
+      }),
+    )
+  }
+
+  fn stored_trait_ranges(rtx: &ReadTransaction) -> Result<Option<BTreeMap<(u8, u64), u64>>> {
+    if let Err(err) = rtx.open_table(SAT_TRAIT_TO_SATPOINT) {
+      return match err {
+        redb::TableError::TableDoesNotExist(_) => Ok(None),
+        err => Err(err.into()),
+      };
+    }
+
+    let sat_trait_range_to_end = match rtx.open_table(SAT_TRAIT_RANGE_TO_END) {
+      Ok(table) => table,
+      Err(redb::TableError::TableDoesNotExist(_)) => return Ok(Some(BTreeMap::new())),
+      Err(err) => return Err(err.into()),
+    };
+
+    let mut entries = BTreeMap::new();
+    for result in sat_trait_range_to_end.iter()? {
+      let (range, end) = result?;
+      entries.insert(range.value(), end.value());
+    }
+
+    Ok(Some(entries))
+  }
+
+  fn trait_range_entries(trait_ranges: &TraitRanges) -> BTreeMap<(u8, u64), u64> {
+    trait_ranges
+      .iter()
+      .flat_map(|(sat_trait, ranges)| {
+        ranges
+          .iter()
+          .map(move |(start, end)| ((sat_trait.key(), *start), *end))
+      })
+      .collect()
+  }
+
+  pub(crate) fn has_lost_sat_index(&self) -> Result<bool> {
+    match self.begin_read()?.0.open_table(LOST_SAT_OFFSET_TO_RANGE) {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn has_sat_history_index(&self) -> Result<bool> {
+    match self.begin_read()?.0.open_table(SAT_RANGE_TO_HISTORY) {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn has_address_index(&self) -> Result<bool> {
+    match self
+      .begin_read()?
+      .0
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)
+    {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  pub(crate) fn has_content_index(&self) -> Result<bool> {
+    match self.begin_read()?.0.open_table(INSCRIPTION_ID_TO_CONTENT) {
+      Ok(_) => Ok(true),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(false),
+      Err(err) => Err(err.into()),
+    }
+  }
+
   fn require_sat_index(&self, feature: &str) -> Result {
     if !self.has_sat_index()? {
       bail!("{feature} requires index created with `--index-sats` flag")
@@ -420,10 +662,16 @@ impl Index {
   }
 
   pub(crate) fn update(&self) -> Result {
+    self.update_from(|| block_source::open(&self.options, self.metrics.clone()))
+  }
+
+  /// Updates the index with blocks from a source returned by
+  /// `open_block_source`, which is called again to resume after a reorg.
+  fn update_from(&self, open_block_source: impl Fn() -> Result<Box<dyn BlockSource>>) -> Result {
     let mut updater = Updater::new(self)?;
 
     loop {
-      match updater.update_index() {
+      match updater.update_index(&open_block_source) {
         Ok(ok) => return Ok(ok),
         Err(err) => {
           log::info!("{}", err.to_string());
@@ -486,17 +734,11 @@ impl Index {
         let address = if satpoint.outpoint == unbound_outpoint() {
           "unbound".to_string()
         } else {
-          let output = self
-            .get_transaction(satpoint.outpoint.txid)?
-            .unwrap()
-            .output
-            .into_iter()
-            .nth(satpoint.outpoint.vout.try_into().unwrap())
-            .unwrap();
+          let script_pubkey = self.get_script_pubkey(satpoint.outpoint)?.unwrap();
           self
             .options
             .chain()
-            .address_from_script(&output.script_pubkey)
+            .address_from_script(&script_pubkey)
             .map(|address| address.to_string())
             .unwrap_or_else(|e| e.to_string())
         };
@@ -553,6 +795,33 @@ impl Index {
       .unwrap_or(0)
   }
 
+  pub(crate) fn metrics(&self) -> &Metrics {
+    &self.metrics
+  }
+
+  pub(crate) fn render_metrics(&self) -> Result<String> {
+    let rtx = self.database.begin_read()?;
+
+    let statistic_to_count = rtx.open_table(STATISTIC_TO_COUNT)?;
+
+    let mut statistics = Vec::new();
+    for statistic in Statistic::ALL {
+      statistics.push((
+        statistic.name(),
+        statistic_to_count
+          .get(&statistic.key())?
+          .map(|x| x.value())
+          .unwrap_or(0),
+      ));
+    }
+
+    Ok(
+      self
+        .metrics
+        .render(self.block_height()?.map(|height| height.n()), &statistics),
+    )
+  }
+
   pub(crate) fn block_count(&self) -> Result<u64> {
     self.begin_read()?.block_count()
   }
@@ -601,6 +870,124 @@ impl Index {
     }
   }
 
+  /// Unspent sats with `sat_trait`, in sat order, as the first sat of each
+  /// run, the number of sats in the run, and where the run starts. Returns up
+  /// to `n` runs starting at or after `from`, and the first sat of the run
+  /// after them to continue from, if any.
+  pub(crate) fn sats_with_trait(
+    &self,
+    sat_trait: SatTrait,
+    from: Option<Sat>,
+    n: usize,
+  ) -> Result<Option<(Vec<(Sat, u64, SatPoint)>, Option<Sat>)>> {
+    if !self.has_trait_index()? {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let sat_trait_to_satpoint = rtx.open_table(SAT_TRAIT_TO_SATPOINT)?;
+
+    let mut sats = Vec::new();
+
+    for result in sat_trait_to_satpoint
+      .range((sat_trait.key(), from.map_or(0, Sat::n))..=(sat_trait.key(), u64::MAX))?
+      .map(|result| {
+        result.map(|(key, value)| {
+          let start = key.value().1;
+          let (end, satpoint) = value.value();
+          (Sat(start), end - start, Entry::load(*satpoint))
+        })
+      })
+      .filter(|result| {
+        result
+          .as_ref()
+          .map_or(true, |(_, _, satpoint): &(Sat, u64, SatPoint)| {
+            satpoint.outpoint != OutPoint::null()
+          })
+      })
+      .take(n.saturating_add(1))
+    {
+      sats.push(result?);
+    }
+
+    let next = if sats.len() > n {
+      sats.pop().map(|(sat, _size, _satpoint)| sat)
+    } else {
+      None
+    };
+
+    Ok(Some((sats, next)))
+  }
+
+  /// Every output `sat` has been assigned to, oldest first, with the height
+  /// of the block that assigned it. Lost sats end in the null outpoint.
+  pub(crate) fn sat_history(&self, sat: Sat) -> Result<Option<Vec<(Height, SatPoint)>>> {
+    if !self.has_sat_history_index()? {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let sat_range_to_history = rtx.open_table(SAT_RANGE_TO_HISTORY)?;
+
+    let mut history = Vec::new();
+
+    for result in sat_range_to_history
+      .range((sat.height().starting_sat().n(), 0, 0)..=(sat.n(), u64::MAX, u32::MAX))?
+    {
+      let (key, value) = result?;
+      let (start, height, sequence) = key.value();
+      let (end, offset, outpoint) = value.value();
+
+      if sat.n() < end {
+        history.push((
+          (height, sequence),
+          SatPoint {
+            outpoint: Entry::load(*outpoint),
+            offset: offset + sat.n() - start,
+          },
+        ));
+      }
+    }
+
+    history.sort_by_key(|(key, _satpoint)| *key);
+
+    Ok(Some(
+      history
+        .into_iter()
+        .map(|((height, _sequence), satpoint)| (Height(height), satpoint))
+        .collect(),
+    ))
+  }
+
+  /// Lost sat ranges in the order they were lost, each with its offset into
+  /// the null outpoint, the height of the block that lost it and why.
+  pub(crate) fn lost_sat_ranges(
+    &self,
+  ) -> Result<Option<Vec<(u64, (u64, u64), Height, LostCause)>>> {
+    if !self.has_lost_sat_index()? {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+
+    let mut ranges = Vec::new();
+
+    for result in rtx.open_table(LOST_SAT_OFFSET_TO_RANGE)?.range(0..)? {
+      let (offset, range) = result?;
+      let (start, end, height, cause) = range.value();
+      ranges.push((
+        offset.value(),
+        (start, end),
+        Height(height),
+        LostCause::from_key(cause),
+      ));
+    }
+
+    Ok(Some(ranges))
+  }
+
   pub(crate) fn rare_sat_satpoint(&self, sat: Sat) -> Result<Option<SatPoint>> {
     if self.has_sat_index()? {
       Ok(
@@ -617,26 +1004,27 @@ impl Index {
   }
 
   pub(crate) fn block_header(&self, hash: BlockHash) -> Result<Option<Header>> {
-    self.client.get_block_header(&hash).into_option()
+    self.client()?.get_block_header(&hash).into_option()
   }
 
   pub(crate) fn block_header_info(&self, hash: BlockHash) -> Result<Option<GetBlockHeaderResult>> {
-    self.client.get_block_header_info(&hash).into_option()
+    self.client()?.get_block_header_info(&hash).into_option()
   }
 
   pub(crate) fn get_block_by_height(&self, height: u64) -> Result<Option<Block>> {
+    let client = self.client()?;
+
     Ok(
-      self
-        .client
+      client
         .get_block_hash(height)
         .into_option()?
-        .map(|hash| self.client.get_block(&hash))
+        .map(|hash| client.get_block(&hash))
         .transpose()?,
     )
   }
 
   pub(crate) fn get_block_by_hash(&self, hash: BlockHash) -> Result<Option<Block>> {
-    self.client.get_block(&hash).into_option()
+    self.client()?.get_block(&hash).into_option()
   }
 
   pub(crate) fn get_inscription_ids_by_height(&self, height: u64) -> Result<Vec<InscriptionId>> {
@@ -711,9 +1099,9 @@ impl Index {
     &self,
     inscription_id: InscriptionId,
   ) -> Result<Option<Inscription>> {
-    if self
-      .database
-      .begin_read()?
+    let rtx = self.database.begin_read()?;
+
+    if rtx
       .open_table(INSCRIPTION_ID_TO_SATPOINT)?
       .get(&inscription_id.store())?
       .is_none()
@@ -721,6 +1109,35 @@ impl Index {
       return Ok(None);
     }
 
+    match rtx.open_table(INSCRIPTION_ID_TO_CONTENT) {
+      Ok(inscription_id_to_content) => {
+        let Some(content) = inscription_id_to_content.get(&inscription_id.store())? else {
+          return Ok(None);
+        };
+
+        let (content_type, content_hash) = content.value();
+
+        let body = match content_hash {
+          Some(content_hash) => Some(
+            rtx
+              .open_table(CONTENT_HASH_TO_BODY)?
+              .get(content_hash)?
+              .ok_or_else(|| anyhow!("index has no body for inscription {inscription_id}"))?
+              .value()
+              .to_vec(),
+          ),
+          None => None,
+        };
+
+        return Ok(Some(Inscription::new(
+          content_type.map(|content_type| content_type.to_vec()),
+          body,
+        )));
+      }
+      Err(redb::TableError::TableDoesNotExist(_)) => {}
+      Err(err) => return Err(err.into()),
+    }
+
     Ok(self.get_transaction(inscription_id.txid)?.and_then(|tx| {
       Inscription::from_transaction(&tx)
         .get(inscription_id.index as usize)
@@ -728,6 +1145,27 @@ impl Index {
     }))
   }
 
+  /// The hash of an inscription's body, if the index stores content.
+  pub(crate) fn get_content_hash(
+    &self,
+    inscription_id: InscriptionId,
+  ) -> Result<Option<sha256::Hash>> {
+    match self
+      .database
+      .begin_read()?
+      .open_table(INSCRIPTION_ID_TO_CONTENT)
+    {
+      Ok(inscription_id_to_content) => Ok(
+        inscription_id_to_content
+          .get(&inscription_id.store())?
+          .and_then(|content| content.value().1.copied())
+          .map(sha256::Hash::from_byte_array),
+      ),
+      Err(redb::TableError::TableDoesNotExist(_)) => Ok(None),
+      Err(err) => Err(err.into()),
+    }
+  }
+
   pub(crate) fn get_inscriptions_on_output_with_satpoints(
     &self,
     outpoint: OutPoint,
@@ -752,75 +1190,348 @@ impl Index {
     )
   }
 
-  pub(crate) fn get_outpoint_value(&self, outpoint: &OutPoint) -> Result<u64> {
-    Ok(self.get_transaction(outpoint.txid)?.unwrap().output[outpoint.vout as usize].value)
-  }
-
-  pub(crate) fn get_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
-    if txid == self.genesis_block_coinbase_txid {
-      Ok(Some(self.genesis_block_coinbase_transaction.clone()))
-    } else {
-      self.client.get_raw_transaction(&txid, None).into_option()
-    }
-  }
-
-  pub(crate) fn get_transaction_info(
+  /// Returns up to `n` inscriptions matching `query` in inscription number
+  /// order, starting from `from`, and the number of the next match.
+  pub(crate) fn query_inscriptions(
     &self,
-    txid: Txid,
-  ) -> Result<GetRawTransactionResult, bitcoincore_rpc::Error> {
-    self.client.get_raw_transaction_info(&txid, None)
-  }
+    query: &InscriptionQuery,
+    from: Option<i64>,
+    n: usize,
+  ) -> Result<(Vec<InscriptionId>, Option<i64>)> {
+    let rtx = self.database.begin_read()?;
 
-  pub(crate) fn get_block_height(
-    &self,
-    blockhash: bitcoin::BlockHash,
-  ) -> Result<usize, bitcoincore_rpc::Error> {
-    Ok(self.client.get_block_header_info(&blockhash)?.height)
-  }
+    let content_type_and_number = rtx.open_table(CONTENT_TYPE_AND_INSCRIPTION_NUMBER)?;
+    let id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+    let id_to_satpoint = rtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
+    let number_to_id = rtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+    let rarity_and_number = rtx.open_table(RARITY_AND_INSCRIPTION_NUMBER)?;
+
+    let outputs = query
+      .outputs
+      .as_ref()
+      .map(|outputs| outputs.iter().copied().collect::<HashSet<OutPoint>>());
+
+    // the part of `range` at or after `from` in the query's order
+    let bound = |range: Range<i64>| {
+      let range = match from {
+        Some(from) if query.ascending => range.start.max(from)..range.end,
+        Some(from) => range.start..range.end.min(from + 1),
+        None => range,
+      };
+      range.start..range.end.max(range.start)
+    };
 
-  pub(crate) fn get_transaction_blockhash(&self, txid: Txid) -> Result<Option<BlockHash>> {
-    Ok(
-      self
-        .client
-        .get_raw_transaction_info(&txid, None)
-        .into_option()?
-        .and_then(|info| {
-          if info.in_active_chain.unwrap_or_default() {
-            info.blockhash
-          } else {
-            None
+    let numbers: Box<dyn DoubleEndedIterator<Item = Result<i64>>> =
+      if let Some(content_type) = &query.content_type {
+        let numbers = bound(i64::MIN..i64::MAX);
+        Box::new(
+          content_type_and_number
+            .range::<(&[u8], i64)>(
+              (content_type.as_bytes(), numbers.start)..(content_type.as_bytes(), numbers.end),
+            )?
+            .map(|result| Ok(result?.0.value().1)),
+        )
+      } else if let Some(rarity) = query.rarity {
+        let numbers = bound(i64::MIN..i64::MAX);
+        Box::new(
+          rarity_and_number
+            .range((rarity as u8, numbers.start)..(rarity as u8, numbers.end))?
+            .map(|result| Ok(result?.0.value().1)),
+        )
+      } else if let Some(outputs) = &query.outputs {
+        let mut numbers = Vec::new();
+        for outpoint in outputs {
+          for inscription_id in self.get_inscriptions_on_output(*outpoint)? {
+            if let Some(entry) = id_to_entry.get(&inscription_id.store())? {
+              numbers.push(InscriptionEntry::load(entry.value()).number);
+            }
           }
-        }),
-    )
-  }
+        }
+        numbers.sort();
+        Box::new(numbers.into_iter().map(Ok))
+      } else {
+        let (cursed, blessed) = Self::height_range_numbers(
+          &number_to_id,
+          &id_to_entry,
+          query.min_height,
+          query.max_height,
+        )?;
+
+        Box::new(
+          number_to_id
+            .range(bound(cursed))?
+            .chain(number_to_id.range(bound(blessed))?)
+            .map(|result| Ok(result?.0.value())),
+        )
+      };
 
-  pub(crate) fn is_transaction_in_active_chain(&self, txid: Txid) -> Result<bool> {
-    Ok(
-      self
-        .client
-        .get_raw_transaction_info(&txid, None)
-        .into_option()?
-        .and_then(|info| info.in_active_chain)
-        .unwrap_or(false),
-    )
-  }
+    let numbers = if query.ascending {
+      numbers
+    } else {
+      Box::new(numbers.rev())
+    };
 
-  pub(crate) fn find(
-    &self,
-    search_start: Sat,
-    search_end: Sat,
-    outpoints: &Vec<OutPoint>,
-    allow_bad_ranges: bool,
-  ) -> Result<Option<Vec<FindRangeOutput>>> {
-    self.require_sat_index("find")?;
+    let mut inscriptions = Vec::new();
 
-    let search_start = search_start.0;
-    let search_end = search_end.0;
+    for number in numbers {
+      let number = number?;
 
-    let rtx = self.begin_read()?;
+      if let Some(from) = from {
+        if (query.ascending && number < from) || (!query.ascending && number > from) {
+          continue;
+        }
+      }
 
-    if !allow_bad_ranges && rtx.block_count()? <= Sat(search_end - 1).height().n() {
-      return Ok(None);
+      let inscription_id = *number_to_id
+        .get(number)?
+        .ok_or_else(|| anyhow!("index has no inscription number {number}"))?
+        .value();
+
+      let entry = InscriptionEntry::load(
+        id_to_entry
+          .get(&inscription_id)?
+          .ok_or_else(|| anyhow!("index has no entry for inscription number {number}"))?
+          .value(),
+      );
+
+      if !query.matches(&entry) {
+        continue;
+      }
+
+      if let Some(outputs) = &outputs {
+        let satpoint = SatPoint::load(
+          *id_to_satpoint
+            .get(&inscription_id)?
+            .ok_or_else(|| anyhow!("index has no satpoint for inscription number {number}"))?
+            .value(),
+        );
+
+        if !outputs.contains(&satpoint.outpoint) {
+          continue;
+        }
+      }
+
+      if inscriptions.len() == n {
+        return Ok((inscriptions, Some(number)));
+      }
+
+      inscriptions.push(InscriptionId::load(inscription_id));
+    }
+
+    Ok((inscriptions, None))
+  }
+
+  /// The cursed and blessed numbers of inscriptions created between
+  /// `min_height` and `max_height`. Inscriptions are numbered in block order,
+  /// blessed inscriptions counting up from zero and cursed inscriptions down
+  /// from -1, so each is a contiguous range that can be found by bisection.
+  fn height_range_numbers(
+    number_to_id: &impl ReadableTable<i64, &'static InscriptionIdValue>,
+    id_to_entry: &impl ReadableTable<&'static InscriptionIdValue, InscriptionEntryValue>,
+    min_height: Option<u64>,
+    max_height: Option<u64>,
+  ) -> Result<(Range<i64>, Range<i64>)> {
+    let height = |number: i64| -> Result<u64> {
+      let inscription_id = number_to_id
+        .get(number)?
+        .ok_or_else(|| anyhow!("index has no inscription number {number}"))?;
+
+      Ok(
+        InscriptionEntry::load(
+          id_to_entry
+            .get(inscription_id.value())?
+            .ok_or_else(|| anyhow!("index has no entry for inscription number {number}"))?
+            .value(),
+        )
+        .height,
+      )
+    };
+
+    let partition_point = |count: i64, number: fn(i64) -> i64, after: &dyn Fn(u64) -> bool| {
+      let (mut lo, mut hi) = (0, count);
+      while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        if after(height(number(mid))?) {
+          hi = mid;
+        } else {
+          lo = mid + 1;
+        }
+      }
+      Ok::<i64, Error>(lo)
+    };
+
+    let above_min = |height: u64| min_height.map_or(true, |min_height| height >= min_height);
+    let above_max = |height: u64| max_height.map_or(false, |max_height| height > max_height);
+
+    let cursed_count = match number_to_id.range(..0)?.next() {
+      Some(result) => -result?.0.value(),
+      None => 0,
+    };
+
+    let blessed_count = match number_to_id.range(0..)?.next_back() {
+      Some(result) => result?.0.value() + 1,
+      None => 0,
+    };
+
+    let cursed = |k: i64| -k - 1;
+    let blessed = |k: i64| k;
+
+    Ok((
+      -partition_point(cursed_count, cursed, &above_max)?
+        ..-partition_point(cursed_count, cursed, &above_min)?,
+      partition_point(blessed_count, blessed, &above_min)?
+        ..partition_point(blessed_count, blessed, &above_max)?,
+    ))
+  }
+
+  pub(crate) fn get_address_outputs(
+    &self,
+    script_pubkey: &Script,
+  ) -> Result<Option<Vec<(OutPoint, u64)>>> {
+    if !self.has_address_index()? {
+      return Ok(None);
+    }
+
+    let rtx = self.database.begin_read()?;
+    let outpoint_to_tx_out = rtx.open_table(OUTPOINT_TO_TX_OUT)?;
+
+    let mut outputs = Vec::new();
+
+    for outpoint in rtx
+      .open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT)?
+      .get(script_pubkey.as_bytes())?
+    {
+      let outpoint = OutPoint::load(*outpoint?.value());
+
+      let tx_out: TxOut = consensus::encode::deserialize(
+        outpoint_to_tx_out
+          .get(&outpoint.store())?
+          .ok_or_else(|| anyhow!("index has no output for {outpoint}"))?
+          .value(),
+      )?;
+
+      outputs.push((outpoint, tx_out.value));
+    }
+
+    Ok(Some(outputs))
+  }
+
+  pub(crate) fn get_outpoint_value(&self, outpoint: &OutPoint) -> Result<u64> {
+    Ok(self.get_transaction(outpoint.txid)?.unwrap().output[outpoint.vout as usize].value)
+  }
+
+  pub(crate) fn get_transaction(&self, txid: Txid) -> Result<Option<Transaction>> {
+    if txid == self.genesis_block_coinbase_txid {
+      Ok(Some(self.genesis_block_coinbase_transaction.clone()))
+    } else {
+      self
+        .client()?
+        .get_raw_transaction(&txid, None)
+        .into_option()
+    }
+  }
+
+  /// The script pubkey of unspent output `outpoint`, if the index was built
+  /// with `--index-utxos` or `--index-addresses`.
+  pub(crate) fn get_indexed_script_pubkey(&self, outpoint: OutPoint) -> Result<Option<ScriptBuf>> {
+    Ok(
+      self
+        .get_indexed_tx_out(outpoint)?
+        .map(|tx_out| tx_out.script_pubkey),
+    )
+  }
+
+  /// The script pubkey of `outpoint`, from the index if possible, and
+  /// otherwise from its transaction.
+  pub(crate) fn get_script_pubkey(&self, outpoint: OutPoint) -> Result<Option<ScriptBuf>> {
+    Ok(
+      self
+        .get_tx_out(outpoint)?
+        .map(|tx_out| tx_out.script_pubkey),
+    )
+  }
+
+  /// Unspent output `outpoint`, if the index was built with `--index-utxos`
+  /// or `--index-addresses`.
+  pub(crate) fn get_indexed_tx_out(&self, outpoint: OutPoint) -> Result<Option<TxOut>> {
+    if !self.has_tx_out_index()? {
+      return Ok(None);
+    }
+
+    self
+      .database
+      .begin_read()?
+      .open_table(OUTPOINT_TO_TX_OUT)?
+      .get(&outpoint.store())?
+      .map(|tx_out| Ok(consensus::encode::deserialize(tx_out.value())?))
+      .transpose()
+  }
+
+  /// Output `outpoint`, from the index if possible, and otherwise from its
+  /// transaction.
+  pub(crate) fn get_tx_out(&self, outpoint: OutPoint) -> Result<Option<TxOut>> {
+    if let Some(tx_out) = self.get_indexed_tx_out(outpoint)? {
+      return Ok(Some(tx_out));
+    }
+
+    Ok(
+      self
+        .get_transaction(outpoint.txid)?
+        .and_then(|tx| tx.output.into_iter().nth(outpoint.vout.try_into().unwrap())),
+    )
+  }
+
+  pub(crate) fn get_transaction_info(&self, txid: Txid) -> Result<GetRawTransactionResult> {
+    Ok(self.client()?.get_raw_transaction_info(&txid, None)?)
+  }
+
+  pub(crate) fn get_block_height(&self, blockhash: bitcoin::BlockHash) -> Result<usize> {
+    Ok(self.client()?.get_block_header_info(&blockhash)?.height)
+  }
+
+  pub(crate) fn get_transaction_blockhash(&self, txid: Txid) -> Result<Option<BlockHash>> {
+    Ok(
+      self
+        .client()?
+        .get_raw_transaction_info(&txid, None)
+        .into_option()?
+        .and_then(|info| {
+          if info.in_active_chain.unwrap_or_default() {
+            info.blockhash
+          } else {
+            None
+          }
+        }),
+    )
+  }
+
+  pub(crate) fn is_transaction_in_active_chain(&self, txid: Txid) -> Result<bool> {
+    Ok(
+      self
+        .client()?
+        .get_raw_transaction_info(&txid, None)
+        .into_option()?
+        .and_then(|info| info.in_active_chain)
+        .unwrap_or(false),
+    )
+  }
+
+  pub(crate) fn find(
+    &self,
+    search_start: Sat,
+    search_end: Sat,
+    outpoints: &Vec<OutPoint>,
+    allow_bad_ranges: bool,
+  ) -> Result<Option<Vec<FindRangeOutput>>> {
+    self.require_sat_index("find")?;
+
+    let search_start = search_start.0;
+    let search_end = search_end.0;
+
+    let rtx = self.begin_read()?;
+
+    if !allow_bad_ranges && rtx.block_count()? <= Sat(search_end - 1).height().n() {
+      return Ok(None);
     }
 
     let mut result = Vec::new();
@@ -1278,6 +1989,40 @@ impl Index {
     )
   }
 
+  pub(crate) fn get_inscriptions_by_content_hash(
+    &self,
+    content_hash: ContentHashValue,
+  ) -> Result<Vec<(InscriptionId, i64)>> {
+    let rtx = self.database.begin_read()?;
+
+    let inscription_id_to_entry = rtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
+
+    let mut inscriptions = Vec::new();
+    for result in rtx
+      .open_multimap_table(CONTENT_HASH_TO_INSCRIPTION_ID)?
+      .get(&content_hash)?
+    {
+      let id = result?;
+      let entry = InscriptionEntry::load(
+        inscription_id_to_entry
+          .get(id.value())?
+          .ok_or_else(|| anyhow!("index has no entry for content hash inscription"))?
+          .value(),
+      );
+      inscriptions.push((InscriptionId::load(*id.value()), entry.height, entry.number));
+    }
+
+    // cursed inscription numbers count down within a block
+    inscriptions.sort_by_key(|(_id, height, number)| (*height, number.unsigned_abs()));
+
+    Ok(
+      inscriptions
+        .into_iter()
+        .map(|(id, _height, number)| (id, number))
+        .collect(),
+    )
+  }
+
   pub(crate) fn compact_db(&mut self) -> Result<bool, CompactionError> {
     self.database.compact()
   }
@@ -1560,7 +2305,10 @@ impl Index {
 mod tests {
   use {
     super::*,
-    bitcoin::secp256k1::rand::{self, RngCore},
+    bitcoin::{
+      hashes::sha256,
+      secp256k1::rand::{self, RngCore},
+    },
   };
 
   struct ContextBuilder {
@@ -1761,125 +2509,517 @@ mod tests {
   }
 
   #[test]
-  fn list_split_ranges_are_tracked_correctly() {
-    let context = Context::builder().arg("--index-sats").build();
+  fn address_outputs_are_tracked() {
+    let context = Context::builder().arg("--index-addresses").build();
 
-    context.mine_blocks(1);
-    let split_coinbase_output = TransactionTemplate {
+    let coinbase = context.mine_blocks(1)[0].txdata[0].txid();
+
+    assert_eq!(
+      context
+        .index
+        .get_address_outputs(&ScriptBuf::new())
+        .unwrap()
+        .unwrap(),
+      [(OutPoint::new(coinbase, 0), 50 * COIN_VALUE)]
+    );
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
       inputs: &[(1, 0, 0)],
       outputs: 2,
       fee: 0,
       ..Default::default()
-    };
-    let txid = context.rpc_server.broadcast_tx(split_coinbase_output);
-
+    });
     context.mine_blocks(1);
 
-    assert_eq!(
-      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
-      List::Unspent(vec![(50 * COIN_VALUE, 75 * COIN_VALUE)])
-    );
+    let outputs = context
+      .index
+      .get_address_outputs(&ScriptBuf::new())
+      .unwrap()
+      .unwrap();
+
+    assert_eq!(outputs.len(), 3);
+    assert!(outputs.contains(&(OutPoint::new(txid, 0), 25 * COIN_VALUE)));
+    assert!(outputs.contains(&(OutPoint::new(txid, 1), 25 * COIN_VALUE)));
+    assert!(!outputs
+      .iter()
+      .any(|(outpoint, _value)| *outpoint == OutPoint::new(coinbase, 0)));
+  }
 
+  #[test]
+  fn address_outputs_require_address_index() {
+    let context = Context::builder().build();
     assert_eq!(
-      context.index.list(OutPoint::new(txid, 1)).unwrap().unwrap(),
-      List::Unspent(vec![(75 * COIN_VALUE, 100 * COIN_VALUE)])
+      context
+        .index
+        .get_address_outputs(&ScriptBuf::new())
+        .unwrap(),
+      None
     );
   }
 
   #[test]
-  fn list_merge_ranges_are_tracked_correctly() {
-    let context = Context::builder().arg("--index-sats").build();
-
-    context.mine_blocks(2);
-    let merge_coinbase_outputs = TransactionTemplate {
-      inputs: &[(1, 0, 0), (2, 0, 0)],
-      fee: 0,
-      ..Default::default()
-    };
+  fn tx_outs_are_read_from_address_index() {
+    let context = Context::builder().arg("--index-addresses").build();
 
-    let txid = context.rpc_server.broadcast_tx(merge_coinbase_outputs);
-    context.mine_blocks(1);
+    let coinbase = context.mine_blocks(1)[0].txdata[0].clone();
+    let outpoint = OutPoint::new(coinbase.txid(), 0);
 
     assert_eq!(
-      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
-      List::Unspent(vec![
-        (50 * COIN_VALUE, 100 * COIN_VALUE),
-        (100 * COIN_VALUE, 150 * COIN_VALUE)
-      ]),
+      context.index.get_indexed_tx_out(outpoint).unwrap(),
+      Some(coinbase.output[0].clone())
     );
-  }
 
-  #[test]
-  fn list_fee_paying_transaction_range() {
-    let context = Context::builder().arg("--index-sats").build();
-
-    context.mine_blocks(1);
-    let fee_paying_tx = TransactionTemplate {
+    context.rpc_server.broadcast_tx(TransactionTemplate {
       inputs: &[(1, 0, 0)],
-      outputs: 2,
-      fee: 10,
       ..Default::default()
-    };
-    let txid = context.rpc_server.broadcast_tx(fee_paying_tx);
-    let coinbase_txid = context.mine_blocks(1)[0].txdata[0].txid();
+    });
+    context.mine_blocks(1);
 
+    assert_eq!(context.index.get_indexed_tx_out(outpoint).unwrap(), None);
     assert_eq!(
-      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
-      List::Unspent(vec![(50 * COIN_VALUE, 7499999995)]),
+      context.index.get_tx_out(outpoint).unwrap(),
+      Some(coinbase.output[0].clone())
     );
+  }
 
-    assert_eq!(
-      context.index.list(OutPoint::new(txid, 1)).unwrap().unwrap(),
-      List::Unspent(vec![(7499999995, 9999999990)]),
-    );
+  #[test]
+  fn tx_outs_require_address_index() {
+    let context = Context::builder().build();
 
+    let coinbase = context.mine_blocks(1)[0].txdata[0].clone();
+    let outpoint = OutPoint::new(coinbase.txid(), 0);
+
+    assert_eq!(context.index.get_indexed_tx_out(outpoint).unwrap(), None);
     assert_eq!(
-      context
-        .index
-        .list(OutPoint::new(coinbase_txid, 0))
-        .unwrap()
-        .unwrap(),
-      List::Unspent(vec![(10000000000, 15000000000), (9999999990, 10000000000)])
+      context.index.get_tx_out(outpoint).unwrap(),
+      Some(coinbase.output[0].clone())
     );
   }
 
   #[test]
-  fn list_two_fee_paying_transaction_range() {
-    let context = Context::builder().arg("--index-sats").build();
+  fn content_index_deduplicates_bodies() {
+    let context = Context::builder().arg("--index-content").build();
 
     context.mine_blocks(2);
-    let first_fee_paying_tx = TransactionTemplate {
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
       inputs: &[(1, 0, 0)],
-      fee: 10,
+      witness: inscription("text/plain", "hello").to_witness(),
       ..Default::default()
-    };
-    let second_fee_paying_tx = TransactionTemplate {
+    });
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
       inputs: &[(2, 0, 0)],
-      fee: 10,
+      witness: inscription("text/html", "hello").to_witness(),
       ..Default::default()
-    };
-    context.rpc_server.broadcast_tx(first_fee_paying_tx);
-    context.rpc_server.broadcast_tx(second_fee_paying_tx);
+    });
 
-    let coinbase_txid = context.mine_blocks(1)[0].txdata[0].txid();
+    context.mine_blocks(1);
 
     assert_eq!(
       context
         .index
-        .list(OutPoint::new(coinbase_txid, 0))
+        .get_inscription_by_id(first.into())
         .unwrap()
         .unwrap(),
-      List::Unspent(vec![
-        (15000000000, 20000000000),
-        (9999999990, 10000000000),
-        (14999999990, 15000000000)
-      ])
+      inscription("text/plain", "hello")
     );
-  }
-
-  #[test]
-  fn list_null_output() {
-    let context = Context::builder().arg("--index-sats").build();
+
+    assert_eq!(
+      context
+        .index
+        .get_inscription_by_id(second.into())
+        .unwrap()
+        .unwrap(),
+      inscription("text/html", "hello")
+    );
+
+    assert_eq!(
+      context
+        .index
+        .database
+        .begin_read()
+        .unwrap()
+        .open_table(CONTENT_HASH_TO_BODY)
+        .unwrap()
+        .len()
+        .unwrap(),
+      1
+    );
+  }
+
+  #[test]
+  fn content_hashes_are_read_from_content_index() {
+    for (args, stored) in [(&["--index-content"][..], true), (&[][..], false)] {
+      let context = Context::builder().args(args).build();
+
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+
+      context.mine_blocks(1);
+
+      assert_eq!(
+        context.index.get_content_hash(txid.into()).unwrap(),
+        stored.then(|| sha256::Hash::hash(b"hello"))
+      );
+    }
+  }
+
+  #[test]
+  fn inscriptions_are_looked_up_by_content_hash() {
+    let context = Context::builder().build();
+
+    context.mine_blocks(3);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0)],
+      witness: inscription("text/html", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0)],
+      witness: inscription("text/plain", "goodbye").to_witness(),
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_by_content_hash(sha256::Hash::hash(b"hello").to_byte_array())
+        .unwrap(),
+      vec![(first.into(), 0), (second.into(), 1)]
+    );
+
+    assert_eq!(
+      context
+        .index
+        .get_inscriptions_by_content_hash(sha256::Hash::hash(b"foo").to_byte_array())
+        .unwrap(),
+      Vec::new()
+    );
+  }
+
+  #[test]
+  fn query_inscriptions() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(3);
+
+    let mut inscriptions = Vec::new();
+    for (input, content_type) in [(1, "text/plain"), (2, "image/png"), (3, "text/plain")] {
+      inscriptions.push(InscriptionId::from(context.rpc_server.broadcast_tx(
+        TransactionTemplate {
+          inputs: &[(input, 0, 0)],
+          witness: inscription(content_type, "hello").to_witness(),
+          ..Default::default()
+        },
+      )));
+      context.mine_blocks(1);
+    }
+
+    let query = |query: InscriptionQuery, from: Option<i64>, n: usize| {
+      context.index.query_inscriptions(&query, from, n).unwrap()
+    };
+
+    assert_eq!(
+      query(InscriptionQuery::default(), None, 10),
+      (
+        vec![inscriptions[2], inscriptions[1], inscriptions[0]],
+        None
+      )
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          content_type: Some("text/plain".into()),
+          ..Default::default()
+        },
+        None,
+        10
+      ),
+      (vec![inscriptions[2], inscriptions[0]], None)
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          ascending: true,
+          content_type: Some("text/plain".into()),
+          ..Default::default()
+        },
+        Some(1),
+        10
+      ),
+      (vec![inscriptions[2]], None)
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          ascending: true,
+          min_height: Some(5),
+          ..Default::default()
+        },
+        None,
+        10
+      ),
+      (vec![inscriptions[1], inscriptions[2]], None)
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          rarity: Some(Rarity::Uncommon),
+          cursed: Some(false),
+          ..Default::default()
+        },
+        None,
+        1
+      ),
+      (vec![inscriptions[2]], Some(1))
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          rarity: Some(Rarity::Uncommon),
+          ..Default::default()
+        },
+        Some(1),
+        1
+      ),
+      (vec![inscriptions[1]], Some(0))
+    );
+
+    assert_eq!(
+      query(
+        InscriptionQuery {
+          content_type: Some("text/html".into()),
+          ..Default::default()
+        },
+        None,
+        10
+      ),
+      (Vec::new(), None)
+    );
+  }
+
+  #[test]
+  fn query_inscriptions_by_height() {
+    let context = Context::builder().build();
+
+    context.mine_blocks(4);
+
+    let witness = envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"bar"]);
+
+    let first = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0), (2, 0, 0)],
+      witness: witness.clone(),
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+
+    let second = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(3, 0, 0), (4, 0, 0)],
+      witness: witness.clone(),
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+
+    let third = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(5, 0, 0)],
+      witness,
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+
+    let id = |txid: Txid, index: u32| InscriptionId { txid, index };
+
+    let query = |ascending: bool,
+                 min_height: Option<u64>,
+                 max_height: Option<u64>,
+                 from: Option<i64>,
+                 n: usize| {
+      context
+        .index
+        .query_inscriptions(
+          &InscriptionQuery {
+            ascending,
+            min_height,
+            max_height,
+            ..Default::default()
+          },
+          from,
+          n,
+        )
+        .unwrap()
+    };
+
+    assert_eq!(
+      query(false, Some(6), Some(6), None, 10),
+      (vec![id(second, 0), id(second, 1)], None)
+    );
+
+    assert_eq!(
+      query(true, Some(6), None, None, 10),
+      (vec![id(second, 1), id(second, 0), id(third, 0)], None)
+    );
+
+    assert_eq!(
+      query(false, None, Some(5), None, 10),
+      (vec![id(first, 0), id(first, 1)], None)
+    );
+
+    assert_eq!(
+      query(false, Some(5), None, Some(1), 1),
+      (vec![id(second, 0)], Some(0))
+    );
+
+    assert_eq!(
+      query(true, None, Some(6), Some(-1), 2),
+      (vec![id(first, 1), id(first, 0)], Some(1))
+    );
+
+    assert_eq!(query(false, Some(8), None, None, 10), (Vec::new(), None));
+  }
+
+  #[test]
+  fn list_split_ranges_are_tracked_correctly() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(1);
+    let split_coinbase_output = TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      fee: 0,
+      ..Default::default()
+    };
+    let txid = context.rpc_server.broadcast_tx(split_coinbase_output);
+
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
+      List::Unspent(vec![(50 * COIN_VALUE, 75 * COIN_VALUE)])
+    );
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 1)).unwrap().unwrap(),
+      List::Unspent(vec![(75 * COIN_VALUE, 100 * COIN_VALUE)])
+    );
+  }
+
+  #[test]
+  fn list_merge_ranges_are_tracked_correctly() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(2);
+    let merge_coinbase_outputs = TransactionTemplate {
+      inputs: &[(1, 0, 0), (2, 0, 0)],
+      fee: 0,
+      ..Default::default()
+    };
+
+    let txid = context.rpc_server.broadcast_tx(merge_coinbase_outputs);
+    context.mine_blocks(1);
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
+      List::Unspent(vec![
+        (50 * COIN_VALUE, 100 * COIN_VALUE),
+        (100 * COIN_VALUE, 150 * COIN_VALUE)
+      ]),
+    );
+  }
+
+  #[test]
+  fn list_fee_paying_transaction_range() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(1);
+    let fee_paying_tx = TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      fee: 10,
+      ..Default::default()
+    };
+    let txid = context.rpc_server.broadcast_tx(fee_paying_tx);
+    let coinbase_txid = context.mine_blocks(1)[0].txdata[0].txid();
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 0)).unwrap().unwrap(),
+      List::Unspent(vec![(50 * COIN_VALUE, 7499999995)]),
+    );
+
+    assert_eq!(
+      context.index.list(OutPoint::new(txid, 1)).unwrap().unwrap(),
+      List::Unspent(vec![(7499999995, 9999999990)]),
+    );
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(coinbase_txid, 0))
+        .unwrap()
+        .unwrap(),
+      List::Unspent(vec![(10000000000, 15000000000), (9999999990, 10000000000)])
+    );
+  }
+
+  #[test]
+  fn list_two_fee_paying_transaction_range() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(2);
+    let first_fee_paying_tx = TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      fee: 10,
+      ..Default::default()
+    };
+    let second_fee_paying_tx = TransactionTemplate {
+      inputs: &[(2, 0, 0)],
+      fee: 10,
+      ..Default::default()
+    };
+    context.rpc_server.broadcast_tx(first_fee_paying_tx);
+    context.rpc_server.broadcast_tx(second_fee_paying_tx);
+
+    let coinbase_txid = context.mine_blocks(1)[0].txdata[0].txid();
+
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint::new(coinbase_txid, 0))
+        .unwrap()
+        .unwrap(),
+      List::Unspent(vec![
+        (15000000000, 20000000000),
+        (9999999990, 10000000000),
+        (14999999990, 15000000000)
+      ])
+    );
+  }
+
+  #[test]
+  fn list_null_output() {
+    let context = Context::builder().arg("--index-sats").build();
 
     context.mine_blocks(1);
     let no_value_output = TransactionTemplate {
@@ -2430,105 +3570,461 @@ mod tests {
     }
   }
 
-  #[test]
-  fn inscription_can_be_fee_spent_in_first_transaction() {
-    for context in Context::configurations() {
-      context.mine_blocks(1);
+  #[test]
+  fn inscription_can_be_fee_spent_in_first_transaction() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        fee: 50 * COIN_VALUE,
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId::from(txid);
+
+      let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+
+      context.index.assert_inscription_location(
+        inscription_id,
+        SatPoint {
+          outpoint: OutPoint {
+            txid: coinbase_tx,
+            vout: 0,
+          },
+          offset: 50 * COIN_VALUE,
+        },
+        Some(50 * COIN_VALUE),
+      );
+    }
+  }
+
+  #[test]
+  fn lost_inscriptions() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        fee: 50 * COIN_VALUE,
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+      let inscription_id = InscriptionId::from(txid);
+
+      context.mine_blocks_with_subsidy(1, 0);
+
+      context.index.assert_inscription_location(
+        inscription_id,
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: 0,
+        },
+        Some(50 * COIN_VALUE),
+      );
+    }
+  }
+
+  #[test]
+  fn multiple_inscriptions_can_be_lost() {
+    for context in Context::configurations() {
+      context.mine_blocks(1);
+
+      let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(1, 0, 0)],
+        fee: 50 * COIN_VALUE,
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+      let first_inscription_id = InscriptionId::from(first_txid);
+
+      context.mine_blocks_with_subsidy(1, 0);
+      context.mine_blocks(1);
+
+      let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+        inputs: &[(3, 0, 0)],
+        fee: 50 * COIN_VALUE,
+        witness: inscription("text/plain", "hello").to_witness(),
+        ..Default::default()
+      });
+      let second_inscription_id = InscriptionId::from(second_txid);
+
+      context.mine_blocks_with_subsidy(1, 0);
+
+      context.index.assert_inscription_location(
+        first_inscription_id,
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: 0,
+        },
+        Some(50 * COIN_VALUE),
+      );
+
+      context.index.assert_inscription_location(
+        second_inscription_id,
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: 50 * COIN_VALUE,
+        },
+        Some(150 * COIN_VALUE),
+      );
+    }
+  }
+
+  #[test]
+  fn lost_sat_ranges_record_height_and_cause() {
+    let context = Context::builder().arg("--index-sats").build();
+
+    context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 0);
+    context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 0);
+
+    assert_eq!(
+      context.index.lost_sat_ranges().unwrap().unwrap(),
+      [
+        (
+          0,
+          (100 * COIN_VALUE, 150 * COIN_VALUE),
+          Height(2),
+          LostCause::Unclaimed
+        ),
+        (
+          50 * COIN_VALUE,
+          (200 * COIN_VALUE, 250 * COIN_VALUE),
+          Height(4),
+          LostCause::Unclaimed
+        ),
+      ]
+    );
+  }
+
+  #[test]
+  fn lost_sat_ranges_require_sat_index() {
+    let context = Context::builder().build();
+
+    context.mine_blocks_with_subsidy(1, 0);
+
+    assert_eq!(context.index.lost_sat_ranges().unwrap(), None);
+  }
+
+  #[test]
+  fn duplicate_coinbase_txids_lose_earlier_sats() {
+    let coinbase = Transaction {
+      version: 1,
+      lock_time: bitcoin::blockdata::locktime::absolute::LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint::null(),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: Witness::new(),
+      }],
+      output: vec![TxOut {
+        value: 50 * COIN_VALUE,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+
+    for _ in 0..2 {
+      blocks.push(Block {
+        header: Header {
+          prev_blockhash: blocks.last().unwrap().block_hash(),
+          ..blocks[0].header
+        },
+        txdata: vec![coinbase.clone()],
+      });
+    }
+
+    // the duplicate is either still in the updater's cache, or has already
+    // been committed when the second block is indexed
+    for first_update in [2, 3] {
+      let context = Context::builder().arg("--index-sats").build();
+
+      for len in [first_update, 3] {
+        let memory = block_source::Memory {
+          blocks: blocks[..len].to_vec(),
+        };
+
+        context
+          .index
+          .update_from(|| Ok(Box::new(memory.clone())))
+          .unwrap();
+      }
+
+      assert_eq!(context.index.block_count().unwrap(), 3);
+
+      assert_eq!(
+        context.index.lost_sat_ranges().unwrap().unwrap(),
+        [(
+          0,
+          (50 * COIN_VALUE, 100 * COIN_VALUE),
+          Height(2),
+          LostCause::DuplicateTxid
+        )]
+      );
+
+      assert_eq!(
+        context
+          .index
+          .list(OutPoint {
+            txid: coinbase.txid(),
+            vout: 0,
+          })
+          .unwrap(),
+        Some(List::Unspent(vec![(100 * COIN_VALUE, 150 * COIN_VALUE)]))
+      );
+    }
+  }
+
+  #[test]
+  fn blocks_are_indexed_from_a_file_without_rpc() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks_file = tempdir.path().join("blocks.dat");
+
+    let block = |parent: &Block, tag: u8, txdata: Vec<Transaction>| {
+      let mut block = Block {
+        header: Header {
+          prev_blockhash: parent.block_hash(),
+          ..parent.header
+        },
+        txdata: iter::once(Transaction {
+          version: 1,
+          lock_time: bitcoin::blockdata::locktime::absolute::LockTime::ZERO,
+          input: vec![TxIn {
+            previous_output: OutPoint::null(),
+            script_sig: ScriptBuf::from(vec![tag]),
+            sequence: Sequence::MAX,
+            witness: Witness::new(),
+          }],
+          output: vec![TxOut {
+            value: 50 * COIN_VALUE,
+            script_pubkey: ScriptBuf::new(),
+          }],
+        })
+        .chain(txdata)
+        .collect(),
+      };
+
+      block.header.merkle_root = block.compute_merkle_root().unwrap();
+      block
+    };
+
+    let write_blocks = |blocks: &[Block]| {
+      fs::write(
+        &blocks_file,
+        blocks
+          .iter()
+          .flat_map(consensus::serialize)
+          .collect::<Vec<u8>>(),
+      )
+      .unwrap();
+    };
+
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+    blocks.push(block(&blocks[0], 1, Vec::new()));
+
+    let reveal = Transaction {
+      version: 1,
+      lock_time: bitcoin::blockdata::locktime::absolute::LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: blocks[1].txdata[0].txid(),
+          vout: 0,
+        },
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      }],
+      output: vec![TxOut {
+        value: 50 * COIN_VALUE,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+    let id = InscriptionId {
+      txid: reveal.txid(),
+      index: 0,
+    };
+
+    blocks.push(block(&blocks[1], 2, vec![reveal.clone()]));
+    blocks.push(block(&blocks[2], 3, Vec::new()));
+
+    write_blocks(&blocks[..1]);
+
+    // nothing listens on port 1, so any RPC request fails
+    let options = Options::try_parse_from([
+      "ord".into(),
+      "--rpc-url".into(),
+      "127.0.0.1:1".into(),
+      "--data-dir".into(),
+      OsString::from(tempdir.path()),
+      "--regtest".into(),
+      "--block-source".into(),
+      "file".into(),
+      "--blocks-file".into(),
+      blocks_file.clone().into(),
+    ])
+    .unwrap();
+
+    let index = Index::open(&options).unwrap();
+    index.update().unwrap();
+
+    write_blocks(&blocks);
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), 4);
+
+    index.assert_inscription_location(
+      id,
+      SatPoint {
+        outpoint: OutPoint {
+          txid: reveal.txid(),
+          vout: 0,
+        },
+        offset: 0,
+      },
+      None,
+    );
+
+    // replace the block with the inscription, so that the reorg is detected
+    // and recovered from using the file's block hashes
+    blocks.truncate(2);
+    blocks.push(block(&blocks[1], 4, Vec::new()));
+    blocks.push(block(&blocks[2], 5, Vec::new()));
+    blocks.push(block(&blocks[3], 6, Vec::new()));
+
+    write_blocks(&blocks);
+
+    index.update().unwrap();
+
+    assert_eq!(index.block_count().unwrap(), 5);
+    assert_eq!(
+      index.block_hash(Some(2)).unwrap(),
+      Some(blocks[2].block_hash())
+    );
+    index.assert_non_existence_of_inscription(id);
+  }
+
+  #[test]
+  fn script_pubkeys_of_unspent_outputs_are_indexed() {
+    let context = Context::builder().arg("--index-utxos").build();
+
+    let genesis = OutPoint {
+      txid: context.index.options.chain().genesis_block().txdata[0].txid(),
+      vout: 0,
+    };
+
+    assert_eq!(
+      context.index.get_indexed_script_pubkey(genesis).unwrap(),
+      Some(
+        context.index.options.chain().genesis_block().txdata[0].output[0]
+          .script_pubkey
+          .clone()
+      )
+    );
+
+    context.mine_blocks(1);
+
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      ..Default::default()
+    });
+
+    context.mine_blocks(1);
 
-      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0)],
-        fee: 50 * COIN_VALUE,
-        witness: inscription("text/plain", "hello").to_witness(),
-        ..Default::default()
-      });
-      let inscription_id = InscriptionId::from(txid);
+    let coinbase = OutPoint {
+      txid: context.rpc_server.tx(1, 0).txid(),
+      vout: 0,
+    };
 
-      let coinbase_tx = context.mine_blocks(1)[0].txdata[0].txid();
+    assert_eq!(
+      context.index.get_indexed_script_pubkey(coinbase).unwrap(),
+      None
+    );
 
-      context.index.assert_inscription_location(
-        inscription_id,
-        SatPoint {
-          outpoint: OutPoint {
-            txid: coinbase_tx,
-            vout: 0,
-          },
-          offset: 50 * COIN_VALUE,
-        },
-        Some(50 * COIN_VALUE),
-      );
-    }
+    assert_eq!(
+      context
+        .index
+        .get_indexed_script_pubkey(OutPoint { txid, vout: 0 })
+        .unwrap(),
+      Some(ScriptBuf::new())
+    );
   }
 
   #[test]
-  fn lost_inscriptions() {
-    for context in Context::configurations() {
-      context.mine_blocks(1);
+  fn script_pubkeys_require_utxo_index() {
+    let context = Context::builder().arg("--index-sats").build();
 
-      let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0)],
-        fee: 50 * COIN_VALUE,
-        witness: inscription("text/plain", "hello").to_witness(),
-        ..Default::default()
-      });
-      let inscription_id = InscriptionId::from(txid);
+    context.mine_blocks(1);
 
-      context.mine_blocks_with_subsidy(1, 0);
+    let outpoint = OutPoint {
+      txid: context.rpc_server.tx(1, 0).txid(),
+      vout: 0,
+    };
 
-      context.index.assert_inscription_location(
-        inscription_id,
-        SatPoint {
-          outpoint: OutPoint::null(),
-          offset: 0,
-        },
-        Some(50 * COIN_VALUE),
-      );
-    }
+    assert_eq!(
+      context.index.get_indexed_script_pubkey(outpoint).unwrap(),
+      None
+    );
+    assert_eq!(
+      context.index.get_script_pubkey(outpoint).unwrap(),
+      Some(ScriptBuf::new())
+    );
   }
 
   #[test]
-  fn multiple_inscriptions_can_be_lost() {
-    for context in Context::configurations() {
-      context.mine_blocks(1);
+  fn blocks_are_read_from_blk_files() {
+    let bitcoin_data_dir = TempDir::new().unwrap();
+    let blocks_dir = bitcoin_data_dir.path().join("regtest/blocks");
+    fs::create_dir_all(&blocks_dir).unwrap();
+
+    let context = Context::builder()
+      .arg("--bitcoin-data-dir")
+      .arg(bitcoin_data_dir.path())
+      .args(["--block-source", "blk-files", "--index-sats"])
+      .build();
+
+    let getblock_calls = |n| {
+      assert!(context
+        .index
+        .render_metrics()
+        .unwrap()
+        .contains(&format!("ord_rpc_calls_total{{method=\"getblock\"}} {n}\n")));
+    };
 
-      let first_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-        inputs: &[(1, 0, 0)],
-        fee: 50 * COIN_VALUE,
-        witness: inscription("text/plain", "hello").to_witness(),
-        ..Default::default()
-      });
-      let first_inscription_id = InscriptionId::from(first_txid);
+    // genesis isn't in a block file yet, so it was fetched over RPC
+    getblock_calls(1);
 
-      context.mine_blocks_with_subsidy(1, 0);
-      context.mine_blocks(1);
+    let blocks = context.rpc_server.mine_blocks(2);
 
-      let second_txid = context.rpc_server.broadcast_tx(TransactionTemplate {
-        inputs: &[(3, 0, 0)],
-        fee: 50 * COIN_VALUE,
-        witness: inscription("text/plain", "hello").to_witness(),
-        ..Default::default()
-      });
-      let second_inscription_id = InscriptionId::from(second_txid);
+    blk_files::write_blk_file(
+      &blocks_dir.join("blk00000.dat"),
+      Network::Regtest,
+      [0; 8],
+      &blocks,
+    );
 
-      context.mine_blocks_with_subsidy(1, 0);
+    context.mine_blocks(1);
 
-      context.index.assert_inscription_location(
-        first_inscription_id,
-        SatPoint {
-          outpoint: OutPoint::null(),
-          offset: 0,
-        },
-        Some(50 * COIN_VALUE),
-      );
+    assert_eq!(context.index.block_count().unwrap(), 4);
+    assert_eq!(
+      context.index.block_hash(Some(2)).unwrap(),
+      Some(blocks[1].block_hash())
+    );
+    assert_eq!(
+      context
+        .index
+        .list(OutPoint {
+          txid: blocks[1].txdata[0].txid(),
+          vout: 0,
+        })
+        .unwrap(),
+      Some(List::Unspent(vec![(100 * COIN_VALUE, 150 * COIN_VALUE)]))
+    );
 
-      context.index.assert_inscription_location(
-        second_inscription_id,
-        SatPoint {
-          outpoint: OutPoint::null(),
-          offset: 50 * COIN_VALUE,
-        },
-        Some(150 * COIN_VALUE),
-      );
-    }
+    // the last block isn't in a block file either
+    getblock_calls(2);
   }
 
   #[test]
@@ -2724,6 +4220,186 @@ mod tests {
     );
   }
 
+  #[test]
+  fn sats_with_trait_follow_their_ranges() {
+    let context = Context::builder().arg("--index-traits").build();
+
+    context.mine_blocks(1);
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      fee: 0,
+      ..Default::default()
+    });
+    let coinbase = context.mine_blocks(1)[0].txdata[0].txid();
+    context.mine_blocks_with_subsidy(1, 0);
+
+    let genesis = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+      .parse()
+      .unwrap();
+
+    let outputs = [
+      (genesis, 0, 50),
+      (OutPoint::new(txid, 0), 50, 75),
+      (OutPoint::new(txid, 1), 75, 100),
+      (OutPoint::new(coinbase, 0), 100, 150),
+    ];
+
+    let alphas = outputs
+      .iter()
+      .flat_map(|(outpoint, start, end)| {
+        (*start..*end).map(move |n| {
+          (
+            Sat(n * COIN_VALUE),
+            1,
+            SatPoint {
+              outpoint: *outpoint,
+              offset: (n - start) * COIN_VALUE,
+            },
+          )
+        })
+      })
+      .collect::<Vec<(Sat, u64, SatPoint)>>();
+
+    assert_eq!(alphas.len(), 150);
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Alpha, None, 200)
+        .unwrap()
+        .unwrap(),
+      (alphas.clone(), None)
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Alpha, None, 100)
+        .unwrap()
+        .unwrap(),
+      (alphas[..100].to_vec(), Some(Sat(100 * COIN_VALUE)))
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Alpha, Some(Sat(100 * COIN_VALUE)), 100)
+        .unwrap()
+        .unwrap(),
+      (alphas[100..].to_vec(), None)
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Alpha, Some(Sat(100 * COIN_VALUE - 1)), 1)
+        .unwrap()
+        .unwrap(),
+      (alphas[100..101].to_vec(), Some(Sat(101 * COIN_VALUE)))
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Vintage, None, 100)
+        .unwrap()
+        .unwrap(),
+      (
+        outputs
+          .iter()
+          .map(|(outpoint, start, end)| (
+            Sat(start * COIN_VALUE),
+            (end - start) * COIN_VALUE,
+            SatPoint {
+              outpoint: *outpoint,
+              offset: 0,
+            }
+          ))
+          .collect(),
+        None
+      )
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sats_with_trait(SatTrait::Pizza, None, 100)
+        .unwrap()
+        .unwrap(),
+      (Vec::new(), None)
+    );
+  }
+
+  #[test]
+  fn sat_history_follows_sat_through_outputs() {
+    let context = Context::builder().arg("--index-sat-history").build();
+
+    let coinbase = context.mine_blocks(1)[0].txdata[0].txid();
+    let txid = context.rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      outputs: 2,
+      fee: 0,
+      ..Default::default()
+    });
+    context.mine_blocks(1);
+    context.mine_blocks_with_subsidy(1, 0);
+
+    assert_eq!(
+      context
+        .index
+        .sat_history(Sat(75 * COIN_VALUE + 1))
+        .unwrap()
+        .unwrap(),
+      [
+        (
+          Height(1),
+          SatPoint {
+            outpoint: OutPoint::new(coinbase, 0),
+            offset: 25 * COIN_VALUE + 1,
+          }
+        ),
+        (
+          Height(2),
+          SatPoint {
+            outpoint: OutPoint::new(txid, 1),
+            offset: 1,
+          }
+        ),
+      ]
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sat_history(Sat(150 * COIN_VALUE + 5))
+        .unwrap()
+        .unwrap(),
+      [(
+        Height(3),
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: 5,
+        }
+      )]
+    );
+
+    assert_eq!(
+      context
+        .index
+        .sat_history(Sat(200 * COIN_VALUE))
+        .unwrap()
+        .unwrap(),
+      []
+    );
+  }
+
+  #[test]
+  fn sat_history_requires_index() {
+    let context = Context::builder().arg("--index-sats").build();
+    assert_eq!(context.index.sat_history(Sat(0)).unwrap(), None);
+  }
+
   #[test]
   fn old_schema_gives_correct_error() {
     let tempdir = {
@@ -2751,6 +4427,67 @@ mod tests {
       format!("index at `{}{delimiter}regtest{delimiter}index.redb` appears to have been built with an older, incompatible version of ord, consider deleting and rebuilding the index: index schema 0, ord schema {SCHEMA_VERSION}", path.display()));
   }
 
+  #[test]
+  fn trait_ranges_are_fixed_when_index_is_created() {
+    let tempdir = TempDir::new().unwrap();
+    let config = tempdir.path().join("ord.yaml");
+    fs::write(
+      &config,
+      "hidden: []\ntrait_ranges:\n  pizza:\n  - [0, 100]\n",
+    )
+    .unwrap();
+
+    let tempdir = {
+      let context = Context::builder()
+        .tempdir(tempdir)
+        .arg("--index-traits")
+        .arg("--config")
+        .arg(&config)
+        .build();
+
+      assert_eq!(
+        context.index.trait_ranges().unwrap(),
+        Some(iter::once((SatTrait::Pizza, vec![(0, 100)])).collect())
+      );
+
+      assert_eq!(
+        context
+          .index
+          .sats_with_trait(SatTrait::Pizza, None, 10)
+          .unwrap()
+          .unwrap()
+          .0
+          .len(),
+        1
+      );
+
+      context.tempdir
+    };
+
+    fs::write(
+      &config,
+      "hidden: []\ntrait_ranges:\n  pizza:\n  - [0, 200]\n",
+    )
+    .unwrap();
+
+    let path = tempdir.path().to_owned();
+
+    let delimiter = if cfg!(windows) { '\\' } else { '/' };
+
+    assert_eq!(
+      Context::builder()
+        .tempdir(tempdir)
+        .arg("--index-traits")
+        .arg("--config")
+        .arg(&config)
+        .try_build()
+        .err()
+        .unwrap()
+        .to_string(),
+      format!("index at `{}{delimiter}regtest{delimiter}index.redb` was built with different `trait_ranges` than are configured, consider restoring them or deleting and rebuilding the index", path.display())
+    );
+  }
+
   #[test]
   fn new_schema_gives_correct_error() {
     let tempdir = {
@@ -0,0 +1,363 @@
+use {
+  super::*,
+  std::io::{Seek, SeekFrom},
+};
+
+const RECORD_HEADER_SIZE: usize = 8;
+const BLOCK_HEADER_SIZE: usize = 80;
+
+/// Reads blocks from Bitcoin Core's `blk*.dat` files. Each file is a sequence
+/// of records, a network magic and little-endian size followed by a
+/// serialized block, optionally obfuscated with the key in `xor.dat`.
+///
+/// Only record and block headers are read when scanning. Since Bitcoin Core
+/// appends blocks as they arrive, files are rescanned from where the last
+/// scan stopped whenever a block can't be found.
+pub(crate) struct BlkFiles {
+  dir: PathBuf,
+  key: [u8; 8],
+  locations: HashMap<BlockHash, (u32, u64)>,
+  magic: [u8; 4],
+  parents: HashMap<BlockHash, BlockHash>,
+  scanned: (u32, u64),
+}
+
+impl BlkFiles {
+  pub(crate) fn open(dir: &Path, network: Network) -> Result<Self> {
+    if !dir.is_dir() {
+      bail!("block file directory `{}` not found", dir.display());
+    }
+
+    let key = match fs::read(dir.join("xor.dat")) {
+      Ok(key) => key
+        .try_into()
+        .map_err(|_| anyhow!("`{}` is not 8 bytes long", dir.join("xor.dat").display()))?,
+      Err(err) if err.kind() == io::ErrorKind::NotFound => [0; 8],
+      Err(err) => return Err(err.into()),
+    };
+
+    Ok(Self {
+      dir: dir.into(),
+      key,
+      locations: HashMap::new(),
+      magic: network.magic().to_bytes(),
+      parents: HashMap::new(),
+      scanned: (0, 0),
+    })
+  }
+
+  /// Reads block `hash`, or returns `None` if it isn't in any block file.
+  pub(crate) fn get_block(&mut self, hash: BlockHash) -> Result<Option<Block>> {
+    let (file, offset) = match self.locate(hash)? {
+      Some(location) => location,
+      None => return Ok(None),
+    };
+
+    let mut file = File::open(self.path(file))?;
+
+    let mut size = [0; 4];
+    self.read_at(&mut file, offset - 4, &mut size)?;
+
+    let mut buffer = vec![0; u32::from_le_bytes(size).try_into().unwrap()];
+    self.read_at(&mut file, offset, &mut buffer)?;
+
+    let block = consensus::deserialize::<Block>(&buffer)
+      .with_context(|| format!("failed to deserialize block {hash} from block file"))?;
+
+    Ok(Some(block))
+  }
+
+  /// Reads only the header of block `hash`.
+  pub(crate) fn get_header(&mut self, hash: BlockHash) -> Result<Option<Header>> {
+    let (file, offset) = match self.locate(hash)? {
+      Some(location) => location,
+      None => return Ok(None),
+    };
+
+    let mut header = [0; BLOCK_HEADER_SIZE];
+    self.read_at(&mut File::open(self.path(file))?, offset, &mut header)?;
+
+    Ok(Some(consensus::deserialize(&header)?))
+  }
+
+  /// The hash of the parent of block `hash`, from the headers read while
+  /// scanning.
+  pub(crate) fn get_parent(&mut self, hash: BlockHash) -> Result<Option<BlockHash>> {
+    if self.locate(hash)?.is_none() {
+      return Ok(None);
+    }
+
+    Ok(self.parents.get(&hash).copied())
+  }
+
+  fn locate(&mut self, hash: BlockHash) -> Result<Option<(u32, u64)>> {
+    if !self.locations.contains_key(&hash) {
+      self.scan()?;
+    }
+
+    Ok(self.locations.get(&hash).copied())
+  }
+
+  fn path(&self, file: u32) -> PathBuf {
+    self.dir.join(format!("blk{file:05}.dat"))
+  }
+
+  fn read_at(&self, file: &mut File, offset: u64, buffer: &mut [u8]) -> io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buffer)?;
+
+    for (i, byte) in (offset..).zip(buffer.iter_mut()) {
+      *byte ^= self.key[usize::try_from(i % 8).unwrap()];
+    }
+
+    Ok(())
+  }
+
+  /// Records the location of every complete block written since the last
+  /// scan. Files are preallocated with zeros, so a record without the
+  /// network magic marks the end of a file's blocks.
+  ///
+  /// Bitcoin Core itself finds blocks with its `blocks/index` LevelDB
+  /// database, which also records which blocks are on the active chain.
+  /// Reading it would need a LevelDB implementation, so the files are
+  /// scanned instead, and the parent of each block is recorded so that the
+  /// active chain can be followed back from Bitcoin Core's best block. Stale
+  /// blocks in the files are never on that path, and file order doesn't
+  /// matter.
+  fn scan(&mut self) -> Result {
+    loop {
+      let (number, mut offset) = self.scanned;
+
+      let mut file = match File::open(self.path(number)) {
+        Ok(file) => file,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err.into()),
+      };
+
+      let len = file.metadata()?.len();
+
+      let record_header_size = RECORD_HEADER_SIZE as u64;
+
+      let mut record = [0; RECORD_HEADER_SIZE + BLOCK_HEADER_SIZE];
+
+      while offset + record.len() as u64 <= len {
+        self.read_at(&mut file, offset, &mut record)?;
+
+        if record[..4] != self.magic {
+          break;
+        }
+
+        let size = u32::from_le_bytes(record[4..8].try_into().unwrap());
+
+        let end = offset + record_header_size + u64::from(size);
+
+        if end > len {
+          break;
+        }
+
+        let header = consensus::deserialize::<Header>(&record[RECORD_HEADER_SIZE..])?;
+
+        let hash = header.block_hash();
+
+        self
+          .locations
+          .insert(hash, (number, offset + record_header_size));
+
+        self.parents.insert(hash, header.prev_blockhash);
+
+        offset = end;
+      }
+
+      self.scanned = (number, offset);
+
+      if !self.path(number + 1).is_file() {
+        return Ok(());
+      }
+
+      self.scanned = (number + 1, 0);
+    }
+  }
+}
+
+/// Writes `blocks` to `path` the way Bitcoin Core does, followed by the zero
+/// padding of a preallocated file.
+#[cfg(test)]
+pub(super) fn write_blk_file(path: &Path, network: Network, key: [u8; 8], blocks: &[Block]) {
+  let mut contents = Vec::new();
+
+  for block in blocks {
+    let block = consensus::serialize(block);
+    contents.extend_from_slice(&network.magic().to_bytes());
+    contents.extend_from_slice(&u32::try_from(block.len()).unwrap().to_le_bytes());
+    contents.extend_from_slice(&block);
+  }
+
+  contents.extend_from_slice(&[0; 100]);
+
+  for (i, byte) in contents.iter_mut().enumerate() {
+    *byte ^= key[i % 8];
+  }
+
+  fs::write(path, contents).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const KEY: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+  fn blocks() -> Vec<Block> {
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+
+    for _ in 0..2 {
+      let mut block = Chain::Regtest.genesis_block();
+      block.header.prev_blockhash = blocks.last().unwrap().block_hash();
+      blocks.push(block);
+    }
+
+    blocks
+  }
+
+  #[test]
+  fn read_blocks() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks = blocks();
+
+    write_blk_file(
+      &tempdir.path().join("blk00000.dat"),
+      Network::Regtest,
+      [0; 8],
+      &blocks[..2],
+    );
+    write_blk_file(
+      &tempdir.path().join("blk00001.dat"),
+      Network::Regtest,
+      [0; 8],
+      &blocks[2..],
+    );
+
+    let mut blk_files = BlkFiles::open(tempdir.path(), Network::Regtest).unwrap();
+
+    for block in &blocks {
+      assert_eq!(
+        blk_files.get_block(block.block_hash()).unwrap().as_ref(),
+        Some(block)
+      );
+      assert_eq!(
+        blk_files.get_header(block.block_hash()).unwrap(),
+        Some(block.header)
+      );
+      assert_eq!(
+        blk_files.get_parent(block.block_hash()).unwrap(),
+        Some(block.header.prev_blockhash)
+      );
+    }
+
+    assert_eq!(
+      blk_files
+        .get_block(Chain::Mainnet.genesis_block().block_hash())
+        .unwrap(),
+      None
+    );
+  }
+
+  #[test]
+  fn read_obfuscated_blocks() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks = blocks();
+
+    fs::write(tempdir.path().join("xor.dat"), KEY).unwrap();
+    write_blk_file(
+      &tempdir.path().join("blk00000.dat"),
+      Network::Regtest,
+      KEY,
+      &blocks,
+    );
+
+    let mut blk_files = BlkFiles::open(tempdir.path(), Network::Regtest).unwrap();
+
+    for block in &blocks {
+      assert_eq!(
+        blk_files.get_block(block.block_hash()).unwrap().as_ref(),
+        Some(block)
+      );
+    }
+  }
+
+  #[test]
+  fn blocks_appended_after_scan_are_found() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks = blocks();
+    let path = tempdir.path().join("blk00000.dat");
+
+    write_blk_file(&path, Network::Regtest, [0; 8], &blocks[..1]);
+
+    let mut blk_files = BlkFiles::open(tempdir.path(), Network::Regtest).unwrap();
+
+    assert!(blk_files
+      .get_block(blocks[0].block_hash())
+      .unwrap()
+      .is_some());
+    assert_eq!(blk_files.get_block(blocks[1].block_hash()).unwrap(), None);
+
+    write_blk_file(&path, Network::Regtest, [0; 8], &blocks);
+
+    assert_eq!(
+      blk_files
+        .get_block(blocks[1].block_hash())
+        .unwrap()
+        .as_ref(),
+      Some(&blocks[1])
+    );
+  }
+
+  #[test]
+  fn partially_written_blocks_are_skipped() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks = blocks();
+    let path = tempdir.path().join("blk00000.dat");
+
+    write_blk_file(&path, Network::Regtest, [0; 8], &blocks[..2]);
+
+    let contents = fs::read(&path).unwrap();
+    fs::write(&path, &contents[..contents.len() - 110]).unwrap();
+
+    let mut blk_files = BlkFiles::open(tempdir.path(), Network::Regtest).unwrap();
+
+    assert!(blk_files
+      .get_block(blocks[0].block_hash())
+      .unwrap()
+      .is_some());
+    assert_eq!(blk_files.get_block(blocks[1].block_hash()).unwrap(), None);
+  }
+
+  #[test]
+  fn open_errors() {
+    let tempdir = TempDir::new().unwrap();
+
+    assert_eq!(
+      BlkFiles::open(&tempdir.path().join("blocks"), Network::Regtest)
+        .err()
+        .unwrap()
+        .to_string(),
+      format!(
+        "block file directory `{}` not found",
+        tempdir.path().join("blocks").display()
+      )
+    );
+
+    fs::write(tempdir.path().join("xor.dat"), [0; 4]).unwrap();
+
+    assert_eq!(
+      BlkFiles::open(tempdir.path(), Network::Regtest)
+        .err()
+        .unwrap()
+        .to_string(),
+      format!(
+        "`{}` is not 8 bytes long",
+        tempdir.path().join("xor.dat").display()
+      )
+    );
+  }
+}
@@ -0,0 +1,653 @@
+use {
+  super::{blk_files::BlkFiles, *},
+  clap::ValueEnum,
+  hyper::{client::HttpConnector, StatusCode, Uri},
+};
+
+#[derive(Default, ValueEnum, Copy, Clone, Debug, PartialEq)]
+pub(crate) enum BlockSourceKind {
+  #[default]
+  Rpc,
+  Rest,
+  BlkFiles,
+  File,
+}
+
+/// Where the updater gets blocks, and the transactions whose output values it
+/// needs but hasn't seen.
+pub(crate) trait BlockSource: Send {
+  fn block_count(&mut self) -> Result<u64>;
+
+  fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>>;
+
+  fn block(&mut self, height: u64) -> Result<Option<Block>>;
+
+  fn header(&mut self, height: u64) -> Result<Option<Header>>;
+
+  fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>>;
+
+  /// Whether transactions come from Bitcoin Core's JSON-RPC interface, in
+  /// which case the updater fetches input values with batched requests
+  /// instead of through `transaction`.
+  fn fetches_transactions_over_rpc(&self) -> bool {
+    false
+  }
+}
+
+pub(crate) fn open(options: &Options, metrics: Arc<Metrics>) -> Result<Box<dyn BlockSource>> {
+  Ok(match options.block_source {
+    BlockSourceKind::Rpc => Box::new(Rpc(options.bitcoin_rpc_client_with_metrics(Some(metrics))?)),
+    BlockSourceKind::Rest => Box::new(Rest::new(&options.rest_url())?),
+    BlockSourceKind::BlkFiles => Box::new(BlkFileSource {
+      blk_files: BlkFiles::open(
+        &options.bitcoin_data_dir()?.join("blocks"),
+        options.chain().network(),
+      )?,
+      chain: Vec::new(),
+      fallback: Box::new(Rpc(options.bitcoin_rpc_client_with_metrics(Some(metrics))?)),
+    }),
+    BlockSourceKind::File => {
+      Box::new(Memory::load(options.blocks_file.as_ref().ok_or_else(
+        || anyhow!("`--block-source file` requires `--blocks-file`"),
+      )?)?)
+    }
+  })
+}
+
+/// Bitcoin Core's JSON-RPC interface.
+pub(crate) struct Rpc(pub(crate) Client);
+
+impl BlockSource for Rpc {
+  fn block_count(&mut self) -> Result<u64> {
+    Ok(self.0.get_block_count()?)
+  }
+
+  fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>> {
+    self.0.get_block_hash(height).into_option()
+  }
+
+  fn block(&mut self, height: u64) -> Result<Option<Block>> {
+    self
+      .block_hash(height)?
+      .map(|hash| Ok(self.0.get_block(&hash)?))
+      .transpose()
+  }
+
+  fn header(&mut self, height: u64) -> Result<Option<Header>> {
+    self
+      .block_hash(height)?
+      .map(|hash| Ok(self.0.get_block_header(&hash)?))
+      .transpose()
+  }
+
+  fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>> {
+    self.0.get_raw_transaction(&txid, None).into_option()
+  }
+
+  fn fetches_transactions_over_rpc(&self) -> bool {
+    true
+  }
+}
+
+/// Bitcoin Core's REST interface, enabled with `-rest`. It needs no
+/// authentication and serves blocks in their binary encoding, which is
+/// faster to transfer and decode than JSON-RPC's hex. Transactions outside
+/// the wallet can only be fetched with `-txindex`.
+pub(crate) struct Rest {
+  client: hyper::Client<HttpConnector>,
+  runtime: Runtime,
+  url: String,
+}
+
+impl Rest {
+  pub(crate) fn new(url: &str) -> Result<Self> {
+    Ok(Self {
+      client: hyper::Client::new(),
+      runtime: tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?,
+      url: url.trim_end_matches('/').into(),
+    })
+  }
+
+  fn get(&self, path: &str) -> Result<Option<Vec<u8>>> {
+    let uri = format!("{}/rest/{path}", self.url)
+      .parse::<Uri>()
+      .with_context(|| format!("invalid REST url `{}`", self.url))?;
+
+    self.runtime.block_on(async {
+      let response = self
+        .client
+        .get(uri.clone())
+        .await
+        .with_context(|| format!("failed to connect to Bitcoin Core REST interface at {uri}"))?;
+
+      match response.status() {
+        StatusCode::NOT_FOUND => Ok(None),
+        status if status.is_success() => Ok(Some(
+          hyper::body::to_bytes(response.into_body()).await?.to_vec(),
+        )),
+        status => bail!("request to {uri} failed: {status}"),
+      }
+    })
+  }
+
+  fn get_decoded<T: Decodable>(&self, path: &str) -> Result<Option<T>> {
+    self
+      .get(path)?
+      .map(|bytes| {
+        consensus::deserialize(&bytes).with_context(|| format!("failed to decode /rest/{path}"))
+      })
+      .transpose()
+  }
+}
+
+impl BlockSource for Rest {
+  fn block_count(&mut self) -> Result<u64> {
+    #[derive(Deserialize)]
+    struct ChainInfo {
+      blocks: u64,
+    }
+
+    let chain_info = self
+      .get("chaininfo.json")?
+      .ok_or_else(|| anyhow!("Bitcoin Core REST interface not found, is `-rest` enabled?"))?;
+
+    Ok(serde_json::from_slice::<ChainInfo>(&chain_info)?.blocks)
+  }
+
+  fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>> {
+    self.get_decoded(&format!("blockhashbyheight/{height}.bin"))
+  }
+
+  fn block(&mut self, height: u64) -> Result<Option<Block>> {
+    match self.block_hash(height)? {
+      Some(hash) => self.get_decoded(&format!("block/{hash}.bin")),
+      None => Ok(None),
+    }
+  }
+
+  fn header(&mut self, height: u64) -> Result<Option<Header>> {
+    let hash = match self.block_hash(height)? {
+      Some(hash) => hash,
+      None => return Ok(None),
+    };
+
+    // headers are concatenated without a length prefix, and an unknown block
+    // yields none rather than a 404
+    match self.get(&format!("headers/{hash}.bin?count=1"))? {
+      Some(headers) if headers.len() >= 80 => Ok(Some(consensus::deserialize(&headers[..80])?)),
+      _ => Ok(None),
+    }
+  }
+
+  fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>> {
+    self.get_decoded(&format!("tx/{txid}.bin"))
+  }
+}
+
+/// Bitcoin Core's `blk*.dat` files, with `fallback` for the best block and
+/// for transactions and blocks that haven't been written to disk yet.
+///
+/// The files don't say which blocks are on the active chain, so it is found
+/// by following block parents back from the fallback's best block, and
+/// `fallback` is only asked for the hashes of blocks newer than the files.
+/// The chain is followed again, as far back as it changed, whenever the block
+/// count or a height past its end is requested, so reorgs are seen by the
+/// updater. Input values are fetched by the updater's usual
+/// `getrawtransaction` fetcher, since finding a transaction in the block
+/// files would need Bitcoin Core's transaction index.
+pub(crate) struct BlkFileSource {
+  blk_files: BlkFiles,
+  chain: Vec<BlockHash>,
+  fallback: Box<dyn BlockSource>,
+}
+
+impl BlkFileSource {
+  /// Follows the active chain back from the best block until it meets the
+  /// chain followed last time, and returns the best block's height.
+  fn sync(&mut self) -> Result<u64> {
+    let tip = self.fallback.block_count()?;
+
+    let mut height = tip;
+    let mut hash = self
+      .fallback
+      .block_hash(height)?
+      .ok_or_else(|| anyhow!("best block at height {height} not found"))?;
+
+    let mut branch = Vec::new();
+
+    let fork = loop {
+      let i = usize::try_from(height)?;
+
+      if self.chain.get(i) == Some(&hash) {
+        break i + 1;
+      }
+
+      branch.push(hash);
+
+      if height == 0 {
+        break 0;
+      }
+
+      hash = match self.blk_files.get_parent(hash)? {
+        Some(parent) => parent,
+        None => match self.fallback.header(height)? {
+          Some(header) if header.block_hash() == hash => header.prev_blockhash,
+          _ => bail!("block {hash} at height {height} left the active chain while following it"),
+        },
+      };
+
+      height -= 1;
+    };
+
+    self.chain.truncate(fork);
+    self.chain.extend(branch.into_iter().rev());
+
+    Ok(tip)
+  }
+}
+
+impl BlockSource for BlkFileSource {
+  fn block_count(&mut self) -> Result<u64> {
+    self.sync()
+  }
+
+  fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>> {
+    let i = usize::try_from(height)?;
+
+    if i >= self.chain.len() {
+      self.sync()?;
+    }
+
+    Ok(self.chain.get(i).copied())
+  }
+
+  fn block(&mut self, height: u64) -> Result<Option<Block>> {
+    let hash = match self.block_hash(height)? {
+      Some(hash) => hash,
+      None => return Ok(None),
+    };
+
+    match self.blk_files.get_block(hash)? {
+      Some(block) => Ok(Some(block)),
+      None => self.fallback.block(height),
+    }
+  }
+
+  fn header(&mut self, height: u64) -> Result<Option<Header>> {
+    let hash = match self.block_hash(height)? {
+      Some(hash) => hash,
+      None => return Ok(None),
+    };
+
+    match self.blk_files.get_header(hash)? {
+      Some(header) => Ok(Some(header)),
+      None => self.fallback.header(height),
+    }
+  }
+
+  fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>> {
+    self.fallback.transaction(txid)
+  }
+
+  fn fetches_transactions_over_rpc(&self) -> bool {
+    self.fallback.fetches_transactions_over_rpc()
+  }
+}
+
+/// A fixed chain of blocks starting at genesis, such as a recorded regtest
+/// chain, which needs no Bitcoin Core at all.
+#[derive(Clone, Default)]
+pub(crate) struct Memory {
+  pub(crate) blocks: Vec<Block>,
+}
+
+impl Memory {
+  /// Loads blocks from a file of consensus-serialized blocks, one after the
+  /// other, each of which must be the child of the one before it.
+  pub(crate) fn load(path: &Path) -> Result<Self> {
+    let bytes = fs::read(path).with_context(|| format!("failed to read `{}`", path.display()))?;
+
+    let mut cursor = io::Cursor::new(bytes.as_slice());
+    let mut blocks = Vec::<Block>::new();
+
+    while usize::try_from(cursor.position())? < bytes.len() {
+      let block = Block::consensus_decode(&mut cursor).with_context(|| {
+        format!(
+          "failed to decode block {} in `{}`",
+          blocks.len(),
+          path.display()
+        )
+      })?;
+
+      if let Some(parent) = blocks.last() {
+        if block.header.prev_blockhash != parent.block_hash() {
+          bail!(
+            "block {} in `{}` is not a child of the block before it",
+            block.block_hash(),
+            path.display(),
+          );
+        }
+      }
+
+      blocks.push(block);
+    }
+
+    Ok(Self { blocks })
+  }
+}
+
+impl BlockSource for Memory {
+  fn block_count(&mut self) -> Result<u64> {
+    Ok(u64::try_from(self.blocks.len()).unwrap().saturating_sub(1))
+  }
+
+  fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>> {
+    Ok(self.header(height)?.map(|header| header.block_hash()))
+  }
+
+  fn block(&mut self, height: u64) -> Result<Option<Block>> {
+    Ok(
+      usize::try_from(height)
+        .ok()
+        .and_then(|height| self.blocks.get(height))
+        .cloned(),
+    )
+  }
+
+  fn header(&mut self, height: u64) -> Result<Option<Header>> {
+    Ok(
+      usize::try_from(height)
+        .ok()
+        .and_then(|height| self.blocks.get(height))
+        .map(|block| block.header),
+    )
+  }
+
+  fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>> {
+    Ok(
+      self
+        .blocks
+        .iter()
+        .flat_map(|block| &block.txdata)
+        .find(|tx| tx.txid() == txid)
+        .cloned(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    axum::{extract::Path as UrlPath, routing::get, Router},
+  };
+
+  fn chain() -> Vec<Block> {
+    let mut blocks = vec![Chain::Regtest.genesis_block()];
+
+    for _ in 0..2 {
+      let mut block = Chain::Regtest.genesis_block();
+      block.header.prev_blockhash = blocks.last().unwrap().block_hash();
+      blocks.push(block);
+    }
+
+    blocks
+  }
+
+  #[test]
+  fn memory() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("blocks.dat");
+    let blocks = chain();
+
+    fs::write(
+      &path,
+      blocks
+        .iter()
+        .flat_map(consensus::serialize)
+        .collect::<Vec<u8>>(),
+    )
+    .unwrap();
+
+    let mut memory = Memory::load(&path).unwrap();
+
+    assert_eq!(memory.block_count().unwrap(), 2);
+    assert_eq!(memory.block(1).unwrap().as_ref(), Some(&blocks[1]));
+    assert_eq!(memory.header(2).unwrap(), Some(blocks[2].header));
+    assert_eq!(memory.block_hash(2).unwrap(), Some(blocks[2].block_hash()));
+    assert_eq!(memory.block(3).unwrap(), None);
+    assert_eq!(
+      memory.transaction(blocks[0].txdata[0].txid()).unwrap(),
+      Some(blocks[0].txdata[0].clone())
+    );
+  }
+
+  #[test]
+  fn memory_blocks_must_be_a_chain() {
+    let tempdir = TempDir::new().unwrap();
+    let path = tempdir.path().join("blocks.dat");
+    let blocks = chain();
+
+    fs::write(
+      &path,
+      [&blocks[0], &blocks[2]]
+        .into_iter()
+        .flat_map(consensus::serialize)
+        .collect::<Vec<u8>>(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      Memory::load(&path).err().unwrap().to_string(),
+      format!(
+        "block {} in `{}` is not a child of the block before it",
+        blocks[2].block_hash(),
+        path.display()
+      )
+    );
+  }
+
+  #[test]
+  fn blk_files_fall_back_for_blocks_not_on_disk() {
+    let tempdir = TempDir::new().unwrap();
+    let blocks = chain();
+
+    blk_files::write_blk_file(
+      &tempdir.path().join("blk00000.dat"),
+      Network::Regtest,
+      [0; 8],
+      &blocks[..2],
+    );
+
+    // the fallback's blocks have no transactions, so blocks read from disk
+    // can be told apart from blocks read from the fallback
+    let mut source = BlkFileSource {
+      blk_files: BlkFiles::open(tempdir.path(), Network::Regtest).unwrap(),
+      chain: Vec::new(),
+      fallback: Box::new(Memory {
+        blocks: blocks
+          .iter()
+          .map(|block| Block {
+            header: block.header,
+            txdata: Vec::new(),
+          })
+          .collect(),
+      }),
+    };
+
+    assert_eq!(source.block_count().unwrap(), 2);
+    assert_eq!(source.block(1).unwrap().unwrap().txdata.len(), 1);
+    assert_eq!(source.block(2).unwrap().unwrap().txdata.len(), 0);
+    assert_eq!(source.header(2).unwrap(), Some(blocks[2].header));
+    assert_eq!(source.block(3).unwrap(), None);
+  }
+
+  /// Only knows the hash of the best block.
+  struct BestBlock(Memory);
+
+  impl BlockSource for BestBlock {
+    fn block_count(&mut self) -> Result<u64> {
+      self.0.block_count()
+    }
+
+    fn block_hash(&mut self, height: u64) -> Result<Option<BlockHash>> {
+      assert_eq!(
+        height,
+        self.0.block_count()?,
+        "hash requested from fallback"
+      );
+      self.0.block_hash(height)
+    }
+
+    fn block(&mut self, height: u64) -> Result<Option<Block>> {
+      panic!("block {height} requested from fallback")
+    }
+
+    fn header(&mut self, height: u64) -> Result<Option<Header>> {
+      panic!("header {height} requested from fallback")
+    }
+
+    fn transaction(&mut self, txid: Txid) -> Result<Option<Transaction>> {
+      panic!("transaction {txid} requested from fallback")
+    }
+  }
+
+  #[test]
+  fn blk_files_follow_the_active_chain_back_from_the_best_block() {
+    let tempdir = TempDir::new().unwrap();
+    let mut blocks = chain();
+
+    let mut stale = blocks[2].clone();
+    stale.header.time += 1;
+
+    blk_files::write_blk_file(
+      &tempdir.path().join("blk00000.dat"),
+      Network::Regtest,
+      [0; 8],
+      &[
+        blocks[0].clone(),
+        blocks[1].clone(),
+        stale.clone(),
+        blocks[2].clone(),
+      ],
+    );
+
+    let mut source = BlkFileSource {
+      blk_files: BlkFiles::open(tempdir.path(), Network::Regtest).unwrap(),
+      chain: Vec::new(),
+      fallback: Box::new(BestBlock(Memory {
+        blocks: blocks.clone(),
+      })),
+    };
+
+    assert_eq!(source.block_count().unwrap(), 2);
+
+    for (height, block) in blocks.iter().enumerate() {
+      assert_eq!(
+        source.block_hash(height.try_into().unwrap()).unwrap(),
+        Some(block.block_hash())
+      );
+    }
+
+    assert_eq!(source.block(2).unwrap().as_ref(), Some(&blocks[2]));
+
+    blocks[2] = stale;
+
+    source.fallback = Box::new(BestBlock(Memory {
+      blocks: blocks.clone(),
+    }));
+
+    assert_eq!(source.block_count().unwrap(), 2);
+    assert_eq!(source.block(2).unwrap().as_ref(), Some(&blocks[2]));
+    assert_eq!(source.block_hash(1).unwrap(), Some(blocks[1].block_hash()));
+  }
+
+  fn rest_response(blocks: &[Block], path: &str) -> Result<Vec<u8>, StatusCode> {
+    let (endpoint, argument) = path
+      .trim_start_matches('/')
+      .split_once('/')
+      .unwrap_or((path, ""));
+
+    let argument = argument.trim_end_matches(".bin");
+
+    let block = |hash: &str| {
+      blocks
+        .iter()
+        .find(|block| block.block_hash().to_string() == hash)
+    };
+
+    match endpoint {
+      "chaininfo.json" => Ok(format!(r#"{{"blocks":{}}}"#, blocks.len() - 1).into_bytes()),
+      "blockhashbyheight" => argument
+        .parse::<usize>()
+        .ok()
+        .and_then(|height| blocks.get(height))
+        .map(|block| consensus::serialize(&block.block_hash()))
+        .ok_or(StatusCode::NOT_FOUND),
+      "block" => block(argument)
+        .map(consensus::serialize)
+        .ok_or(StatusCode::NOT_FOUND),
+      "headers" => Ok(
+        block(argument)
+          .map(|block| consensus::serialize(&block.header))
+          .unwrap_or_default(),
+      ),
+      "tx" => blocks
+        .iter()
+        .flat_map(|block| &block.txdata)
+        .find(|tx| tx.txid().to_string() == argument)
+        .map(consensus::serialize)
+        .ok_or(StatusCode::NOT_FOUND),
+      _ => Err(StatusCode::BAD_REQUEST),
+    }
+  }
+
+  /// Serves `blocks` the way Bitcoin Core's REST interface does.
+  fn rest_server(blocks: Vec<Block>) -> String {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let blocks = Arc::new(blocks);
+
+    let router = Router::new().route(
+      "/rest/*path",
+      get(move |UrlPath(path): UrlPath<String>| {
+        let blocks = blocks.clone();
+        async move { rest_response(&blocks, &path) }
+      }),
+    );
+
+    thread::spawn(|| {
+      Runtime::new().unwrap().block_on(async {
+        axum::Server::from_tcp(listener)
+          .unwrap()
+          .serve(router.into_make_service())
+          .await
+          .unwrap()
+      })
+    });
+
+    url
+  }
+
+  #[test]
+  fn rest() {
+    let blocks = chain();
+    let mut rest = Rest::new(&rest_server(blocks.clone())).unwrap();
+
+    assert_eq!(rest.block_count().unwrap(), 2);
+    assert_eq!(rest.block_hash(1).unwrap(), Some(blocks[1].block_hash()));
+    assert_eq!(rest.block(2).unwrap().as_ref(), Some(&blocks[2]));
+    assert_eq!(rest.header(2).unwrap(), Some(blocks[2].header));
+    assert_eq!(rest.block(3).unwrap(), None);
+    assert_eq!(rest.header(3).unwrap(), None);
+    assert_eq!(
+      rest.transaction(blocks[0].txdata[0].txid()).unwrap(),
+      Some(blocks[0].txdata[0].clone())
+    );
+    assert_eq!(rest.transaction(Txid::all_zeros()).unwrap(), None);
+  }
+}
@@ -64,6 +64,8 @@ impl Entry for InscriptionEntry {
   }
 }
 
+pub(super) type ContentHashValue = [u8; 32];
+
 pub(super) type InscriptionIdValue = [u8; 36];
 
 impl Entry for InscriptionId {
@@ -1,16 +1,18 @@
 use {
-  crate::Options,
+  crate::{Metrics, Options},
   anyhow::{anyhow, Result},
   base64::Engine,
   bitcoin::{Transaction, Txid},
   hyper::{client::HttpConnector, Body, Client, Method, Request, Uri},
   serde::Deserialize,
   serde_json::{json, Value},
+  std::sync::Arc,
 };
 
 pub(crate) struct Fetcher {
   auth: String,
   client: Client<HttpConnector>,
+  metrics: Arc<Metrics>,
   url: Uri,
 }
 
@@ -28,7 +30,7 @@ struct JsonError {
 }
 
 impl Fetcher {
-  pub(crate) fn new(options: &Options) -> Result<Self> {
+  pub(crate) fn new(options: &Options, metrics: Arc<Metrics>) -> Result<Self> {
     let client = Client::new();
 
     let url = if options.rpc_url().starts_with("http://") {
@@ -45,7 +47,12 @@ impl Fetcher {
       "Basic {}",
       &base64::engine::general_purpose::STANDARD.encode(auth)
     );
-    Ok(Fetcher { client, url, auth })
+    Ok(Fetcher {
+      client,
+      metrics,
+      url,
+      auth,
+    })
   }
 
   pub(crate) async fn get_transactions(&self, txids: Vec<Txid>) -> Result<Vec<Transaction>> {
@@ -64,6 +71,10 @@ impl Fetcher {
       reqs.push(req);
     }
 
+    self
+      .metrics
+      .increment_rpc_calls("getrawtransaction", txids.len().try_into().unwrap());
+
     let body = Value::Array(reqs).to_string();
 
     let mut results: Vec<JsonResponse<String>>;
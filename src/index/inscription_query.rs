@@ -0,0 +1,80 @@
+use super::*;
+
+#[derive(Debug, Default)]
+pub(crate) struct InscriptionQuery {
+  pub(crate) ascending: bool,
+  pub(crate) content_type: Option<String>,
+  pub(crate) cursed: Option<bool>,
+  pub(crate) max_height: Option<u64>,
+  pub(crate) min_height: Option<u64>,
+  pub(crate) outputs: Option<Vec<OutPoint>>,
+  pub(crate) rarity: Option<Rarity>,
+}
+
+impl InscriptionQuery {
+  pub(super) fn matches(&self, entry: &InscriptionEntry) -> bool {
+    self
+      .min_height
+      .map_or(true, |min_height| entry.height >= min_height)
+      && self
+        .max_height
+        .map_or(true, |max_height| entry.height <= max_height)
+      && self
+        .cursed
+        .map_or(true, |cursed| cursed == (entry.number < 0))
+      && self
+        .rarity
+        .map_or(true, |rarity| entry.sat.map(Rarity::from) == Some(rarity))
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn matches() {
+    let entry = InscriptionEntry {
+      fee: 0,
+      height: 10,
+      number: -1,
+      sat: Some(Sat(50 * COIN_VALUE)),
+      timestamp: 0,
+    };
+
+    assert!(InscriptionQuery::default().matches(&entry));
+
+    assert!(InscriptionQuery {
+      min_height: Some(10),
+      max_height: Some(10),
+      cursed: Some(true),
+      rarity: Some(Rarity::Uncommon),
+      ..Default::default()
+    }
+    .matches(&entry));
+
+    assert!(!InscriptionQuery {
+      min_height: Some(11),
+      ..Default::default()
+    }
+    .matches(&entry));
+
+    assert!(!InscriptionQuery {
+      max_height: Some(9),
+      ..Default::default()
+    }
+    .matches(&entry));
+
+    assert!(!InscriptionQuery {
+      cursed: Some(false),
+      ..Default::default()
+    }
+    .matches(&entry));
+
+    assert!(!InscriptionQuery {
+      rarity: Some(Rarity::Common),
+      ..Default::default()
+    }
+    .matches(&entry));
+  }
+}
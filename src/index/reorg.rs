@@ -1,4 +1,4 @@
-use {super::*, updater::BlockData};
+use {super::*, block_source::BlockSource, updater::BlockData};
 
 #[derive(Debug, PartialEq)]
 pub(crate) enum ReorgError {
@@ -26,7 +26,12 @@ const CHAIN_TIP_DISTANCE: u64 = 21;
 pub(crate) struct Reorg {}
 
 impl Reorg {
-  pub(crate) fn detect_reorg(block: &BlockData, height: u64, index: &Index) -> Result {
+  pub(crate) fn detect_reorg(
+    block: &BlockData,
+    height: u64,
+    index: &Index,
+    block_source: &Mutex<Box<dyn BlockSource>>,
+  ) -> Result {
     let bitcoind_prev_blockhash = block.header.prev_blockhash;
 
     match index.block_hash(height.checked_sub(1))? {
@@ -37,10 +42,10 @@ impl Reorg {
 
         for depth in 1..max_recoverable_reorg_depth {
           let index_block_hash = index.block_hash(height.checked_sub(depth))?;
-          let bitcoind_block_hash = index
-            .client
-            .get_block_hash(height.saturating_sub(depth))
-            .into_option()?;
+          let bitcoind_block_hash = block_source
+            .lock()
+            .unwrap()
+            .block_hash(height.saturating_sub(depth))?;
 
           if index_block_hash == bitcoind_block_hash {
             return Err(anyhow!(ReorgError::Recoverable((height, depth))));
@@ -74,7 +79,11 @@ impl Reorg {
     Ok(())
   }
 
-  pub(crate) fn update_savepoints(index: &Index, height: u64) -> Result {
+  pub(crate) fn update_savepoints(
+    index: &Index,
+    height: u64,
+    block_source: &Mutex<Box<dyn BlockSource>>,
+  ) -> Result {
     let last_save_point_height = index
       .begin_read()?
       .0
@@ -84,10 +93,10 @@ impl Reorg {
       .unwrap_or(0);
 
     if (height < SAVEPOINT_INTERVAL || height - last_save_point_height >= SAVEPOINT_INTERVAL)
-      && index
-        .client
-        .get_blockchain_info()?
-        .headers
+      && block_source
+        .lock()
+        .unwrap()
+        .block_count()?
         .saturating_sub(height)
         <= CHAIN_TIP_DISTANCE
     {
@@ -1,6 +1,7 @@
 use {
   self::inscription_updater::InscriptionUpdater,
-  super::{fetcher::Fetcher, *},
+  super::{block_source::BlockSource, fetcher::Fetcher, *},
+  crate::sat_trait::TraitRanges,
   futures::future::try_join_all,
   std::sync::mpsc,
   tokio::sync::mpsc::{error::TryRecvError, Receiver, Sender},
@@ -33,34 +34,65 @@ pub(crate) struct Updater<'index> {
   range_cache: HashMap<OutPointValue, Vec<u8>>,
   height: u64,
   index: &'index Index,
+  index_addresses: bool,
+  index_content: bool,
+  index_lost_sats: bool,
+  index_sat_history: bool,
   index_sats: bool,
+  index_traits: bool,
+  index_tx_outs: bool,
   index_utxos: bool,
+  last_commit: (Instant, u64),
   skip_empty_outputs: bool,
   sat_ranges_since_flush: u64,
   outputs_cached: u64,
   outputs_inserted_since_flush: u64,
   outputs_traversed: u64,
+  trait_ranges: TraitRanges,
 }
 
 impl<'index> Updater<'_> {
   pub(crate) fn new(index: &'index Index) -> Result<Updater<'index>> {
+    let height = index.block_count()?;
+
+    let index_traits = index.has_trait_index()?;
+
     Ok(Updater {
       range_cache: HashMap::new(),
-      height: index.block_count()?,
+      height,
       index,
+      index_addresses: index.has_address_index()?,
+      index_content: index.has_content_index()?,
+      index_lost_sats: index.has_lost_sat_index()?,
+      index_sat_history: index.has_sat_history_index()?,
       index_sats: index.has_sat_index()?,
+      index_traits,
+      index_tx_outs: index.has_tx_out_index()?,
       index_utxos: index.has_utxo_index()?,
+      last_commit: (Instant::now(), height),
       skip_empty_outputs: index.options.skip_empty_outputs,
       sat_ranges_since_flush: 0,
       outputs_cached: 0,
       outputs_inserted_since_flush: 0,
       outputs_traversed: 0,
+      trait_ranges: index.trait_ranges()?.unwrap_or_default(),
     })
   }
 
-  pub(crate) fn update_index(&mut self) -> Result {
+  /// Indexes blocks from a source returned by `open_block_source`, which is
+  /// shared by the block fetching thread and the tip and reorg checks, and
+  /// opened again to fetch input values if it doesn't fetch them over RPC.
+  pub(crate) fn update_index(
+    &mut self,
+    open_block_source: &dyn Fn() -> Result<Box<dyn BlockSource>>,
+  ) -> Result {
+    let block_source = Arc::new(Mutex::new(open_block_source()?));
+
     let mut wtx = self.index.begin_write()?;
-    let starting_height = self.index.client.get_block_count()? + 1;
+
+    let starting_height = block_source.lock().unwrap().block_count()? + 1;
+
+    self.index.metrics.set_bitcoind_height(starting_height - 1);
 
     wtx
       .open_table(WRITE_TRANSACTION_STARTING_BLOCK_COUNT_TO_TIMESTAMP)?
@@ -88,15 +120,26 @@ impl<'index> Updater<'_> {
       Some(progress_bar)
     };
 
-    let rx = Self::fetch_blocks_from(self.index, self.height, self.index_sats)?;
+    let rx = Self::fetch_blocks_from(
+      self.index,
+      block_source.clone(),
+      self.height,
+      self.index_sats || self.index_addresses,
+    )?;
 
-    let (mut outpoint_sender, mut value_receiver) = Self::spawn_fetcher(self.index)?;
+    let (mut outpoint_sender, mut value_receiver) =
+      if block_source.lock().unwrap().fetches_transactions_over_rpc() {
+        Self::spawn_fetcher(self.index)?
+      } else {
+        Self::spawn_block_source_fetcher(open_block_source()?)?
+      };
 
     let mut uncommitted = 0;
     let mut value_cache = HashMap::new();
     while let Ok(block) = rx.recv() {
       self.index_block(
         self.index,
+        &block_source,
         &mut outpoint_sender,
         &mut value_receiver,
         &mut wtx,
@@ -104,11 +147,17 @@ impl<'index> Updater<'_> {
         &mut value_cache,
       )?;
 
+      self
+        .index
+        .metrics
+        .set_range_cache_size(self.range_cache.len());
+
       if let Some(progress_bar) = &mut progress_bar {
         progress_bar.inc(1);
 
         if progress_bar.position() > progress_bar.length().unwrap() {
-          if let Ok(count) = self.index.client.get_block_count() {
+          if let Ok(count) = block_source.lock().unwrap().block_count() {
+            self.index.metrics.set_bitcoind_height(count);
             progress_bar.set_length(count + 1);
           } else {
             log::warn!("Failed to fetch latest block height");
@@ -119,7 +168,7 @@ impl<'index> Updater<'_> {
       uncommitted += 1;
 
       if uncommitted == self.index.options.commit {
-        self.commit(wtx, value_cache)?;
+        self.commit(wtx, value_cache, &block_source)?;
         value_cache = HashMap::new();
         uncommitted = 0;
         wtx = self.index.begin_write()?;
@@ -152,7 +201,7 @@ impl<'index> Updater<'_> {
     }
 
     if uncommitted > 0 {
-      self.commit(wtx, value_cache)?;
+      self.commit(wtx, value_cache, &block_source)?;
     }
 
     if let Some(progress_bar) = &mut progress_bar {
@@ -164,15 +213,14 @@ impl<'index> Updater<'_> {
 
   fn fetch_blocks_from(
     index: &Index,
+    block_source: Arc<Mutex<Box<dyn BlockSource>>>,
     mut height: u64,
-    index_sats: bool,
+    full_blocks: bool,
   ) -> Result<mpsc::Receiver<BlockData>> {
     let (tx, rx) = mpsc::sync_channel(32);
 
     let height_limit = index.height_limit;
 
-    let client = index.options.bitcoin_rpc_client()?;
-
     let first_inscription_height = index.first_inscription_height;
 
     thread::spawn(move || loop {
@@ -182,7 +230,12 @@ impl<'index> Updater<'_> {
         }
       }
 
-      match Self::get_block_with_retries(&client, height, index_sats, first_inscription_height) {
+      match Self::get_block_with_retries(
+        &block_source,
+        height,
+        full_blocks,
+        first_inscription_height,
+      ) {
         Ok(Some(block)) => {
           if let Err(err) = tx.send(block.into()) {
             log::info!("Block receiver disconnected: {err}");
@@ -202,30 +255,29 @@ impl<'index> Updater<'_> {
   }
 
   fn get_block_with_retries(
-    client: &Client,
+    block_source: &Mutex<Box<dyn BlockSource>>,
     height: u64,
-    index_sats: bool,
+    full_blocks: bool,
     first_inscription_height: u64,
   ) -> Result<Option<Block>> {
     let mut errors = 0;
     loop {
-      match client
-        .get_block_hash(height)
-        .into_option()
-        .and_then(|option| {
-          option
-            .map(|hash| {
-              if index_sats || height >= first_inscription_height {
-                Ok(client.get_block(&hash)?)
-              } else {
-                Ok(Block {
-                  header: client.get_block_header(&hash)?,
-                  txdata: Vec::new(),
-                })
-              }
+      let result = {
+        let mut block_source = block_source.lock().unwrap();
+
+        if full_blocks || height >= first_inscription_height {
+          block_source.block(height)
+        } else {
+          block_source.header(height).map(|header| {
+            header.map(|header| Block {
+              header,
+              txdata: Vec::new(),
             })
-            .transpose()
-        }) {
+          })
+        }
+      };
+
+      match result {
         Err(err) => {
           if cfg!(test) {
             return Err(err);
@@ -248,7 +300,7 @@ impl<'index> Updater<'_> {
   }
 
   fn spawn_fetcher(index: &Index) -> Result<(Sender<OutPoint>, Receiver<u64>)> {
-    let fetcher = Fetcher::new(&index.options)?;
+    let fetcher = Fetcher::new(&index.options, index.metrics.clone())?;
 
     // Not sure if any block has more than 20k inputs, but none so far after first inscription block
     const CHANNEL_BUFFER_SIZE: usize = 20_000;
@@ -313,16 +365,59 @@ impl<'index> Updater<'_> {
     Ok((outpoint_sender, value_receiver))
   }
 
+  /// Fetches input values one transaction at a time from a block source
+  /// without a batched interface.
+  fn spawn_block_source_fetcher(
+    mut block_source: Box<dyn BlockSource>,
+  ) -> Result<(Sender<OutPoint>, Receiver<u64>)> {
+    const CHANNEL_BUFFER_SIZE: usize = 20_000;
+    let (outpoint_sender, mut outpoint_receiver) =
+      tokio::sync::mpsc::channel::<OutPoint>(CHANNEL_BUFFER_SIZE);
+    let (value_sender, value_receiver) = tokio::sync::mpsc::channel::<u64>(CHANNEL_BUFFER_SIZE);
+
+    thread::spawn(move || {
+      while let Some(outpoint) = outpoint_receiver.blocking_recv() {
+        let value = match block_source.transaction(outpoint.txid) {
+          Ok(Some(tx)) => match tx.output.get(usize::try_from(outpoint.vout).unwrap()) {
+            Some(output) => output.value,
+            None => {
+              log::error!("output {outpoint} not found");
+              return;
+            }
+          },
+          Ok(None) => {
+            log::error!("transaction {} not found", outpoint.txid);
+            return;
+          }
+          Err(err) => {
+            log::error!("Couldn't receive tx {}: {err}", outpoint.txid);
+            return;
+          }
+        };
+
+        if value_sender.blocking_send(value).is_err() {
+          log::error!("Value channel closed unexpectedly");
+          return;
+        }
+      }
+
+      log::debug!("Outpoint channel closed");
+    });
+
+    Ok((outpoint_sender, value_receiver))
+  }
+
   fn index_block(
     &mut self,
     index: &Index,
+    block_source: &Mutex<Box<dyn BlockSource>>,
     outpoint_sender: &mut Sender<OutPoint>,
     value_receiver: &mut Receiver<u64>,
     wtx: &mut WriteTransaction,
     block: BlockData,
     value_cache: &mut HashMap<OutPoint, u64>,
   ) -> Result<()> {
-    Reorg::detect_reorg(&block, self.height, self.index)?;
+    Reorg::detect_reorg(&block, self.height, self.index, block_source)?;
 
     let start = Instant::now();
     let mut sat_ranges_written = 0;
@@ -379,13 +474,26 @@ impl<'index> Updater<'_> {
       }
     }
 
+    let mut content_hash_to_body = self
+      .index_content
+      .then(|| wtx.open_table(CONTENT_HASH_TO_BODY))
+      .transpose()?;
+    let mut content_hash_to_inscription_id =
+      wtx.open_multimap_table(CONTENT_HASH_TO_INSCRIPTION_ID)?;
+    let mut content_type_and_inscription_number =
+      wtx.open_table(CONTENT_TYPE_AND_INSCRIPTION_NUMBER)?;
     let mut height_to_block_hash = wtx.open_table(HEIGHT_TO_BLOCK_HASH)?;
     let mut height_to_inscription_id = wtx.open_multimap_table(HEIGHT_TO_INSCRIPTION_ID)?;
+    let mut inscription_id_to_content = self
+      .index_content
+      .then(|| wtx.open_table(INSCRIPTION_ID_TO_CONTENT))
+      .transpose()?;
     let mut inscription_id_to_inscription_entry =
       wtx.open_table(INSCRIPTION_ID_TO_INSCRIPTION_ENTRY)?;
     let mut inscription_id_to_satpoint = wtx.open_table(INSCRIPTION_ID_TO_SATPOINT)?;
     let mut inscription_number_to_inscription_id =
       wtx.open_table(INSCRIPTION_NUMBER_TO_INSCRIPTION_ID)?;
+    let mut rarity_and_inscription_number = wtx.open_table(RARITY_AND_INSCRIPTION_NUMBER)?;
     let mut reinscription_id_to_seq_num = wtx.open_table(REINSCRIPTION_ID_TO_SEQUENCE_NUMBER)?;
     let mut sat_to_inscription_id = wtx.open_multimap_table(SAT_TO_INSCRIPTION_ID)?;
     let mut satpoint_to_inscription_id = wtx.open_multimap_table(SATPOINT_TO_INSCRIPTION_ID)?;
@@ -402,14 +510,19 @@ impl<'index> Updater<'_> {
       .unwrap_or(0);
 
     let mut inscription_updater = InscriptionUpdater::new(
+      &mut content_hash_to_body,
+      &mut content_hash_to_inscription_id,
+      &mut content_type_and_inscription_number,
       self.height,
       &mut height_to_inscription_id,
       &mut inscription_id_to_satpoint,
       value_receiver,
+      &mut inscription_id_to_content,
       &mut inscription_id_to_inscription_entry,
       lost_sats,
       &mut inscription_number_to_inscription_id,
       &mut outpoint_to_value,
+      &mut rarity_and_inscription_number,
       &mut reinscription_id_to_seq_num,
       &mut sat_to_inscription_id,
       &mut satpoint_to_inscription_id,
@@ -426,6 +539,21 @@ impl<'index> Updater<'_> {
       } else {
         None
       };
+      let mut sat_trait_to_satpoint = if self.index_traits {
+        wtx.open_table(SAT_TRAIT_TO_SATPOINT).ok()
+      } else {
+        None
+      };
+      let mut sat_range_to_history = if self.index_sat_history {
+        wtx.open_table(SAT_RANGE_TO_HISTORY).ok()
+      } else {
+        None
+      };
+      let mut lost_sat_offset_to_range = if self.index_lost_sats {
+        wtx.open_table(LOST_SAT_OFFSET_TO_RANGE).ok()
+      } else {
+        None
+      };
 
       let mut coinbase_inputs = VecDeque::new();
 
@@ -479,6 +607,10 @@ impl<'index> Updater<'_> {
           tx,
           *txid,
           &mut sat_to_outpoint,
+          &mut sat_trait_to_satpoint,
+          &mut sat_range_to_history,
+          &mut lost_sat_offset_to_range,
+          tx_offset.try_into().unwrap(),
           &mut lost_sats,
           &mut outpoint_to_sat_ranges,
           &mut sat_to_satpoint,
@@ -497,6 +629,10 @@ impl<'index> Updater<'_> {
           tx,
           *txid,
           &mut sat_to_outpoint,
+          &mut sat_trait_to_satpoint,
+          &mut sat_range_to_history,
+          &mut lost_sat_offset_to_range,
+          block.txdata.len().try_into().unwrap(),
           &mut lost_sats,
           &mut outpoint_to_sat_ranges,
           &mut sat_to_satpoint,
@@ -509,12 +645,30 @@ impl<'index> Updater<'_> {
       }
 
       if !coinbase_inputs.is_empty() {
+        let mut offset = lost_sats;
+        for range in &coinbase_inputs {
+          Self::record_sat_history(
+            &mut sat_range_to_history,
+            (self.height, (block.txdata.len() + 1).try_into().unwrap()),
+            *range,
+            SatPoint {
+              outpoint: OutPoint::null(),
+              offset,
+            },
+          )?;
+          offset += range.1 - range.0;
+        }
+
         Self::mark_sats_as_lost(
           coinbase_inputs,
+          (self.height, LostCause::Unclaimed),
           &mut lost_sats,
           &mut outpoint_to_sat_ranges,
           &mut sat_to_satpoint,
           &mut sat_to_outpoint,
+          &mut sat_trait_to_satpoint,
+          &mut lost_sat_offset_to_range,
+          &self.trait_ranges,
         )?;
       }
     } else {
@@ -523,6 +677,10 @@ impl<'index> Updater<'_> {
       }
     }
 
+    if self.index_tx_outs {
+      Self::index_block_tx_outs(wtx, &block, self.index_addresses)?;
+    }
+
     statistic_to_count.insert(&Statistic::LostSats.key(), &inscription_updater.lost_sats)?;
 
     statistic_to_count.insert(
@@ -543,12 +701,64 @@ impl<'index> Updater<'_> {
     Ok(())
   }
 
+  fn index_block_tx_outs(
+    wtx: &WriteTransaction,
+    block: &BlockData,
+    index_addresses: bool,
+  ) -> Result {
+    let mut outpoint_to_tx_out = wtx.open_table(OUTPOINT_TO_TX_OUT)?;
+    let mut script_pubkey_to_outpoint = index_addresses
+      .then(|| wtx.open_multimap_table(SCRIPT_PUBKEY_TO_OUTPOINT))
+      .transpose()?;
+
+    for (tx, txid) in &block.txdata {
+      for input in &tx.input {
+        let outpoint = input.previous_output.store();
+
+        let tx_out = outpoint_to_tx_out
+          .remove(&outpoint)?
+          .map(|tx_out| tx_out.value().to_vec());
+
+        if let (Some(tx_out), Some(script_pubkey_to_outpoint)) =
+          (tx_out, &mut script_pubkey_to_outpoint)
+        {
+          let tx_out: TxOut = consensus::encode::deserialize(&tx_out)?;
+          script_pubkey_to_outpoint.remove(tx_out.script_pubkey.as_bytes(), &outpoint)?;
+        }
+      }
+
+      for (vout, tx_out) in tx.output.iter().enumerate() {
+        if tx_out.script_pubkey.is_provably_unspendable() {
+          continue;
+        }
+
+        let outpoint = OutPoint {
+          txid: *txid,
+          vout: vout.try_into().unwrap(),
+        }
+        .store();
+
+        outpoint_to_tx_out.insert(&outpoint, consensus::encode::serialize(tx_out).as_slice())?;
+
+        if let Some(script_pubkey_to_outpoint) = &mut script_pubkey_to_outpoint {
+          script_pubkey_to_outpoint.insert(tx_out.script_pubkey.as_bytes(), &outpoint)?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
   fn mark_sats_as_lost(
     ranges: VecDeque<(u64, u64)>,
+    (height, cause): (u64, LostCause),
     lost_sats: &mut u64,
     outpoint_to_sat_ranges: &mut Table<&OutPointValue, &[u8]>,
     sat_to_satpoint: &mut Table<u64, &SatPointValue>,
     sat_to_outpoint: &mut Option<Table<u64, &OutPointPrefixValue>>,
+    sat_trait_to_satpoint: &mut Option<Table<(u8, u64), (u64, &SatPointValue)>>,
+    lost_sat_offset_to_range: &mut Option<Table<u64, (u64, u64, u64, u8)>>,
+    trait_ranges: &TraitRanges,
   ) -> Result {
     let mut lost_sat_ranges = outpoint_to_sat_ranges
       .remove(&OutPoint::null().store())?
@@ -571,6 +781,20 @@ impl<'index> Updater<'_> {
         sat_to_outpoint.insert(&start, &OutPoint::null().store().store())?;
       }
 
+      Self::index_sat_traits(
+        sat_trait_to_satpoint,
+        trait_ranges,
+        (start, end),
+        SatPoint {
+          outpoint: OutPoint::null(),
+          offset: *lost_sats,
+        },
+      )?;
+
+      if let Some(lost_sat_offset_to_range) = lost_sat_offset_to_range {
+        lost_sat_offset_to_range.insert(*lost_sats, (start, end, height, cause.key()))?;
+      }
+
       lost_sat_ranges.extend_from_slice(&(start, end).store());
 
       *lost_sats += end - start;
@@ -581,11 +805,61 @@ impl<'index> Updater<'_> {
     Ok(())
   }
 
+  /// Records that `range` moved to `satpoint` in transaction `sequence` of
+  /// block `height`.
+  fn record_sat_history(
+    sat_range_to_history: &mut Option<Table<(u64, u64, u32), (u64, u64, &OutPointValue)>>,
+    (height, sequence): (u64, u32),
+    (start, end): (u64, u64),
+    satpoint: SatPoint,
+  ) -> Result {
+    if let Some(sat_range_to_history) = sat_range_to_history {
+      sat_range_to_history.insert(
+        (start, height, sequence),
+        (end, satpoint.offset, &satpoint.outpoint.store()),
+      )?;
+    }
+
+    Ok(())
+  }
+
+  /// Records where every run of sats with each trait in `range` now lives.
+  fn index_sat_traits(
+    sat_trait_to_satpoint: &mut Option<Table<(u8, u64), (u64, &SatPointValue)>>,
+    trait_ranges: &TraitRanges,
+    (start, end): (u64, u64),
+    satpoint: SatPoint,
+  ) -> Result {
+    if let Some(sat_trait_to_satpoint) = sat_trait_to_satpoint {
+      for sat_trait in SatTrait::ALL {
+        for (run_start, run_end) in sat_trait.runs_in(start, end, trait_ranges) {
+          sat_trait_to_satpoint.insert(
+            (sat_trait.key(), run_start),
+            (
+              run_end,
+              &SatPoint {
+                outpoint: satpoint.outpoint,
+                offset: satpoint.offset + run_start - start,
+              }
+              .store(),
+            ),
+          )?;
+        }
+      }
+    }
+
+    Ok(())
+  }
+
   fn index_transaction_sats(
     &mut self,
     tx: &Transaction,
     txid: Txid,
     sat_to_outpoint: &mut Option<Table<u64, &OutPointPrefixValue>>,
+    sat_trait_to_satpoint: &mut Option<Table<(u8, u64), (u64, &SatPointValue)>>,
+    sat_range_to_history: &mut Option<Table<(u64, u64, u32), (u64, u64, &OutPointValue)>>,
+    lost_sat_offset_to_range: &mut Option<Table<u64, (u64, u64, u64, u8)>>,
+    sequence: u32,
     lost_sats: &mut u64,
     outpoint_to_sat_ranges: &mut Table<&OutPointValue, &[u8]>,
     sat_to_satpoint: &mut Table<u64, &SatPointValue>,
@@ -634,6 +908,26 @@ impl<'index> Updater<'_> {
           range
         };
 
+        Self::index_sat_traits(
+          sat_trait_to_satpoint,
+          &self.trait_ranges,
+          assigned,
+          SatPoint {
+            outpoint,
+            offset: output.value - remaining,
+          },
+        )?;
+
+        Self::record_sat_history(
+          sat_range_to_history,
+          (self.height, sequence),
+          assigned,
+          SatPoint {
+            outpoint,
+            offset: output.value - remaining,
+          },
+        )?;
+
         sats.extend_from_slice(&assigned.store());
 
         remaining -= assigned.1 - assigned.0;
@@ -646,7 +940,16 @@ impl<'index> Updater<'_> {
       if sats.is_empty() && self.skip_empty_outputs {
         tprintln!("not indexing empty output {outpoint}");
       } else {
-        let old_value = self.range_cache.insert(outpoint.store(), sats);
+        let mut old_value = self.range_cache.insert(outpoint.store(), sats);
+
+        // Only a coinbase can repeat an earlier transaction's txid, so only its
+        // outputs are checked against those already flushed to the database.
+        if old_value.is_none() && tx.is_coin_base() {
+          old_value = outpoint_to_sat_ranges
+            .remove(&outpoint.store())?
+            .map(|old_value| old_value.value().to_vec());
+        }
+
         if let Some(old_value) = old_value {
           let mut sats = VecDeque::new();
           for chunk in old_value.chunks_exact(11) {
@@ -656,10 +959,14 @@ impl<'index> Updater<'_> {
 
           Self::mark_sats_as_lost(
             sats,
+            (self.height, LostCause::DuplicateTxid),
             lost_sats,
             outpoint_to_sat_ranges,
             sat_to_satpoint,
             sat_to_outpoint,
+            sat_trait_to_satpoint,
+            lost_sat_offset_to_range,
+            &self.trait_ranges,
           )?;
         }
         self.outputs_inserted_since_flush += 1;
@@ -669,7 +976,14 @@ impl<'index> Updater<'_> {
     Ok(())
   }
 
-  fn commit(&mut self, wtx: WriteTransaction, value_cache: HashMap<OutPoint, u64>) -> Result {
+  fn commit(
+    &mut self,
+    wtx: WriteTransaction,
+    value_cache: HashMap<OutPoint, u64>,
+    block_source: &Mutex<Box<dyn BlockSource>>,
+  ) -> Result {
+    let start = Instant::now();
+
     log::info!(
       "Committing at block height {}, {} outputs traversed, {} in map, {} cached",
       self.height,
@@ -693,15 +1007,8 @@ impl<'index> Updater<'_> {
         None
       };
 
-      let mut sats = VecDeque::new();
       for (outpoint, sat_range) in self.range_cache.drain() {
-        let old_value = outpoint_to_sat_ranges.insert(&outpoint, sat_range.as_slice())?;
-        if let Some(old_value) = old_value {
-          for chunk in old_value.value().chunks_exact(11) {
-            let range: SatRange = SatRange::load(chunk.try_into().unwrap());
-            sats.push_back(range);
-          }
-        }
+        outpoint_to_sat_ranges.insert(&outpoint, sat_range.as_slice())?;
 
         if let Some(sat_to_outpoint) = sat_to_outpoint.as_mut() {
           for chunk in sat_range.as_slice().chunks_exact(11) {
@@ -713,25 +1020,6 @@ impl<'index> Updater<'_> {
         }
       }
 
-      if !sats.is_empty() {
-        let mut statistic_to_count = wtx.open_table(STATISTIC_TO_COUNT)?;
-        let mut lost_sats = statistic_to_count
-          .get(&Statistic::LostSats.key())?
-          .map(|lost_sats| lost_sats.value())
-          .unwrap_or(0);
-        let mut sat_to_satpoint = wtx.open_table(SAT_TO_SATPOINT)?;
-
-        Self::mark_sats_as_lost(
-          sats,
-          &mut lost_sats,
-          &mut outpoint_to_sat_ranges,
-          &mut sat_to_satpoint,
-          &mut sat_to_outpoint,
-        )?;
-
-        statistic_to_count.insert(&Statistic::LostSats.key(), &lost_sats)?;
-      }
-
       self.outputs_inserted_since_flush = 0;
     }
 
@@ -751,7 +1039,18 @@ impl<'index> Updater<'_> {
     wtx.commit()?;
     log::info!("Commit finished");
 
-    Reorg::update_savepoints(self.index, self.height)?;
+    let (last_commit, last_commit_height) = self.last_commit;
+    self.index.metrics.observe_commit(start.elapsed());
+    self.index.metrics.set_blocks_per_second(
+      (self.height - last_commit_height) as f64 / last_commit.elapsed().as_secs_f64(),
+    );
+    self
+      .index
+      .metrics
+      .set_range_cache_size(self.range_cache.len());
+    self.last_commit = (Instant::now(), self.height);
+
+    Reorg::update_savepoints(self.index, self.height, block_source)?;
 
     Ok(())
   }
@@ -1,4 +1,4 @@
-use {super::*, inscription::Curse};
+use {super::*, bitcoin::hashes::sha256, inscription::Curse};
 
 #[derive(Debug, Clone)]
 pub(super) struct Flotsam {
@@ -10,6 +10,7 @@ pub(super) struct Flotsam {
 #[derive(Debug, Clone)]
 enum Origin {
   New {
+    content_type: Option<String>,
     fee: u64,
     cursed: bool,
     unbound: bool,
@@ -20,17 +21,30 @@ enum Origin {
 }
 
 pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
+  content_hash_to_body: &'a mut Option<Table<'db, 'tx, &'static ContentHashValue, &'static [u8]>>,
+  content_hash_to_id:
+    &'a mut MultimapTable<'db, 'tx, &'static ContentHashValue, &'static InscriptionIdValue>,
+  content_type_and_number: &'a mut Table<'db, 'tx, (&'static [u8], i64), ()>,
   flotsam: Vec<Flotsam>,
   height: u64,
   height_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
   id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
   value_receiver: &'a mut Receiver<u64>,
+  id_to_content: &'a mut Option<
+    Table<
+      'db,
+      'tx,
+      &'static InscriptionIdValue,
+      (Option<&'static [u8]>, Option<&'static ContentHashValue>),
+    >,
+  >,
   id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
   pub(super) lost_sats: u64,
   next_cursed_number: i64,
   next_number: i64,
   number_to_id: &'a mut Table<'db, 'tx, i64, &'static InscriptionIdValue>,
   outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+  rarity_and_number: &'a mut Table<'db, 'tx, (u8, i64), ()>,
   reward: u64,
   reinscription_id_to_seq_num: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, u64>,
   sat_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
@@ -43,14 +57,31 @@ pub(super) struct InscriptionUpdater<'a, 'db, 'tx> {
 
 impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
   pub(super) fn new(
+    content_hash_to_body: &'a mut Option<Table<'db, 'tx, &'static ContentHashValue, &'static [u8]>>,
+    content_hash_to_id: &'a mut MultimapTable<
+      'db,
+      'tx,
+      &'static ContentHashValue,
+      &'static InscriptionIdValue,
+    >,
+    content_type_and_number: &'a mut Table<'db, 'tx, (&'static [u8], i64), ()>,
     height: u64,
     height_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
     id_to_satpoint: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, &'static SatPointValue>,
     value_receiver: &'a mut Receiver<u64>,
+    id_to_content: &'a mut Option<
+      Table<
+        'db,
+        'tx,
+        &'static InscriptionIdValue,
+        (Option<&'static [u8]>, Option<&'static ContentHashValue>),
+      >,
+    >,
     id_to_entry: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, InscriptionEntryValue>,
     lost_sats: u64,
     number_to_id: &'a mut Table<'db, 'tx, i64, &'static InscriptionIdValue>,
     outpoint_to_value: &'a mut Table<'db, 'tx, &'static OutPointValue, u64>,
+    rarity_and_number: &'a mut Table<'db, 'tx, (u8, i64), ()>,
     reinscription_id_to_seq_num: &'a mut Table<'db, 'tx, &'static InscriptionIdValue, u64>,
     sat_to_inscription_id: &'a mut MultimapTable<'db, 'tx, u64, &'static InscriptionIdValue>,
     satpoint_to_id: &'a mut MultimapTable<
@@ -78,17 +109,22 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
       .unwrap_or(0);
 
     Ok(Self {
+      content_hash_to_body,
+      content_hash_to_id,
+      content_type_and_number,
       flotsam: Vec::new(),
       height,
       height_to_inscription_id,
       id_to_satpoint,
       value_receiver,
+      id_to_content,
       id_to_entry,
       lost_sats,
       next_cursed_number,
       next_number,
       number_to_id,
       outpoint_to_value,
+      rarity_and_number,
       reward: Height(height).subsidy(),
       reinscription_id_to_seq_num,
       sat_to_inscription_id,
@@ -228,10 +264,13 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           );
         }
 
+        self.index_content(inscription_id, &inscription.inscription)?;
+
         floating_inscriptions.push(Flotsam {
           inscription_id,
           offset,
           origin: Origin::New {
+            content_type: inscription.inscription.content_type().map(str::to_string),
             fee: 0,
             cursed,
             unbound,
@@ -253,6 +292,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           offset,
           origin:
             Origin::New {
+              content_type,
               fee: _,
               cursed,
               unbound,
@@ -263,6 +303,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
             inscription_id,
             offset,
             origin: Origin::New {
+              content_type,
               fee: (input_value - total_output_value) / u64::from(id_counter),
               cursed,
               unbound,
@@ -342,6 +383,43 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
     }
   }
 
+  /// Records the hash of a new inscription's body, and with
+  /// `--index-content` its content type and the body itself, which is stored
+  /// once per distinct hash.
+  fn index_content(&mut self, inscription_id: InscriptionId, inscription: &Inscription) -> Result {
+    let content_hash = inscription
+      .body()
+      .map(|body| sha256::Hash::hash(body).to_byte_array());
+
+    if let Some(content_hash) = &content_hash {
+      self
+        .content_hash_to_id
+        .insert(content_hash, &inscription_id.store())?;
+    }
+
+    if let (Some(content_hash), Some(body), Some(content_hash_to_body)) = (
+      &content_hash,
+      inscription.body(),
+      &mut self.content_hash_to_body,
+    ) {
+      if content_hash_to_body.get(content_hash)?.is_none() {
+        content_hash_to_body.insert(content_hash, body)?;
+      }
+    }
+
+    if let Some(id_to_content) = &mut self.id_to_content {
+      id_to_content.insert(
+        &inscription_id.store(),
+        (
+          inscription.content_type().map(str::as_bytes),
+          content_hash.as_ref(),
+        ),
+      )?;
+    }
+
+    Ok(())
+  }
+
   fn calculate_sat(
     input_sat_ranges: Option<&VecDeque<(u64, u64)>>,
     input_offset: u64,
@@ -385,6 +463,7 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
         false
       }
       Origin::New {
+        content_type,
         fee,
         cursed,
         unbound,
@@ -403,6 +482,12 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
 
         self.number_to_id.insert(number, &inscription_id)?;
 
+        if let Some(content_type) = content_type {
+          self
+            .content_type_and_number
+            .insert((content_type.as_bytes(), number), ())?;
+        }
+
         let sat = if unbound {
           None
         } else {
@@ -423,6 +508,12 @@ impl<'a, 'db, 'tx> InscriptionUpdater<'a, 'db, 'tx> {
           sat
         };
 
+        if let Some(sat) = sat {
+          self
+            .rarity_and_number
+            .insert((Rarity::from(sat) as u8, number), ())?;
+        }
+
         self.id_to_entry.insert(
           &inscription_id,
           &InscriptionEntry {
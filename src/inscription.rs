@@ -38,7 +38,6 @@ pub(crate) struct TransactionInscription {
 }
 
 impl Inscription {
-  #[cfg(test)]
   pub(crate) fn new(content_type: Option<Vec<u8>>, body: Option<Vec<u8>>) -> Self {
     Self { content_type, body }
   }
@@ -758,8 +757,11 @@ mod tests {
   fn chunked_data_is_parsable() {
     let mut witness = Witness::new();
 
-    witness
-      .push(&inscription("foo", [1; 1040]).append_reveal_script(script::Builder::new(), false), false);
+    witness.push(&inscription("foo", [1; 1040]).append_reveal_script(
+      script::Builder::new(),
+      false,
+      false,
+    ));
 
     witness.push([]);
 
@@ -24,6 +24,7 @@ use {
     inscription::Inscription,
     inscription_id::InscriptionId,
     media::Media,
+    metrics::Metrics,
     options::Options,
     outgoing::Outgoing,
     representation::Representation,
@@ -76,7 +77,8 @@ use {
 };
 
 pub use crate::{
-  fee_rate::FeeRate, object::Object, rarity::Rarity, sat::Sat, sat_point::SatPoint,
+  fee_rate::FeeRate, index::LostCause, object::Object, rarity::Rarity, sat::Sat,
+  sat_point::SatPoint, sat_trait::SatTrait,
   subcommand::wallet::transaction_builder::TransactionBuilder,
 };
 
@@ -111,6 +113,7 @@ mod index;
 mod inscription;
 pub mod inscription_id;
 mod media;
+mod metrics;
 mod object;
 mod options;
 mod outgoing;
@@ -119,6 +122,8 @@ pub mod rarity;
 mod representation;
 pub mod sat;
 mod sat_point;
+pub mod sat_range;
+pub mod sat_trait;
 pub mod subcommand;
 mod tally;
 pub mod templates;
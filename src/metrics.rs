@@ -0,0 +1,322 @@
+use {
+  super::*,
+  bitcoincore_rpc::{
+    jsonrpc::{self, client::Transport, simple_http::SimpleHttpTransport, Request, Response},
+    Auth,
+  },
+  std::{fmt::Write, sync::atomic::AtomicU64},
+};
+
+const BUCKETS: [f64; 11] = [
+  0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+#[derive(Default)]
+struct Histogram {
+  buckets: [u64; BUCKETS.len()],
+  count: u64,
+  sum: f64,
+}
+
+impl Histogram {
+  fn observe(&mut self, seconds: f64) {
+    for (bucket, bound) in self.buckets.iter_mut().zip(BUCKETS) {
+      if seconds <= bound {
+        *bucket += 1;
+      }
+    }
+    self.count += 1;
+    self.sum += seconds;
+  }
+
+  fn render(&self, out: &mut String, name: &str, labels: &str) {
+    let separator = if labels.is_empty() { "" } else { "," };
+    for (bucket, bound) in self.buckets.iter().zip(BUCKETS) {
+      writeln!(
+        out,
+        "{name}_bucket{{{labels}{separator}le=\"{bound}\"}} {bucket}"
+      )
+      .unwrap();
+    }
+    writeln!(
+      out,
+      "{name}_bucket{{{labels}{separator}le=\"+Inf\"}} {}",
+      self.count
+    )
+    .unwrap();
+    let labels = if labels.is_empty() {
+      String::new()
+    } else {
+      format!("{{{labels}}}")
+    };
+    writeln!(out, "{name}_sum{labels} {}", self.sum).unwrap();
+    writeln!(out, "{name}_count{labels} {}", self.count).unwrap();
+  }
+}
+
+/// Counters and gauges exposed on `/metrics` in the Prometheus text format.
+#[derive(Default)]
+pub(crate) struct Metrics {
+  bitcoind_height: AtomicU64,
+  blocks_per_second: AtomicU64,
+  commit_duration: Mutex<Histogram>,
+  range_cache_size: AtomicU64,
+  request_duration: Mutex<BTreeMap<String, Histogram>>,
+  requests: Mutex<BTreeMap<(String, u16), u64>>,
+  rpc_calls: Mutex<BTreeMap<String, u64>>,
+}
+
+impl Metrics {
+  pub(crate) fn set_bitcoind_height(&self, height: u64) {
+    self
+      .bitcoind_height
+      .store(height, atomic::Ordering::Relaxed);
+  }
+
+  pub(crate) fn set_blocks_per_second(&self, blocks_per_second: f64) {
+    self
+      .blocks_per_second
+      .store(blocks_per_second.to_bits(), atomic::Ordering::Relaxed);
+  }
+
+  pub(crate) fn set_range_cache_size(&self, size: usize) {
+    self.range_cache_size.store(
+      size.try_into().unwrap_or(u64::MAX),
+      atomic::Ordering::Relaxed,
+    );
+  }
+
+  pub(crate) fn observe_commit(&self, duration: Duration) {
+    self
+      .commit_duration
+      .lock()
+      .unwrap()
+      .observe(duration.as_secs_f64());
+  }
+
+  pub(crate) fn observe_request(&self, route: &str, status: u16, duration: Duration) {
+    *self
+      .requests
+      .lock()
+      .unwrap()
+      .entry((route.into(), status))
+      .or_default() += 1;
+
+    self
+      .request_duration
+      .lock()
+      .unwrap()
+      .entry(route.into())
+      .or_default()
+      .observe(duration.as_secs_f64());
+  }
+
+  pub(crate) fn increment_rpc_calls(&self, method: &str, n: u64) {
+    *self
+      .rpc_calls
+      .lock()
+      .unwrap()
+      .entry(method.into())
+      .or_default() += n;
+  }
+
+  /// Connect to Bitcoin Core with a transport that counts calls by method.
+  pub(crate) fn rpc_client(
+    self: Arc<Self>,
+    url: &str,
+    auth: Auth,
+  ) -> bitcoincore_rpc::Result<Client> {
+    let (user, pass) = auth.get_user_pass()?;
+
+    let mut builder = SimpleHttpTransport::builder()
+      .url(url)
+      .map_err(|err| bitcoincore_rpc::Error::JsonRpc(err.into()))?;
+
+    if let Some(user) = user {
+      builder = builder.auth(user, pass);
+    }
+
+    Ok(Client::from_jsonrpc(jsonrpc::Client::with_transport(
+      CountingTransport {
+        inner: builder.build(),
+        metrics: self,
+      },
+    )))
+  }
+
+  pub(crate) fn render(&self, index_height: Option<u64>, statistics: &[(&str, u64)]) -> String {
+    let mut out = String::new();
+
+    let mut gauge = |name: &str, help: &str, value: &dyn Display| {
+      writeln!(out, "# HELP {name} {help}").unwrap();
+      writeln!(out, "# TYPE {name} gauge").unwrap();
+      writeln!(out, "{name} {value}").unwrap();
+    };
+
+    if let Some(index_height) = index_height {
+      gauge(
+        "ord_index_height",
+        "Height of the last indexed block.",
+        &index_height,
+      );
+    }
+
+    gauge(
+      "ord_bitcoind_height",
+      "Block count reported by Bitcoin Core.",
+      &self.bitcoind_height.load(atomic::Ordering::Relaxed),
+    );
+
+    gauge(
+      "ord_index_blocks_per_second",
+      "Blocks indexed per second between the last two commits.",
+      &f64::from_bits(self.blocks_per_second.load(atomic::Ordering::Relaxed)),
+    );
+
+    gauge(
+      "ord_index_range_cache_size",
+      "Outputs held in the updater's sat range cache.",
+      &self.range_cache_size.load(atomic::Ordering::Relaxed),
+    );
+
+    writeln!(
+      out,
+      "# HELP ord_index_statistic Counters stored in the index."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE ord_index_statistic gauge").unwrap();
+    for (name, value) in statistics {
+      writeln!(out, "ord_index_statistic{{statistic=\"{name}\"}} {value}").unwrap();
+    }
+
+    writeln!(
+      out,
+      "# HELP ord_index_commit_duration_seconds Time spent committing index writes."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE ord_index_commit_duration_seconds histogram").unwrap();
+    self
+      .commit_duration
+      .lock()
+      .unwrap()
+      .render(&mut out, "ord_index_commit_duration_seconds", "");
+
+    writeln!(
+      out,
+      "# HELP ord_http_requests_total HTTP requests by route and status."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE ord_http_requests_total counter").unwrap();
+    for ((route, status), count) in self.requests.lock().unwrap().iter() {
+      writeln!(
+        out,
+        "ord_http_requests_total{{route=\"{route}\",status=\"{status}\"}} {count}"
+      )
+      .unwrap();
+    }
+
+    writeln!(
+      out,
+      "# HELP ord_http_request_duration_seconds HTTP request latency by route."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE ord_http_request_duration_seconds histogram").unwrap();
+    for (route, histogram) in self.request_duration.lock().unwrap().iter() {
+      histogram.render(
+        &mut out,
+        "ord_http_request_duration_seconds",
+        &format!("route=\"{route}\""),
+      );
+    }
+
+    writeln!(
+      out,
+      "# HELP ord_rpc_calls_total Bitcoin Core RPC calls by method."
+    )
+    .unwrap();
+    writeln!(out, "# TYPE ord_rpc_calls_total counter").unwrap();
+    for (method, count) in self.rpc_calls.lock().unwrap().iter() {
+      writeln!(out, "ord_rpc_calls_total{{method=\"{method}\"}} {count}").unwrap();
+    }
+
+    out
+  }
+}
+
+struct CountingTransport {
+  inner: SimpleHttpTransport,
+  metrics: Arc<Metrics>,
+}
+
+impl Transport for CountingTransport {
+  fn send_request(&self, request: Request) -> Result<Response, jsonrpc::Error> {
+    self.metrics.increment_rpc_calls(request.method, 1);
+    self.inner.send_request(request)
+  }
+
+  fn send_batch(&self, requests: &[Request]) -> Result<Vec<Response>, jsonrpc::Error> {
+    for request in requests {
+      self.metrics.increment_rpc_calls(request.method, 1);
+    }
+    self.inner.send_batch(requests)
+  }
+
+  fn fmt_target(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.inner.fmt_target(f)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn histogram_buckets_are_cumulative() {
+    let mut histogram = Histogram::default();
+    histogram.observe(0.003);
+    histogram.observe(0.2);
+    histogram.observe(20.0);
+
+    let mut out = String::new();
+    histogram.render(&mut out, "latency", "route=\"/\"");
+
+    assert!(out.contains("latency_bucket{route=\"/\",le=\"0.005\"} 1\n"));
+    assert!(out.contains("latency_bucket{route=\"/\",le=\"0.25\"} 2\n"));
+    assert!(out.contains("latency_bucket{route=\"/\",le=\"10\"} 2\n"));
+    assert!(out.contains("latency_bucket{route=\"/\",le=\"+Inf\"} 3\n"));
+    assert!(out.contains("latency_count{route=\"/\"} 3\n"));
+  }
+
+  #[test]
+  fn render() {
+    let metrics = Metrics::default();
+    metrics.set_bitcoind_height(10);
+    metrics.set_blocks_per_second(2.5);
+    metrics.set_range_cache_size(7);
+    metrics.observe_commit(Duration::from_millis(30));
+    metrics.observe_request("/sat/:sat", 200, Duration::from_millis(1));
+    metrics.observe_request("/sat/:sat", 200, Duration::from_millis(1));
+    metrics.observe_request("/sat/:sat", 400, Duration::from_millis(1));
+    metrics.increment_rpc_calls("getblock", 3);
+
+    let out = metrics.render(Some(9), &[("commits", 4)]);
+
+    for line in [
+      "ord_index_height 9",
+      "ord_bitcoind_height 10",
+      "ord_index_blocks_per_second 2.5",
+      "ord_index_range_cache_size 7",
+      "ord_index_statistic{statistic=\"commits\"} 4",
+      "ord_index_commit_duration_seconds_count 1",
+      "ord_http_requests_total{route=\"/sat/:sat\",status=\"200\"} 2",
+      "ord_http_requests_total{route=\"/sat/:sat\",status=\"400\"} 1",
+      "ord_http_request_duration_seconds_count{route=\"/sat/:sat\"} 3",
+      "ord_rpc_calls_total{method=\"getblock\"} 3",
+    ] {
+      assert!(
+        out.lines().any(|l| l == line),
+        "missing `{line}` in:\n{out}"
+      );
+    }
+  }
+}
@@ -1,4 +1,4 @@
-use {super::*, bitcoincore_rpc::Auth};
+use {super::*, crate::index::block_source::BlockSourceKind, bitcoincore_rpc::Auth};
 
 #[derive(Clone, Default, Debug, Parser)]
 #[clap(group(
@@ -13,6 +13,18 @@ pub(crate) struct Options {
   pub(crate) bitcoin_rpc_pass: Option<String>,
   #[clap(long, help = "Authenticate to Bitcoin Core RPC as <RPC_USER>.")]
   pub(crate) bitcoin_rpc_user: Option<String>,
+  #[clap(
+    long,
+    arg_enum,
+    default_value = "rpc",
+    help = "Fetch blocks from <BLOCK_SOURCE>. `rest` uses Bitcoin Core's REST interface at the RPC URL, `blk-files` reads the blk*.dat files in its data dir, falling back to RPC for blocks not yet written to disk, and `file` reads the blocks in `--blocks-file` without connecting to Bitcoin Core."
+  )]
+  pub(crate) block_source: BlockSourceKind,
+  #[clap(
+    long,
+    help = "Read blocks for `--block-source file` from <BLOCKS_FILE>, which contains consensus-serialized blocks starting at genesis, one after the other."
+  )]
+  pub(crate) blocks_file: Option<PathBuf>,
   #[clap(
     long = "chain",
     arg_enum,
@@ -48,8 +60,22 @@ pub(crate) struct Options {
   pub(crate) height_limit: Option<u64>,
   #[clap(long, help = "Use index at <INDEX>.")]
   pub(crate) index: Option<PathBuf>,
+  #[clap(long, help = "Track the unspent outputs of every address.")]
+  pub(crate) index_addresses: bool,
+  #[clap(long, help = "Store inscription content in the index.")]
+  pub(crate) index_content: bool,
+  #[clap(
+    long,
+    help = "Track every output each sat passes through. Implies --index-sats."
+  )]
+  pub(crate) index_sat_history: bool,
   #[clap(long, help = "Track location of all satoshis.")]
   pub(crate) index_sats: bool,
+  #[clap(
+    long,
+    help = "Track the location of sats with each trait. Implies --index-sats."
+  )]
+  pub(crate) index_traits: bool,
   #[clap(
     long,
     help = "Track location of all satoshis and the utxos that own them. Implies --index-sats."
@@ -120,26 +146,41 @@ impl Options {
     }
   }
 
+  pub(crate) fn rest_url(&self) -> String {
+    let url = match &self.rpc_url {
+      Some(rpc_url) => rpc_url.clone(),
+      None => format!("127.0.0.1:{}", self.chain().default_rpc_port()),
+    };
+
+    if url.starts_with("http://") {
+      url
+    } else {
+      format!("http://{url}")
+    }
+  }
+
   pub(crate) fn cookie_file(&self) -> Result<PathBuf> {
     if let Some(cookie_file) = &self.cookie_file {
       return Ok(cookie_file.clone());
     }
 
+    Ok(self.bitcoin_data_dir()?.join(".cookie"))
+  }
+
+  pub(crate) fn bitcoin_data_dir(&self) -> Result<PathBuf> {
     let path = if let Some(bitcoin_data_dir) = &self.bitcoin_data_dir {
       bitcoin_data_dir.clone()
     } else if cfg!(target_os = "linux") {
       dirs::home_dir()
-        .ok_or_else(|| anyhow!("failed to get cookie file path: could not get home dir"))?
+        .ok_or_else(|| anyhow!("failed to get bitcoin data dir: could not get home dir"))?
         .join(".bitcoin")
     } else {
       dirs::data_dir()
-        .ok_or_else(|| anyhow!("failed to get cookie file path: could not get data dir"))?
+        .ok_or_else(|| anyhow!("failed to get bitcoin data dir: could not get data dir"))?
         .join("Bitcoin")
     };
 
-    let path = self.chain().join_with_data_dir(&path);
-
-    Ok(path.join(".cookie"))
+    Ok(self.chain().join_with_data_dir(&path))
   }
 
   pub(crate) fn data_dir(&self) -> Result<PathBuf> {
@@ -153,15 +194,21 @@ impl Options {
     Ok(self.chain().join_with_data_dir(&base))
   }
 
-  pub(crate) fn load_config(&self) -> Result<Config> {
+  pub(crate) fn config_path(&self) -> Option<PathBuf> {
     match &self.config {
+      Some(path) => Some(path.clone()),
+      None => self
+        .config_dir
+        .as_ref()
+        .map(|dir| dir.join("ord.yaml"))
+        .filter(|path| path.exists()),
+    }
+  }
+
+  pub(crate) fn load_config(&self) -> Result<Config> {
+    match self.config_path() {
       Some(path) => Ok(serde_yaml::from_reader(File::open(path)?)?),
-      None => match &self.config_dir {
-        Some(dir) if dir.join("ord.yaml").exists() => {
-          Ok(serde_yaml::from_reader(File::open(dir.join("ord.yaml"))?)?)
-        }
-        Some(_) | None => Ok(Default::default()),
-      },
+      None => Ok(Default::default()),
     }
   }
 
@@ -224,6 +271,13 @@ impl Options {
   }
 
   pub(crate) fn bitcoin_rpc_client(&self) -> Result<Client> {
+    self.bitcoin_rpc_client_with_metrics(None)
+  }
+
+  pub(crate) fn bitcoin_rpc_client_with_metrics(
+    &self,
+    metrics: Option<Arc<Metrics>>,
+  ) -> Result<Client> {
     let rpc_url = self.rpc_url();
 
     let auth = self.auth()?;
@@ -237,8 +291,11 @@ impl Options {
       );
     }
 
-    let client = Client::new(&rpc_url, auth)
-      .with_context(|| format!("failed to connect to Bitcoin Core RPC at {rpc_url}"))?;
+    let client = match metrics {
+      Some(metrics) => metrics.rpc_client(&rpc_url, auth),
+      None => Client::new(&rpc_url, auth),
+    }
+    .with_context(|| format!("failed to connect to Bitcoin Core RPC at {rpc_url}"))?;
 
     let rpc_chain = match client.get_blockchain_info()?.chain.as_str() {
       "main" => Chain::Mainnet,
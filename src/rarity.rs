@@ -1,6 +1,6 @@
 use super::*;
 
-#[derive(Debug, PartialEq, PartialOrd)]
+#[derive(Debug, PartialEq, PartialOrd, Copy, Clone)]
 pub enum Rarity {
   Common,
   Uncommon,
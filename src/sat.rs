@@ -82,11 +82,11 @@ impl Sat {
         'a'..='z' => {
           x = x * 26 + c as u64 - 'a' as u64 + 1;
         }
-        _ => bail!("invalid character in sat name: {c}"),
+        _ => bail!("invalid character `{c}` in sat name `{s}`"),
       }
     }
     if x > Self::SUPPLY {
-      bail!("sat name out of range");
+      bail!("sat name `{s}` out of range");
     }
     Ok(Sat(Self::SUPPLY - x))
   }
@@ -95,22 +95,28 @@ impl Sat {
     let (cycle_number, rest) = degree
       .split_once('°')
       .ok_or_else(|| anyhow!("missing degree symbol"))?;
-    let cycle_number = cycle_number.parse::<u64>()?;
+    let cycle_number = cycle_number
+      .parse::<u64>()
+      .with_context(|| format!("invalid cycle number `{cycle_number}`"))?;
 
     let (epoch_offset, rest) = rest
       .split_once('′')
       .ok_or_else(|| anyhow!("missing minute symbol"))?;
-    let epoch_offset = epoch_offset.parse::<u64>()?;
+    let epoch_offset = epoch_offset
+      .parse::<u64>()
+      .with_context(|| format!("invalid epoch offset `{epoch_offset}`"))?;
     if epoch_offset >= SUBSIDY_HALVING_INTERVAL {
-      bail!("invalid epoch offset");
+      bail!("invalid epoch offset {epoch_offset}: must be less than {SUBSIDY_HALVING_INTERVAL}");
     }
 
     let (period_offset, rest) = rest
       .split_once('″')
       .ok_or_else(|| anyhow!("missing second symbol"))?;
-    let period_offset = period_offset.parse::<u64>()?;
+    let period_offset = period_offset
+      .parse::<u64>()
+      .with_context(|| format!("invalid period offset `{period_offset}`"))?;
     if period_offset >= DIFFCHANGE_INTERVAL {
-      bail!("invalid period offset");
+      bail!("invalid period offset {period_offset}: must be less than {DIFFCHANGE_INTERVAL}");
     }
 
     let cycle_start_epoch = cycle_number * CYCLE_EPOCHS;
@@ -122,7 +128,9 @@ impl Sat {
     let relationship = period_offset + SUBSIDY_HALVING_INTERVAL * CYCLE_EPOCHS - epoch_offset;
 
     if relationship % HALVING_INCREMENT != 0 {
-      bail!("relationship between epoch offset and period offset must be multiple of 336");
+      bail!(
+        "relationship between epoch offset {epoch_offset} and period offset {period_offset} must be multiple of {HALVING_INCREMENT}"
+      );
     }
 
     let epochs_since_cycle_start = relationship % DIFFCHANGE_INTERVAL / HALVING_INCREMENT;
@@ -132,16 +140,24 @@ impl Sat {
     let height = Height(epoch * SUBSIDY_HALVING_INTERVAL + epoch_offset);
 
     let (block_offset, rest) = match rest.split_once('‴') {
-      Some((block_offset, rest)) => (block_offset.parse::<u64>()?, rest),
+      Some((block_offset, rest)) => (
+        block_offset
+          .parse::<u64>()
+          .with_context(|| format!("invalid block offset `{block_offset}`"))?,
+        rest,
+      ),
       None => (0, rest),
     };
 
     if !rest.is_empty() {
-      bail!("trailing characters");
+      bail!("trailing characters `{rest}`");
     }
 
     if block_offset >= height.subsidy() {
-      bail!("invalid block offset");
+      bail!(
+        "invalid block offset {block_offset}: block {height} has {} sats",
+        height.subsidy()
+      );
     }
 
     Ok(height.starting_sat() + block_offset)
@@ -151,11 +167,20 @@ impl Sat {
     let (height, offset) = decimal
       .split_once('.')
       .ok_or_else(|| anyhow!("missing period"))?;
-    let height = Height(height.parse()?);
-    let offset = offset.parse::<u64>()?;
+    let height = Height(
+      height
+        .parse()
+        .with_context(|| format!("invalid block height `{height}`"))?,
+    );
+    let offset = offset
+      .parse::<u64>()
+      .with_context(|| format!("invalid block offset `{offset}`"))?;
 
     if offset >= height.subsidy() {
-      bail!("invalid block offset");
+      bail!(
+        "invalid block offset {offset}: block {height} has {} sats",
+        height.subsidy()
+      );
     }
 
     Ok(height.starting_sat() + offset)
@@ -181,7 +206,17 @@ impl Sat {
     }
 
     #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
-    Ok(Sat(n as u64))
+    let n = n as u64;
+
+    // Rounding can land one sat away from the sat whose percentile was
+    // printed, so prefer a neighbour that formats to the same value.
+    Ok(
+      [n, n.saturating_sub(1), (n + 1).min(Sat::LAST.n())]
+        .into_iter()
+        .map(Sat)
+        .find(|sat| sat.percentile().trim_end_matches('%').parse::<f64>() == Ok(percentile))
+        .unwrap_or(Sat(n)),
+    )
   }
 }
 
@@ -588,6 +623,34 @@ mod tests {
     "101%".parse::<Sat>().unwrap_err();
   }
 
+  #[test]
+  fn fractional_percentile_round_trip() {
+    for percentile in ["12.5%", "33.333333%", "0.0000000001%", "99.99999999%"] {
+      let sat = percentile.parse::<Sat>().unwrap();
+      assert_eq!(sat.percentile().parse::<Sat>().unwrap(), sat);
+    }
+  }
+
+  #[test]
+  fn degree_errors() {
+    assert_eq!(
+      "0°1′0″".parse::<Sat>().unwrap_err().to_string(),
+      "relationship between epoch offset 1 and period offset 0 must be multiple of 336"
+    );
+    assert_eq!(
+      "0°210000′0″0‴".parse::<Sat>().unwrap_err().to_string(),
+      "invalid epoch offset 210000: must be less than 210000"
+    );
+    assert_eq!(
+      "0°0′0″5000000000‴".parse::<Sat>().unwrap_err().to_string(),
+      "invalid block offset 5000000000: block 0 has 5000000000 sats"
+    );
+    assert_eq!(
+      "0°0′0″0‴0".parse::<Sat>().unwrap_err().to_string(),
+      "trailing characters `0`"
+    );
+  }
+
   #[test]
   fn percentile_round_trip() {
     fn case(n: u64) {
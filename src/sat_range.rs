@@ -0,0 +1,348 @@
+use super::*;
+
+/// A half-open range of sats, `start..end`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct SatRange {
+  pub start: u64,
+  pub end: u64,
+}
+
+impl SatRange {
+  pub fn new(start: u64, end: u64) -> Self {
+    assert!(
+      start <= end,
+      "sat range {start}-{end} ends before it starts"
+    );
+    Self { start, end }
+  }
+
+  pub fn size(self) -> u64 {
+    self.end - self.start
+  }
+
+  pub fn is_empty(self) -> bool {
+    self.start == self.end
+  }
+
+  pub fn contains(self, sat: Sat) -> bool {
+    (self.start..self.end).contains(&sat.n())
+  }
+
+  pub fn intersection(self, other: Self) -> Option<Self> {
+    let start = self.start.max(other.start);
+    let end = self.end.min(other.end);
+    (start < end).then_some(Self { start, end })
+  }
+
+  /// Split into the first `offset` sats and the rest. Either half may be
+  /// empty.
+  pub fn split_at(self, offset: u64) -> (Self, Self) {
+    let middle = self.start + offset.min(self.size());
+    (Self::new(self.start, middle), Self::new(middle, self.end))
+  }
+}
+
+impl From<(u64, u64)> for SatRange {
+  fn from((start, end): (u64, u64)) -> Self {
+    Self::new(start, end)
+  }
+}
+
+impl Display for SatRange {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    write!(f, "{}-{}", self.start, self.end)
+  }
+}
+
+impl FromStr for SatRange {
+  type Err = Error;
+
+  /// Parses `<START>-<END>`, with either side in any sat notation, or a
+  /// single sat. An en dash may be used instead of a hyphen.
+  fn from_str(s: &str) -> Result<Self> {
+    let mut split = s.trim().split(&['-', '\u{2013}']);
+
+    let start = split.next().unwrap_or_default().trim().parse::<Sat>()?.n();
+
+    let end = match split.next() {
+      Some(end) => {
+        let end = end.trim();
+        match end.parse::<u64>() {
+          Ok(end) if end <= Sat::SUPPLY => end,
+          _ => end.parse::<Sat>()?.n(),
+        }
+      }
+      None => start + 1,
+    };
+
+    if split.next().is_some() {
+      bail!("invalid sat range `{s}`: trailing junk");
+    }
+
+    if end < start {
+      bail!("invalid sat range `{s}`: end before start");
+    }
+
+    Ok(Self::new(start, end))
+  }
+}
+
+/// A set of sats, stored as sorted, disjoint, non-adjacent ranges.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SatRangeSet(Vec<SatRange>);
+
+impl SatRangeSet {
+  pub fn ranges(&self) -> &[SatRange] {
+    &self.0
+  }
+
+  pub fn size(&self) -> u64 {
+    self.0.iter().map(|range| range.size()).sum()
+  }
+
+  pub fn is_empty(&self) -> bool {
+    self.0.is_empty()
+  }
+
+  pub fn contains(&self, sat: Sat) -> bool {
+    let i = self.0.partition_point(|range| range.end <= sat.n());
+    self.0.get(i).map_or(false, |range| range.contains(sat))
+  }
+
+  pub fn union(&self, other: &Self) -> Self {
+    self.0.iter().chain(&other.0).copied().collect()
+  }
+
+  pub fn intersection(&self, other: &Self) -> Self {
+    let mut ranges = Vec::new();
+
+    let (mut i, mut j) = (0, 0);
+    while let (Some(a), Some(b)) = (self.0.get(i), other.0.get(j)) {
+      ranges.extend(a.intersection(*b));
+
+      if a.end < b.end {
+        i += 1;
+      } else {
+        j += 1;
+      }
+    }
+
+    Self(ranges)
+  }
+
+  pub fn difference(&self, other: &Self) -> Self {
+    let mut ranges = Vec::new();
+
+    let mut j = 0;
+    for a in &self.0 {
+      while other.0.get(j).map_or(false, |b| b.end <= a.start) {
+        j += 1;
+      }
+
+      let mut start = a.start;
+      for b in other.0[j..].iter().take_while(|b| b.start < a.end) {
+        if b.start > start {
+          ranges.push(SatRange::new(start, b.start));
+        }
+        start = start.max(b.end);
+      }
+
+      if start < a.end {
+        ranges.push(SatRange::new(start, a.end));
+      }
+    }
+
+    Self(ranges)
+  }
+
+  /// Split into the first `offset` sats, in sat order, and the rest.
+  pub fn split_at(&self, offset: u64) -> (Self, Self) {
+    let (before, after) = split_ranges_at(&self.0, offset);
+    (Self(before), Self(after))
+  }
+
+  /// The parts of this set found in an output holding `output` ranges, in
+  /// output order, each with its offset into the output.
+  pub fn locate(&self, output: &[SatRange]) -> Vec<(u64, SatRange)> {
+    let mut located = Vec::new();
+
+    let mut offset = 0;
+    for range in output {
+      let i = self.0.partition_point(|ours| ours.end <= range.start);
+      for ours in self.0[i..].iter().take_while(|ours| ours.start < range.end) {
+        if let Some(overlap) = ours.intersection(*range) {
+          located.push((offset + overlap.start - range.start, overlap));
+        }
+      }
+      offset += range.size();
+    }
+
+    located
+  }
+}
+
+/// Split `ranges`, in the order given, into the first `offset` sats and the
+/// rest. Empty pieces are dropped.
+pub fn split_ranges_at(ranges: &[SatRange], offset: u64) -> (Vec<SatRange>, Vec<SatRange>) {
+  let mut before = Vec::new();
+  let mut after = Vec::new();
+
+  let mut remaining = offset;
+  for range in ranges {
+    let (head, tail) = range.split_at(remaining);
+    remaining -= head.size();
+    before.extend((!head.is_empty()).then_some(head));
+    after.extend((!tail.is_empty()).then_some(tail));
+  }
+
+  (before, after)
+}
+
+impl FromIterator<SatRange> for SatRangeSet {
+  fn from_iter<I: IntoIterator<Item = SatRange>>(iter: I) -> Self {
+    let mut ranges = iter
+      .into_iter()
+      .filter(|range| !range.is_empty())
+      .collect::<Vec<SatRange>>();
+
+    ranges.sort();
+
+    let mut merged: Vec<SatRange> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+      match merged.last_mut() {
+        Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+        _ => merged.push(range),
+      }
+    }
+
+    Self(merged)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn set(ranges: &[(u64, u64)]) -> SatRangeSet {
+    ranges.iter().copied().map(SatRange::from).collect()
+  }
+
+  #[test]
+  fn parse() {
+    assert_eq!("0-10".parse::<SatRange>().unwrap(), SatRange::new(0, 10));
+    assert_eq!("5".parse::<SatRange>().unwrap(), SatRange::new(5, 6));
+    assert_eq!(
+      "1.0 \u{2013} 2.0".parse::<SatRange>().unwrap(),
+      SatRange::new(50 * COIN_VALUE, 100 * COIN_VALUE)
+    );
+    assert_eq!(
+      format!("0-{}", Sat::SUPPLY).parse::<SatRange>().unwrap(),
+      SatRange::new(0, Sat::SUPPLY)
+    );
+    assert_eq!(
+      "10-5".parse::<SatRange>().unwrap_err().to_string(),
+      "invalid sat range `10-5`: end before start"
+    );
+    assert_eq!(
+      "1-2-3".parse::<SatRange>().unwrap_err().to_string(),
+      "invalid sat range `1-2-3`: trailing junk"
+    );
+    assert!("foo-1".parse::<SatRange>().is_err());
+  }
+
+  #[test]
+  fn display_round_trips() {
+    let range = SatRange::new(3, 7);
+    assert_eq!(range.to_string().parse::<SatRange>().unwrap(), range);
+  }
+
+  #[test]
+  fn range_split_at() {
+    let range = SatRange::new(10, 20);
+    assert_eq!(
+      range.split_at(3),
+      (SatRange::new(10, 13), SatRange::new(13, 20))
+    );
+    assert_eq!(
+      range.split_at(30),
+      (SatRange::new(10, 20), SatRange::new(20, 20))
+    );
+  }
+
+  #[test]
+  fn sets_are_normalized() {
+    assert_eq!(
+      set(&[(10, 20), (0, 5), (5, 7), (15, 25), (30, 30)]).ranges(),
+      [SatRange::new(0, 7), SatRange::new(10, 25)]
+    );
+  }
+
+  #[test]
+  fn contains() {
+    let set = set(&[(0, 5), (10, 20)]);
+    assert!(set.contains(Sat(0)));
+    assert!(set.contains(Sat(19)));
+    assert!(!set.contains(Sat(5)));
+    assert!(!set.contains(Sat(20)));
+  }
+
+  #[test]
+  fn union() {
+    assert_eq!(
+      set(&[(0, 5), (20, 30)]).union(&set(&[(5, 10), (25, 40)])),
+      set(&[(0, 10), (20, 40)])
+    );
+  }
+
+  #[test]
+  fn intersection() {
+    assert_eq!(
+      set(&[(0, 10), (20, 30)]).intersection(&set(&[(5, 25), (28, 29), (40, 50)])),
+      set(&[(5, 10), (20, 25), (28, 29)])
+    );
+    assert!(set(&[(0, 10)]).intersection(&set(&[(10, 20)])).is_empty());
+  }
+
+  #[test]
+  fn difference() {
+    assert_eq!(
+      set(&[(0, 10), (20, 30)]).difference(&set(&[(2, 4), (6, 22), (25, 26)])),
+      set(&[(0, 2), (4, 6), (22, 25), (26, 30)])
+    );
+    assert_eq!(
+      set(&[(0, 10)]).difference(&set(&[(0, 10)])),
+      SatRangeSet::default()
+    );
+  }
+
+  #[test]
+  fn set_split_at() {
+    let (before, after) = set(&[(0, 5), (10, 20)]).split_at(8);
+    assert_eq!(before, set(&[(0, 5), (10, 13)]));
+    assert_eq!(after, set(&[(13, 20)]));
+    assert_eq!(before.size() + after.size(), 15);
+  }
+
+  #[test]
+  fn split_ranges_at_keeps_order() {
+    assert_eq!(
+      split_ranges_at(&[SatRange::new(10, 20), SatRange::new(0, 5)], 12),
+      (
+        vec![SatRange::new(10, 20), SatRange::new(0, 2)],
+        vec![SatRange::new(2, 5)]
+      )
+    );
+  }
+
+  #[test]
+  fn locate() {
+    assert_eq!(
+      set(&[(3, 6), (100, 200)]).locate(&[
+        SatRange::new(150, 160),
+        SatRange::new(0, 10),
+        SatRange::new(50, 60),
+      ]),
+      [(0, SatRange::new(150, 160)), (13, SatRange::new(3, 6)),]
+    );
+  }
+}
@@ -0,0 +1,395 @@
+use {super::*, std::iter};
+
+/// Traits sat hunters look for beyond `Rarity`. Most are computed from the
+/// sat's number, height and offset. Pizza, hitman and Nakamoto sats depend on
+/// transaction history and are matched against the `trait_ranges` configured
+/// in `ord.yaml`, which can also extend any other trait.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SatTrait {
+  Alpha,
+  Block9,
+  Block78,
+  FirstInBlock,
+  Hitman,
+  LastInBlock,
+  Nakamoto,
+  Omega,
+  Palindrome,
+  Pizza,
+  RoundNumber,
+  Vintage,
+}
+
+pub(crate) type TraitRanges = BTreeMap<SatTrait, Vec<(u64, u64)>>;
+
+impl SatTrait {
+  pub(crate) const ALL: [Self; 12] = [
+    Self::Alpha,
+    Self::Block9,
+    Self::Block78,
+    Self::FirstInBlock,
+    Self::Hitman,
+    Self::LastInBlock,
+    Self::Nakamoto,
+    Self::Omega,
+    Self::Palindrome,
+    Self::Pizza,
+    Self::RoundNumber,
+    Self::Vintage,
+  ];
+
+  pub(crate) fn of(sat: Sat, ranges: &TraitRanges) -> Vec<Self> {
+    Self::ALL
+      .into_iter()
+      .filter(|sat_trait| {
+        sat_trait.is_computed_for(sat)
+          || ranges.get(sat_trait).map_or(false, |ranges| {
+            ranges
+              .iter()
+              .any(|(start, end)| (*start..*end).contains(&sat.n()))
+          })
+      })
+      .collect()
+  }
+
+  /// The first sat in `start..end` with this trait, if any.
+  pub(crate) fn first_in(self, start: u64, end: u64, ranges: &TraitRanges) -> Option<u64> {
+    let overlap = |from: u64, to: u64| (from < end && to > start).then(|| from.max(start));
+
+    let computed = match self {
+      Self::Alpha => Some((start + COIN_VALUE - 1) / COIN_VALUE * COIN_VALUE),
+      Self::Block9 | Self::Block78 | Self::Vintage => {
+        let (from, to) = self.computed_range().unwrap();
+        overlap(from, to)
+      }
+      Self::FirstInBlock => {
+        let sat = Sat(start);
+        if sat.third() == 0 {
+          Some(start)
+        } else {
+          Some(Height(sat.height().n() + 1).starting_sat().n())
+        }
+      }
+      Self::LastInBlock => Some(Height(Sat(start).height().n() + 1).starting_sat().n() - 1),
+      Self::Omega => Some((start + COIN_VALUE) / COIN_VALUE * COIN_VALUE - 1),
+      Self::Palindrome => Some(next_palindrome(start)),
+      Self::RoundNumber => Some(next_round_number(start)),
+      Self::Hitman | Self::Nakamoto | Self::Pizza => None,
+    };
+
+    let configured = ranges
+      .get(&self)
+      .into_iter()
+      .flatten()
+      .filter_map(|(from, to)| overlap(*from, *to))
+      .min();
+
+    computed
+      .into_iter()
+      .chain(configured)
+      .filter(|n| (start..end).contains(n))
+      .min()
+  }
+
+  /// The sats in `start..end` with this trait, as `(start, end)` runs. Sats
+  /// in a block or configured range with this trait form a single run, and
+  /// all others are returned one by one.
+  pub(crate) fn runs_in(
+    self,
+    start: u64,
+    end: u64,
+    ranges: &TraitRanges,
+  ) -> impl Iterator<Item = (u64, u64)> + '_ {
+    let mut next = start;
+    iter::from_fn(move || {
+      let first = self.first_in(next, end, ranges)?;
+      next = self.run_end(first, end, ranges);
+      Some((first, next))
+    })
+  }
+
+  /// The end of the run of sats with this trait starting at `sat`, capped at
+  /// `end`.
+  fn run_end(self, sat: u64, end: u64, ranges: &TraitRanges) -> u64 {
+    let mut run_end = sat + 1;
+
+    while let Some(to) = self
+      .computed_range()
+      .into_iter()
+      .chain(ranges.get(&self).into_iter().flatten().copied())
+      .filter(|(from, to)| (*from..*to).contains(&run_end))
+      .map(|(_, to)| to)
+      .max()
+    {
+      run_end = to;
+    }
+
+    run_end.min(end)
+  }
+
+  fn computed_range(self) -> Option<(u64, u64)> {
+    match self {
+      Self::Block9 => Some((Height(9).starting_sat().n(), Height(10).starting_sat().n())),
+      Self::Block78 => Some((Height(78).starting_sat().n(), Height(79).starting_sat().n())),
+      Self::Vintage => Some((0, Height(1000).starting_sat().n())),
+      _ => None,
+    }
+  }
+
+  pub(crate) fn key(self) -> u8 {
+    self as u8
+  }
+
+  fn is_computed_for(self, sat: Sat) -> bool {
+    match self {
+      Self::Alpha => sat.n() % COIN_VALUE == 0,
+      Self::Block9 => sat.height() == 9,
+      Self::Block78 => sat.height() == 78,
+      Self::FirstInBlock => sat.third() == 0,
+      Self::LastInBlock => sat == Sat::LAST || Sat(sat.n() + 1).third() == 0,
+      Self::Omega => sat.n() % COIN_VALUE == COIN_VALUE - 1,
+      Self::Palindrome => {
+        let digits = sat.n().to_string();
+        digits.bytes().eq(digits.bytes().rev())
+      }
+      Self::RoundNumber => {
+        let mut n = sat.n();
+        let mut zeros = 0;
+        while n != 0 && n % 10 == 0 {
+          n /= 10;
+          zeros += 1;
+        }
+        zeros >= 3 && n < 10
+      }
+      Self::Vintage => sat.height().n() < 1000,
+      Self::Hitman | Self::Nakamoto | Self::Pizza => false,
+    }
+  }
+}
+
+fn next_palindrome(n: u64) -> u64 {
+  let digits = n.to_string();
+  let len = digits.len();
+
+  let mirror = |half: &str| -> u64 {
+    half
+      .chars()
+      .chain(half.chars().rev().skip(len % 2))
+      .collect::<String>()
+      .parse()
+      .unwrap()
+  };
+
+  let half = &digits[..(len + 1) / 2];
+
+  let palindrome = mirror(half);
+  if palindrome >= n {
+    return palindrome;
+  }
+
+  let next = (half.parse::<u64>().unwrap() + 1).to_string();
+  if next.len() > half.len() {
+    10u64.pow(len.try_into().unwrap()) + 1
+  } else {
+    mirror(&next)
+  }
+}
+
+fn next_round_number(n: u64) -> u64 {
+  let mut unit = 1000;
+  loop {
+    let multiple = ((n + unit - 1) / unit).max(1);
+    if multiple <= 9 {
+      return multiple * unit;
+    }
+    unit *= 10;
+  }
+}
+
+impl Display for SatTrait {
+  fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+    write!(
+      f,
+      "{}",
+      match self {
+        Self::Alpha => "alpha",
+        Self::Block9 => "block-9",
+        Self::Block78 => "block-78",
+        Self::FirstInBlock => "first-in-block",
+        Self::Hitman => "hitman",
+        Self::LastInBlock => "last-in-block",
+        Self::Nakamoto => "nakamoto",
+        Self::Omega => "omega",
+        Self::Palindrome => "palindrome",
+        Self::Pizza => "pizza",
+        Self::RoundNumber => "round-number",
+        Self::Vintage => "vintage",
+      }
+    )
+  }
+}
+
+impl FromStr for SatTrait {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    Self::ALL
+      .into_iter()
+      .find(|sat_trait| sat_trait.to_string() == s)
+      .ok_or_else(|| anyhow!("invalid sat trait: {s}"))
+  }
+}
+
+impl Serialize for SatTrait {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for SatTrait {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn traits(n: u64) -> Vec<SatTrait> {
+    SatTrait::of(Sat(n), &TraitRanges::new())
+  }
+
+  #[test]
+  fn computed_traits() {
+    use SatTrait::*;
+
+    assert_eq!(traits(0), [Alpha, FirstInBlock, Palindrome, Vintage]);
+    assert_eq!(traits(12), [Vintage]);
+    assert_eq!(traits(121), [Palindrome, Vintage]);
+    assert_eq!(traits(7000), [RoundNumber, Vintage]);
+    assert_eq!(traits(7100), [Vintage]);
+    assert_eq!(traits(50 * COIN_VALUE - 1), [LastInBlock, Omega, Vintage]);
+    assert_eq!(
+      traits(9 * 50 * COIN_VALUE),
+      [Alpha, Block9, FirstInBlock, Vintage]
+    );
+    assert_eq!(traits(78 * 50 * COIN_VALUE + 1), [Block78, Vintage]);
+    assert_eq!(traits(1000 * 50 * COIN_VALUE + 1), []);
+    assert_eq!(traits(Sat::LAST.n()), [FirstInBlock, LastInBlock]);
+  }
+
+  #[test]
+  fn ranged_traits() {
+    let ranges = [(SatTrait::Pizza, vec![(10, 20)])].into_iter().collect();
+    let sat = Sat(1000 * 50 * COIN_VALUE + 15);
+
+    assert_eq!(
+      SatTrait::of(Sat(15), &ranges),
+      [SatTrait::Pizza, SatTrait::Vintage]
+    );
+    assert_eq!(SatTrait::of(Sat(20), &ranges), [SatTrait::Vintage]);
+    assert_eq!(SatTrait::of(sat, &ranges), []);
+  }
+
+  #[test]
+  fn first_in_matches_computed_traits() {
+    let ranges = [(SatTrait::Pizza, vec![(30, 40), (20, 25)])]
+      .into_iter()
+      .collect();
+
+    for (start, end) in [
+      (0, 1),
+      (5, 2000),
+      (985, 1200),
+      (50 * COIN_VALUE - 10, 50 * COIN_VALUE + 10),
+      (9 * 50 * COIN_VALUE - 5, 9 * 50 * COIN_VALUE + 5),
+      (999 * 50 * COIN_VALUE + 10, 999 * 50 * COIN_VALUE + 1000),
+      (1000 * 50 * COIN_VALUE - 1, 1000 * 50 * COIN_VALUE + 1),
+      (99_990, 100_010),
+      (999_999_000, 1_000_001_000),
+    ] {
+      for sat_trait in SatTrait::ALL {
+        assert_eq!(
+          sat_trait.first_in(start, end, &ranges),
+          (start..end).find(|n| SatTrait::of(Sat(*n), &ranges).contains(&sat_trait)),
+          "{sat_trait} in {start}..{end}",
+        );
+      }
+    }
+  }
+
+  #[test]
+  fn runs_cover_every_sat_with_trait() {
+    let ranges = [(SatTrait::Pizza, vec![(30, 40), (35, 50), (60, 70)])]
+      .into_iter()
+      .collect();
+
+    for (start, end) in [
+      (0, 100),
+      (45, 65),
+      (985, 1200),
+      (50 * COIN_VALUE - 10, 50 * COIN_VALUE + 10),
+      (9 * 50 * COIN_VALUE - 5, 9 * 50 * COIN_VALUE + 5),
+      (1000 * 50 * COIN_VALUE - 5, 1000 * 50 * COIN_VALUE + 5),
+      (99_990, 100_010),
+    ] {
+      for sat_trait in SatTrait::ALL {
+        assert_eq!(
+          sat_trait
+            .runs_in(start, end, &ranges)
+            .flat_map(|(start, end)| start..end)
+            .collect::<Vec<u64>>(),
+          (start..end)
+            .filter(|n| SatTrait::of(Sat(*n), &ranges).contains(&sat_trait))
+            .collect::<Vec<u64>>(),
+          "{sat_trait} in {start}..{end}",
+        );
+      }
+    }
+
+    assert_eq!(
+      SatTrait::Pizza.runs_in(0, 100, &ranges).collect::<Vec<_>>(),
+      [(30, 50), (60, 70)]
+    );
+
+    assert_eq!(
+      SatTrait::Vintage
+        .runs_in(10, 1000 * 50 * COIN_VALUE + 10, &ranges)
+        .collect::<Vec<_>>(),
+      [(10, 1000 * 50 * COIN_VALUE)]
+    );
+
+    assert_eq!(
+      SatTrait::Alpha
+        .runs_in(1, 3 * COIN_VALUE + 1, &ranges)
+        .collect::<Vec<_>>(),
+      [
+        (COIN_VALUE, COIN_VALUE + 1),
+        (2 * COIN_VALUE, 2 * COIN_VALUE + 1),
+        (3 * COIN_VALUE, 3 * COIN_VALUE + 1),
+      ]
+    );
+  }
+
+  #[test]
+  fn from_str_round_trips() {
+    for sat_trait in SatTrait::ALL {
+      assert_eq!(
+        sat_trait.to_string().parse::<SatTrait>().unwrap(),
+        sat_trait
+      );
+    }
+
+    assert_eq!(
+      "foo".parse::<SatTrait>().unwrap_err().to_string(),
+      "invalid sat trait: foo"
+    );
+  }
+}
@@ -2,15 +2,19 @@ use super::*;
 
 pub mod check_index;
 pub mod compact;
+pub mod convert;
 pub mod decode;
 pub mod epochs;
 pub mod find;
 mod index;
 pub mod info;
 pub mod inscriptions;
+pub mod issuance;
 pub mod list;
+pub mod lost_sats;
 pub mod parse;
 mod preview;
+pub mod ranges;
 mod server;
 pub mod subsidy;
 pub mod supply;
@@ -30,6 +34,8 @@ pub(crate) enum Subcommand {
   CheckIndex(check_index::CheckIndex),
   #[clap(about = "Compact the index file if possible")]
   Compact,
+  #[clap(about = "Convert satoshis between notations")]
+  Convert(convert::Convert),
   #[clap(about = "Decode inscription data from a transaction output")]
   Decode(decode::Decode),
   #[clap(about = "List the first satoshis of each reward epoch")]
@@ -44,10 +50,16 @@ pub(crate) enum Subcommand {
   Info(info::Info),
   #[clap(about = "List all inscriptions")]
   Inscriptions(inscriptions::Inscriptions),
+  #[clap(about = "Report sat issuance by epoch and block range")]
+  Issuance(issuance::Issuance),
   #[clap(about = "List the satoshis in an output")]
   List(list::List),
+  #[clap(about = "List lost sats and inscriptions")]
+  LostSats,
   #[clap(about = "Parse a satoshi from ordinal notation")]
   Parse(parse::Parse),
+  #[clap(subcommand, about = "Combine, split and locate sat ranges")]
+  Ranges(ranges::Ranges),
   #[clap(about = "Display information about a block's subsidy")]
   Subsidy(subsidy::Subsidy),
   #[clap(about = "Run the explorer server")]
@@ -67,6 +79,7 @@ impl Subcommand {
     match self {
       Self::CheckIndex(check_index) => check_index.run(options),
       Self::Compact => compact::run(options),
+      Self::Convert(convert) => convert.run(),
       Self::Decode(decode) => decode.run(options),
       Self::Epochs => epochs::run(),
       Self::Preview(preview) => preview.run(),
@@ -74,8 +87,11 @@ impl Subcommand {
       Self::Index(index) => index.run(options),
       Self::Info(info) => info.run(options),
       Self::Inscriptions(inscriptions) => inscriptions.run(options),
+      Self::Issuance(issuance) => issuance.run(options),
       Self::List(list) => list.run(options),
+      Self::LostSats => lost_sats::run(options),
       Self::Parse(parse) => parse.run(),
+      Self::Ranges(ranges) => ranges.run(options),
       Self::Subsidy(subsidy) => subsidy.run(),
       Self::Server(server) => {
         let index = Arc::new(Index::open(&options)?);
@@ -84,7 +100,7 @@ impl Subcommand {
         server.run(options, index, handle)
       }
       Self::Supply => supply::run(),
-      Self::Traits(traits) => traits.run(),
+      Self::Traits(traits) => traits.run(options),
       Self::Transfer(transfer) => transfer.run(options),
       Self::Wallet(wallet) => wallet.run(options),
     }
@@ -0,0 +1,168 @@
+use {super::*, clap::ValueEnum, std::io::Read};
+
+#[derive(Copy, Clone, Debug, PartialEq, ValueEnum)]
+pub(crate) enum Notation {
+  Number,
+  Decimal,
+  Degree,
+  Name,
+  Percentile,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Convert {
+  #[clap(
+    long,
+    value_enum,
+    help = "Print only <TO> notation, one sat per line, instead of a JSON table."
+  )]
+  to: Option<Notation>,
+  #[clap(
+    help = "Read sats in any notation, one per line, from <INPUT>. Use `-` for standard input."
+  )]
+  input: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub input: String,
+  pub number: u64,
+  pub decimal: String,
+  pub degree: String,
+  pub name: String,
+  pub percentile: String,
+  pub rarity: Rarity,
+  pub epoch: u64,
+  pub cycle: u64,
+  pub block: u64,
+  pub offset: u64,
+}
+
+impl Notation {
+  fn format(self, sat: Sat) -> String {
+    match self {
+      Self::Number => sat.n().to_string(),
+      Self::Decimal => sat.decimal().to_string(),
+      Self::Degree => sat.degree().to_string(),
+      Self::Name => sat.name(),
+      Self::Percentile => sat.percentile(),
+    }
+  }
+}
+
+impl Convert {
+  pub(crate) fn run(self) -> Result {
+    let contents = if self.input == Path::new("-") {
+      let mut contents = String::new();
+      io::stdin().read_to_string(&mut contents)?;
+      contents
+    } else {
+      fs::read_to_string(&self.input)
+        .with_context(|| format!("I/O error reading `{}`", self.input.display()))?
+    };
+
+    let sats = parse_sats(&contents)?;
+
+    if let Some(notation) = self.to {
+      for (_input, sat) in sats {
+        println!("{}", notation.format(sat));
+      }
+      return Ok(());
+    }
+
+    print_json(
+      sats
+        .into_iter()
+        .map(|(input, sat)| Output {
+          input,
+          number: sat.n(),
+          decimal: sat.decimal().to_string(),
+          degree: sat.degree().to_string(),
+          name: sat.name(),
+          percentile: sat.percentile(),
+          rarity: sat.rarity(),
+          epoch: sat.epoch().0,
+          cycle: sat.cycle(),
+          block: sat.height().n(),
+          offset: sat.third(),
+        })
+        .collect::<Vec<Output>>(),
+    )
+  }
+}
+
+/// Parses one sat per line, skipping blank lines and `#` comments. Every
+/// invalid line is reported, not just the first.
+fn parse_sats(contents: &str) -> Result<Vec<(String, Sat)>> {
+  let mut sats = Vec::new();
+  let mut errors = Vec::new();
+
+  for (i, line) in contents.lines().enumerate() {
+    let input = line
+      .split('#')
+      .next()
+      .unwrap_or_default()
+      .trim_start_matches('\u{feff}')
+      .trim();
+
+    if input.is_empty() {
+      continue;
+    }
+
+    match input.parse::<Sat>() {
+      Ok(sat) => sats.push((input.to_string(), sat)),
+      Err(err) => errors.push(format!("line {}: `{input}`: {err}", i + 1)),
+    }
+  }
+
+  if !errors.is_empty() {
+    bail!(
+      "{} invalid {}:\n{}",
+      errors.len(),
+      if errors.len() == 1 { "sat" } else { "sats" },
+      errors.join("\n")
+    );
+  }
+
+  Ok(sats)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_skips_comments_and_blank_lines() {
+    assert_eq!(
+      parse_sats("# sats\n\n0\n1.0 # block one\nnvtdijuwxlp\n").unwrap(),
+      [
+        ("0".into(), Sat(0)),
+        ("1.0".into(), Sat(50 * COIN_VALUE)),
+        ("nvtdijuwxlp".into(), Sat(0)),
+      ]
+    );
+  }
+
+  #[test]
+  fn parse_reports_every_error() {
+    assert_eq!(
+      parse_sats("0\nfoo1\n0°1′0″0‴\n").unwrap_err().to_string(),
+      "2 invalid sats:
+line 2: `foo1`: invalid character `1` in sat name `foo1`
+line 3: `0°1′0″0‴`: relationship between epoch offset 1 and period offset 0 must be multiple of 336"
+    );
+  }
+
+  #[test]
+  fn notations() {
+    let sat = Sat(50 * COIN_VALUE + 1);
+    assert_eq!(Notation::Number.format(sat), "5000000001");
+    assert_eq!(Notation::Decimal.format(sat), "1.1");
+    assert_eq!(Notation::Degree.format(sat), "0°1′1″1‴");
+    assert_eq!(Notation::Name.format(sat), "nvtcsezkbtg");
+    assert_eq!(
+      Notation::Percentile.format(sat).parse::<Sat>().unwrap(),
+      sat
+    );
+  }
+}
@@ -1,5 +1,6 @@
 use {
   super::*,
+  crate::{index::FindRangeOutput, sat_range::SatRange},
   chrono::NaiveDateTime,
   std::io::{BufRead, BufReader},
 };
@@ -35,6 +36,19 @@ pub(crate) struct Find {
   show_time: bool,
   #[clap(long, help = "Show output values in the results.")]
   show_value: bool,
+  #[clap(
+    long = "trait",
+    conflicts_with = "sat",
+    help = "Find all unspent sats with <TRAIT>. Requires --index-traits."
+  )]
+  sat_trait: Option<SatTrait>,
+  #[clap(
+    long,
+    requires = "sat",
+    conflicts_with_all = &["end", "file"],
+    help = "Find where <SAT> was as of block <AT_HEIGHT>. Requires --index-sat-history."
+  )]
+  at_height: Option<Height>,
   #[clap(help = "Find output and offset of <SAT>.")]
   sat: Option<Sat>,
   #[clap(help = "Find output and offset of all sats in the range <SAT>-<END>.")]
@@ -71,81 +85,117 @@ impl Find {
     let mut targets = Vec::new();
     let mut results = Vec::new();
 
-    if let Some(sat) = self.sat {
-      let end = match self.end {
-        Some(end) => end,
-        None => sat + 1,
-      };
-
-      if sat < end {
-        targets.push((sat, end));
-      } else {
-        bail!("range is empty");
-      }
-    }
-
     let comment_re = Regex::new(r"#.*")?;
     for file in self.file {
       let reader = BufReader::new(File::open(&file)?);
-      let mut line_number = 0;
-      for line in reader.lines() {
-        line_number += 1;
+      for (i, line) in reader.lines().enumerate() {
         let line = line?;
         let line = comment_re.replace(&line, "");
         let line = line.trim();
         if line.is_empty() {
           continue;
         }
-        let mut split = line
-          .trim_start_matches('\u{feff}')
-          .split(&['-', '\u{2013}']); // ASCII hyphen or Unicode 'EN DASH'
-        if let Some(start) = split.next() {
-          let start = match start.parse() {
-            Ok(start) => start,
-            Err(err) => bail!(
-              "parsing sat '{start}' on line {line_number} of '{:?}': {err}",
-              file
-            ),
-          };
-
-          let end = match split.next() {
-            Some(end) => {
-              if let Some(junk) = split.next() {
-                bail!(
-                  "trailing junk on line {line_number} of '{}' ({})",
-                  file.display(),
-                  junk
-                );
-              } else {
-                match end.parse() {
-                  Ok(end) => end,
-                  Err(err) => bail!(
-                    "parsing range end '{end}' on line {line_number} of '{:?}': {err}",
-                    file
-                  ),
-                }
-              }
-            }
-            None => start + 1,
-          };
-          targets.push((start, end));
+        targets.push(
+          line
+            .trim_start_matches('\u{feff}')
+            .parse::<SatRange>()
+            .with_context(|| {
+              format!(
+                "file '{}' is not formatted correctly at line {}",
+                file.display(),
+                i + 1,
+              )
+            })?,
+        );
+      }
+    }
+
+    match (self.sat, self.at_height, self.sat_trait) {
+      (Some(sat), None, None) => {
+        let end = match self.end {
+          Some(end) => end,
+          None => sat + 1,
+        };
+
+        if sat < end {
+          targets.insert(0, SatRange::new(sat.n(), end.n()));
         } else {
-          bail!(
-            "file '{}' is not formatted correctly at line {line_number}",
-            file.display()
-          );
+          bail!("range is empty");
         }
       }
-    }
+      (Some(sat), Some(height), None) => {
+        if index
+          .block_height()?
+          .map_or(true, |indexed| height > indexed)
+        {
+          bail!("index has not reached height {height}");
+        }
 
-    if targets.is_empty() {
-      bail!("nothing to find");
+        let satpoint = index
+          .sat_history(sat)?
+          .ok_or_else(|| {
+            anyhow!("find --at-height requires index created with `--index-sat-history` flag")
+          })?
+          .into_iter()
+          .take_while(|(assigned, _satpoint)| *assigned <= height)
+          .last()
+          .map(|(_assigned, satpoint)| satpoint)
+          .ok_or_else(|| anyhow!("sat {sat} had not been mined as of height {height}"))?;
+
+        results.push(FindRangeOutput {
+          start: sat.n(),
+          size: 1,
+          satpoint,
+        });
+      }
+      (None, None, Some(sat_trait)) => {
+        let mut from = None;
+
+        loop {
+          let (sats, next) = index
+            .sats_with_trait(sat_trait, from, 1000)?
+            .ok_or_else(|| {
+              anyhow!("find --trait requires index created with `--index-traits` flag")
+            })?;
+
+          results.extend(
+            sats
+              .into_iter()
+              .filter(|(_sat, _size, satpoint)| {
+                self.outpoint.is_empty() || self.outpoint.contains(&satpoint.outpoint)
+              })
+              .map(|(sat, size, satpoint)| FindRangeOutput {
+                start: sat.n(),
+                size,
+                satpoint,
+              }),
+          );
+
+          if next.is_none() {
+            break;
+          }
+
+          from = next;
+        }
+      }
+      (None, None, None) => {
+        if targets.is_empty() {
+          bail!("nothing to find");
+        }
+      }
+      (None, Some(_), _) => bail!("--at-height requires <SAT>"),
+      (Some(_), _, Some(_)) => bail!("--trait cannot be used with <SAT>"),
     }
 
     // loop through targets
-    for (sat, end) in targets {
-      // eprintln!("find {sat}-{end}");
-      match index.find(sat, end, &self.outpoint, self.ignore)? {
+    for range in targets {
+      // eprintln!("find {range}");
+      match index.find(
+        Sat(range.start),
+        Sat(range.end),
+        &self.outpoint,
+        self.ignore,
+      )? {
         Some(result) => {
           // eprintln!("  found {} satpoints", result.len());
           results.extend(result);
@@ -153,7 +203,7 @@ impl Find {
         None => {
           if !self.ignore {
             return Err(anyhow!(
-              "range {sat}-{end} not found; use --ignore to continue anyway"
+              "range {range} not found; use --ignore to continue anyway"
             ));
           }
         }
@@ -168,7 +218,23 @@ impl Find {
     // result.satpoint.outpoint.txid == gbt.txid()
 
     for result in results {
-      let tx = if self.show_address
+      // With `--index-utxos`, addresses of unspent outputs are known locally,
+      // so the transaction is only fetched if something else is shown.
+      let address = if self.show_address {
+        index
+          .get_indexed_script_pubkey(result.satpoint.outpoint)?
+          .map(|script_pubkey| {
+            options
+              .chain()
+              .address_from_script(&script_pubkey)
+              .ok()
+              .map(|address| Address::new(address.network, address.payload))
+          })
+      } else {
+        None
+      };
+
+      let tx = if (self.show_address && address.is_none())
         || self.show_blockhash
         || self.show_date
         || self.show_height
@@ -186,7 +252,7 @@ impl Find {
         start: result.start,
         size: result.size,
         satpoint: result.satpoint,
-        address: None,
+        address: address.clone().flatten(),
         blockhash: None,
         date: None,
         height: None,
@@ -196,7 +262,7 @@ impl Find {
       };
 
       if let Some(tx) = tx.clone() {
-        if self.show_address {
+        if self.show_address && address.is_none() {
           result.address = tx.vout[result.satpoint.outpoint.vout as usize]
             .script_pub_key
             .address
@@ -0,0 +1,235 @@
+use super::*;
+
+#[derive(Debug, Parser)]
+pub(crate) struct Issuance {
+  #[clap(long, help = "Also report issuance for every <INTERVAL> mined blocks.")]
+  interval: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub height: Option<u64>,
+  pub supply: u64,
+  pub issued: u64,
+  pub remaining: u64,
+  pub next_halving: Option<NextHalving>,
+  pub epochs: Vec<EpochIssuance>,
+  pub ranges: Vec<BlockRangeIssuance>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct NextHalving {
+  pub height: u64,
+  pub blocks: u64,
+  pub timestamp: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct EpochIssuance {
+  pub epoch: u64,
+  pub subsidy: u64,
+  #[serde(flatten)]
+  pub blocks: BlockRangeIssuance,
+}
+
+/// Issuance of blocks `start..end`.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct BlockRangeIssuance {
+  pub start: u64,
+  pub end: u64,
+  pub first_sat: u64,
+  pub sats: u64,
+  pub rarities: RarityCounts,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RarityCounts {
+  pub common: u64,
+  pub uncommon: u64,
+  pub rare: u64,
+  pub epic: u64,
+  pub legendary: u64,
+  pub mythic: u64,
+}
+
+impl Issuance {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+
+    index.update()?;
+
+    print_json(Output::new(&index, self.interval)?)?;
+
+    Ok(())
+  }
+}
+
+impl Output {
+  pub(crate) fn new(index: &Index, interval: Option<u64>) -> Result<Self> {
+    let height = index.block_height()?;
+
+    let mined = height.map(|height| height.n() + 1).unwrap_or_default();
+
+    let issued = Height(mined).starting_sat().n();
+
+    let next_halving = {
+      let next = Epoch::from(Height(mined)).0 + 1;
+      if next <= Epoch::FIRST_POST_SUBSIDY.0 {
+        let next = Epoch(next).starting_height();
+        Some(NextHalving {
+          height: next.n(),
+          blocks: next.n() - mined,
+          timestamp: index.block_time(next)?.unix_timestamp(),
+        })
+      } else {
+        None
+      }
+    };
+
+    let epochs = (0..Epoch::FIRST_POST_SUBSIDY.0)
+      .map(Epoch)
+      .map(|epoch| EpochIssuance {
+        epoch: epoch.0,
+        subsidy: epoch.subsidy(),
+        blocks: BlockRangeIssuance::new(
+          epoch.starting_height().n(),
+          Epoch(epoch.0 + 1).starting_height().n(),
+        ),
+      })
+      .collect();
+
+    let ranges = match interval {
+      Some(0) => bail!("interval must be greater than zero"),
+      Some(interval) => (0..mined)
+        .step_by(usize::try_from(interval).unwrap_or(usize::MAX))
+        .map(|start| BlockRangeIssuance::new(start, (start + interval).min(mined)))
+        .collect(),
+      None => Vec::new(),
+    };
+
+    Ok(Self {
+      height: height.map(|height| height.n()),
+      supply: Sat::SUPPLY,
+      issued,
+      remaining: Sat::SUPPLY - issued,
+      next_halving,
+      epochs,
+      ranges,
+    })
+  }
+}
+
+impl BlockRangeIssuance {
+  fn new(start: u64, end: u64) -> Self {
+    let first_sat = Height(start).starting_sat().n();
+
+    Self {
+      start,
+      end,
+      first_sat,
+      sats: Height(end).starting_sat().n() - first_sat,
+      rarities: RarityCounts::new(start, end),
+    }
+  }
+}
+
+impl RarityCounts {
+  /// Counts rarities of the sats mined in blocks `start..end` without
+  /// visiting each block. The first sat of every block is uncommon or
+  /// rarer, depending on whether the block starts a difficulty adjustment
+  /// period, an epoch or a cycle.
+  fn new(start: u64, end: u64) -> Self {
+    let end = end
+      .min(Epoch::FIRST_POST_SUBSIDY.starting_height().n())
+      .max(start);
+
+    let multiples =
+      |interval: u64| (end + interval - 1) / interval - (start + interval - 1) / interval;
+
+    let blocks = end - start;
+    let cycles = multiples(SUBSIDY_HALVING_INTERVAL * CYCLE_EPOCHS);
+
+    let mythic = u64::from(start == 0 && blocks > 0);
+    let legendary = cycles - mythic;
+    let epic = multiples(SUBSIDY_HALVING_INTERVAL) - cycles;
+    let rare = multiples(DIFFCHANGE_INTERVAL) - cycles;
+    let uncommon = blocks - mythic - legendary - epic - rare;
+
+    let sats = Height(end).starting_sat().n() - Height(start).starting_sat().n();
+
+    Self {
+      common: sats - blocks,
+      uncommon,
+      rare,
+      epic,
+      legendary,
+      mythic,
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn rarity_counts_match_rarity() {
+    #[track_caller]
+    fn case(start: u64, end: u64) {
+      let mut expected = RarityCounts::default();
+
+      for height in start..end {
+        let subsidy = Height(height).subsidy();
+        if subsidy == 0 {
+          continue;
+        }
+
+        expected.common += subsidy - 1;
+
+        match Height(height).starting_sat().rarity() {
+          Rarity::Common => unreachable!(),
+          Rarity::Uncommon => expected.uncommon += 1,
+          Rarity::Rare => expected.rare += 1,
+          Rarity::Epic => expected.epic += 1,
+          Rarity::Legendary => expected.legendary += 1,
+          Rarity::Mythic => expected.mythic += 1,
+        }
+      }
+
+      assert_eq!(RarityCounts::new(start, end), expected);
+    }
+
+    case(0, 0);
+    case(0, 1);
+    case(0, 5000);
+    case(2015, 2017);
+    case(209_000, 212_000);
+    case(1_259_000, 1_262_000);
+    case(6_929_000, 6_932_000);
+  }
+
+  #[test]
+  fn epochs_add_up_to_supply() {
+    let epochs = (0..Epoch::FIRST_POST_SUBSIDY.0)
+      .map(|epoch| {
+        BlockRangeIssuance::new(
+          Epoch(epoch).starting_height().n(),
+          Epoch(epoch + 1).starting_height().n(),
+        )
+      })
+      .collect::<Vec<BlockRangeIssuance>>();
+
+    assert_eq!(
+      epochs.iter().map(|epoch| epoch.sats).sum::<u64>(),
+      Sat::SUPPLY
+    );
+
+    assert_eq!(
+      epochs
+        .iter()
+        .map(|epoch| epoch.rarities.legendary)
+        .sum::<u64>(),
+      5
+    );
+  }
+}
@@ -4,6 +4,8 @@ use super::*;
 pub(crate) struct List {
   #[clap(help = "List sats in <OUTPOINT>.")]
   outpoint: OutPoint,
+  #[clap(long, help = "Show the address of <OUTPOINT> in the results.")]
+  show_address: bool,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -15,6 +17,8 @@ pub struct Output {
   pub offset: u64,
   pub rarity: Rarity,
   pub name: String,
+  #[serde(skip_serializing_if = "Option::is_none", default)]
+  pub address: Option<Address<NetworkUnchecked>>,
 }
 
 impl List {
@@ -25,26 +29,17 @@ impl List {
 
     match index.list(self.outpoint)? {
       Some(crate::index::List::Unspent(ranges)) => {
-        let mut outputs = Vec::new();
-        for Output {
-          output,
-          start,
-          end,
-          size,
-          offset,
-          rarity,
-          name,
-        } in list(self.outpoint, ranges)
-        {
-          outputs.push(Output {
-            output,
-            start,
-            end,
-            size,
-            offset,
-            rarity,
-            name,
-          });
+        let mut outputs = list(self.outpoint, ranges);
+
+        if self.show_address {
+          let address = index
+            .get_script_pubkey(self.outpoint)?
+            .and_then(|script_pubkey| options.chain().address_from_script(&script_pubkey).ok())
+            .map(|address| Address::new(address.network, address.payload));
+
+          for output in &mut outputs {
+            output.address = address.clone();
+          }
         }
 
         print_json(outputs)?;
@@ -71,6 +66,7 @@ fn list(outpoint: OutPoint, ranges: Vec<(u64, u64)>) -> Vec<Output> {
         offset,
         name: Sat(start).name(),
         rarity: Sat(start).rarity(),
+        address: None,
       };
 
       offset += size;
@@ -101,6 +97,7 @@ mod tests {
       offset,
       name,
       rarity,
+      address: None,
     }
   }
 
@@ -0,0 +1,132 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub lost_sats: u64,
+  pub ranges: Vec<LostSatRange>,
+  pub inscriptions: Vec<LostInscription>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct LostSatRange {
+  pub start: u64,
+  pub end: u64,
+  pub size: u64,
+  pub offset: u64,
+  pub height: u64,
+  pub cause: LostCause,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct LostInscription {
+  pub inscription_id: InscriptionId,
+  pub number: i64,
+  pub satpoint: SatPoint,
+  pub height: u64,
+  pub cause: LostInscriptionCause,
+}
+
+/// Why an inscription ended up in the null or unbound outpoint.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum LostInscriptionCause {
+  /// Its sat was in fees and subsidy not claimed by the coinbase transaction.
+  Unclaimed,
+  /// Its sat was in outputs overwritten by a later transaction with the same
+  /// txid.
+  DuplicateTxid,
+  /// It was made on a zero-value input or not in the first input.
+  Unbound,
+}
+
+impl From<LostCause> for LostInscriptionCause {
+  fn from(cause: LostCause) -> Self {
+    match cause {
+      LostCause::Unclaimed => Self::Unclaimed,
+      LostCause::DuplicateTxid => Self::DuplicateTxid,
+    }
+  }
+}
+
+pub(crate) fn run(options: Options) -> Result {
+  let index = Index::open(&options)?;
+
+  index.update()?;
+
+  print_json(
+    Output::new(&index)?
+      .ok_or_else(|| anyhow!("lost-sats requires index created with `--index-sats` flag"))?,
+  )?;
+
+  Ok(())
+}
+
+impl Output {
+  pub(crate) fn new(index: &Index) -> Result<Option<Self>> {
+    let ranges = match index.lost_sat_ranges()? {
+      Some(ranges) => ranges,
+      None => return Ok(None),
+    };
+
+    let ranges = ranges
+      .into_iter()
+      .map(|(offset, (start, end), height, cause)| LostSatRange {
+        start,
+        end,
+        size: end - start,
+        offset,
+        height: height.n(),
+        cause,
+      })
+      .collect::<Vec<LostSatRange>>();
+
+    let mut inscriptions = Vec::new();
+
+    for (satpoint, inscription_id) in
+      index.get_inscriptions_on_output_with_satpoints(OutPoint::null())?
+    {
+      let entry = index
+        .get_inscription_entry(inscription_id)?
+        .ok_or_else(|| anyhow!("missing entry for lost inscription {inscription_id}"))?;
+
+      // Inscriptions are sent to the null outpoint along with their sat, in
+      // the same block that loses the sat's range.
+      let (height, cause) = ranges
+        .partition_point(|range| range.offset <= satpoint.offset)
+        .checked_sub(1)
+        .map_or((entry.height, LostCause::Unclaimed), |i| {
+          (ranges[i].height, ranges[i].cause)
+        });
+
+      inscriptions.push(LostInscription {
+        inscription_id,
+        number: entry.number,
+        satpoint,
+        height,
+        cause: cause.into(),
+      });
+    }
+
+    for (satpoint, inscription_id) in
+      index.get_inscriptions_on_output_with_satpoints(unbound_outpoint())?
+    {
+      let entry = index
+        .get_inscription_entry(inscription_id)?
+        .ok_or_else(|| anyhow!("missing entry for unbound inscription {inscription_id}"))?;
+
+      inscriptions.push(LostInscription {
+        inscription_id,
+        number: entry.number,
+        satpoint,
+        height: entry.height,
+        cause: LostInscriptionCause::Unbound,
+      });
+    }
+
+    Ok(Some(Self {
+      lost_sats: ranges.iter().map(|range| range.size).sum(),
+      ranges,
+      inscriptions,
+    }))
+  }
+}
@@ -0,0 +1,257 @@
+use {
+  super::*,
+  crate::sat_range::{self, SatRange, SatRangeSet},
+  std::io::Read,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) enum Ranges {
+  #[clap(about = "List sats in any input")]
+  Union(Inputs),
+  #[clap(about = "List sats in every input")]
+  Intersect(Inputs),
+  #[clap(about = "List sats in the first input but in no other")]
+  Subtract(Inputs),
+  #[clap(about = "Split ranges, in input order, at an offset")]
+  Split(Split),
+  #[clap(about = "Find where ranges are in an output")]
+  Overlap(Overlap),
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Inputs {
+  #[clap(
+    required = true,
+    help = "Read ranges from <INPUT>. Use `-` for standard input."
+  )]
+  inputs: Vec<PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Split {
+  #[clap(long, help = "Split after the first <OFFSET> sats.")]
+  offset: u64,
+  #[clap(help = "Read ranges from <INPUT>. Use `-` for standard input.")]
+  input: PathBuf,
+}
+
+#[derive(Debug, Parser)]
+pub(crate) struct Overlap {
+  #[clap(long, help = "Look for ranges in <OUTPUT>.")]
+  output: OutPoint,
+  #[clap(help = "Read ranges from <INPUT>. Use `-` for standard input.")]
+  input: PathBuf,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Output {
+  pub start: u64,
+  pub end: u64,
+  pub size: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SplitOutput {
+  pub before: Vec<Output>,
+  pub after: Vec<Output>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct OverlapOutput {
+  pub output: OutPoint,
+  pub offset: u64,
+  pub start: u64,
+  pub end: u64,
+  pub size: u64,
+}
+
+impl From<SatRange> for Output {
+  fn from(range: SatRange) -> Self {
+    Self {
+      start: range.start,
+      end: range.end,
+      size: range.size(),
+    }
+  }
+}
+
+impl Ranges {
+  pub(crate) fn run(self, options: Options) -> Result {
+    match self {
+      Self::Union(inputs) => {
+        let sets = inputs.read()?;
+        print_sets(
+          sets
+            .iter()
+            .fold(SatRangeSet::default(), |acc, set| acc.union(set)),
+        )
+      }
+      Self::Intersect(inputs) => {
+        let mut sets = inputs.read()?.into_iter();
+        let first = sets.next().unwrap_or_default();
+        print_sets(sets.fold(first, |acc, set| acc.intersection(&set)))
+      }
+      Self::Subtract(inputs) => {
+        let mut sets = inputs.read()?.into_iter();
+        let first = sets.next().unwrap_or_default();
+        print_sets(sets.fold(first, |acc, set| acc.difference(&set)))
+      }
+      Self::Split(split) => {
+        let (before, after) = sat_range::split_ranges_at(&read_ranges(&split.input)?, split.offset);
+
+        print_json(SplitOutput {
+          before: before.into_iter().map(Output::from).collect(),
+          after: after.into_iter().map(Output::from).collect(),
+        })
+      }
+      Self::Overlap(overlap) => {
+        let set = read_ranges(&overlap.input)?
+          .into_iter()
+          .collect::<SatRangeSet>();
+
+        let index = Index::open(&options)?;
+
+        index.update()?;
+
+        let ranges = match index.list(overlap.output)? {
+          Some(crate::index::List::Unspent(ranges)) => ranges,
+          Some(crate::index::List::Spent) => bail!("output spent."),
+          None => bail!("output not found"),
+        };
+
+        print_json(
+          set
+            .locate(&ranges.into_iter().map(SatRange::from).collect::<Vec<_>>())
+            .into_iter()
+            .map(|(offset, range)| OverlapOutput {
+              output: overlap.output,
+              offset,
+              start: range.start,
+              end: range.end,
+              size: range.size(),
+            })
+            .collect::<Vec<OverlapOutput>>(),
+        )
+      }
+    }
+  }
+}
+
+impl Inputs {
+  fn read(&self) -> Result<Vec<SatRangeSet>> {
+    self
+      .inputs
+      .iter()
+      .map(|input| Ok(read_ranges(input)?.into_iter().collect()))
+      .collect()
+  }
+}
+
+fn print_sets(set: SatRangeSet) -> Result {
+  print_json(
+    set
+      .ranges()
+      .iter()
+      .copied()
+      .map(Output::from)
+      .collect::<Vec<Output>>(),
+  )
+}
+
+fn read_ranges(input: &Path) -> Result<Vec<SatRange>> {
+  let contents = if input == Path::new("-") {
+    let mut contents = String::new();
+    io::stdin().read_to_string(&mut contents)?;
+    contents
+  } else {
+    fs::read_to_string(input).with_context(|| format!("I/O error reading `{}`", input.display()))?
+  };
+
+  parse_ranges(&contents).with_context(|| format!("reading ranges from `{}`", input.display()))
+}
+
+/// Parses a JSON array as printed by `ord list`, `ord find` or `ord wallet
+/// sats`, or else one sat or range per line, with `#` comments.
+fn parse_ranges(contents: &str) -> Result<Vec<SatRange>> {
+  if contents.trim_start().starts_with('[') {
+    #[derive(Deserialize)]
+    struct Entry {
+      start: Option<u64>,
+      end: Option<u64>,
+      size: Option<u64>,
+      sat: Option<u64>,
+    }
+
+    serde_json::from_str::<Vec<Entry>>(contents)?
+      .into_iter()
+      .enumerate()
+      .map(|(i, entry)| match entry {
+        Entry {
+          start: Some(start),
+          end: Some(end),
+          ..
+        } if start <= end => Ok(SatRange::new(start, end)),
+        Entry {
+          start: Some(start),
+          size: Some(size),
+          ..
+        } => Ok(SatRange::new(start, start + size)),
+        Entry { sat: Some(sat), .. } => Ok(SatRange::new(sat, sat + 1)),
+        _ => Err(anyhow!("entry {i} is not a sat or sat range")),
+      })
+      .collect()
+  } else {
+    contents
+      .lines()
+      .enumerate()
+      .map(|(i, line)| (i + 1, line.split('#').next().unwrap_or_default().trim()))
+      .filter(|(_, line)| !line.is_empty())
+      .map(|(line_number, line)| {
+        line
+          .trim_start_matches('\u{feff}')
+          .parse()
+          .with_context(|| format!("line {line_number}"))
+      })
+      .collect()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse_text() {
+    assert_eq!(
+      parse_ranges("# ranges\n0-10\n\n20 # one sat\n").unwrap(),
+      [SatRange::new(0, 10), SatRange::new(20, 21)]
+    );
+    assert_eq!(
+      format!("{:#}", parse_ranges("0-10\n10-0\n").unwrap_err()),
+      "line 2: invalid sat range `10-0`: end before start"
+    );
+  }
+
+  #[test]
+  fn parse_json() {
+    assert_eq!(
+      parse_ranges(
+        r#"[
+          {"output": "x", "start": 0, "end": 10, "size": 10, "offset": 0},
+          {"start": 20, "size": 1, "satpoint": "x"},
+          {"sat": 30, "output": "x", "offset": 0}
+        ]"#
+      )
+      .unwrap(),
+      [
+        SatRange::new(0, 10),
+        SatRange::new(20, 21),
+        SatRange::new(30, 31)
+      ]
+    );
+    assert_eq!(
+      parse_ranges(r#"[{"foo": 1}]"#).unwrap_err().to_string(),
+      "entry 0 is not a sat or sat range"
+    );
+  }
+}
@@ -1,28 +1,37 @@
 use {
   self::{
     accept_json::AcceptJson,
+    byte_range::ByteRange,
     deserialize_from_str::DeserializeFromStr,
     error::{OptionExt, ServerError, ServerResult},
+    moderation::{HiddenEntry, Moderation},
+    rate_limit::{RateLimiter, Rejection, RouteClass},
+    thumbnail::{Thumbnail, ThumbnailCache},
   },
   super::*,
-  crate::index::block_index::BlockIndex,
+  crate::index::{block_index::BlockIndex, inscription_query::InscriptionQuery},
   crate::page_config::PageConfig,
+  crate::sat_trait::TraitRanges,
   crate::templates::{
-    BlockHtml, ClockSvg, HomeHtml, InputHtml, InscriptionHtml, InscriptionJson, InscriptionsHtml,
-    InscriptionsJson, OutputHtml, OutputJson, PageContent, PageHtml, PreviewAudioHtml,
-    PreviewImageHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml, PreviewVideoHtml,
-    RangeHtml, RareTxt, SatHtml, SatJson, TransactionHtml,
+    AddressHtml, AddressJson, BlockHtml, ClockSvg, ContentHashHtml, ContentHashJson, Envelope,
+    ErrorJson, HomeHtml, InputHtml, InscriptionHtml, InscriptionJson, InscriptionQueryHtml,
+    InscriptionQueryJson, InscriptionsHtml, InscriptionsJson, OutputHtml, OutputJson, PageContent,
+    PageHtml, PreviewAudioHtml, PreviewImageHtml, PreviewPdfHtml, PreviewTextHtml,
+    PreviewUnknownHtml, PreviewVideoHtml, RangeHtml, RareTxt, SatHistoryHtml, SatHistoryJson,
+    SatHtml, SatJson, SatsHtml, SatsJson, ThumbnailSvg, TransactionHtml,
   },
   axum::{
     body,
-    extract::{Extension, Json, Path, Query},
+    extract::{ConnectInfo, Extension, Json, MatchedPath, Path, Query, RawQuery},
     headers::UserAgent,
     http::{header, HeaderMap, HeaderValue, StatusCode, Uri},
+    middleware::{self, Next},
     response::{IntoResponse, Redirect, Response},
-    routing::get,
+    routing::{get, put},
     Router, TypedHeader,
   },
   axum_server::Handle,
+  bitcoin::hashes::sha256,
   rust_embed::RustEmbed,
   rustls_acme::{
     acme::{LETS_ENCRYPT_PRODUCTION_DIRECTORY, LETS_ENCRYPT_STAGING_DIRECTORY},
@@ -30,7 +39,7 @@ use {
     caches::DirCache,
     AcmeConfig,
   },
-  std::{cmp::Ordering, str, sync::Arc, sync::RwLock},
+  std::{cmp::Ordering, net::SocketAddr, str, sync::Arc, sync::RwLock},
   tokio::time::sleep,
   tokio_stream::StreamExt,
   tower_http::{
@@ -41,7 +50,12 @@ use {
 };
 
 mod accept_json;
+mod byte_range;
 mod error;
+mod moderation;
+mod openapi;
+mod rate_limit;
+mod thumbnail;
 
 #[derive(Clone)]
 pub struct ServerConfig {
@@ -80,6 +94,44 @@ struct Search {
   query: String,
 }
 
+#[derive(Deserialize)]
+struct InscriptionQueryParams {
+  address: Option<String>,
+  content_type: Option<String>,
+  cursed: Option<bool>,
+  from: Option<i64>,
+  max_height: Option<u64>,
+  min_height: Option<u64>,
+  rarity: Option<Rarity>,
+  sort: Option<Sort>,
+}
+
+#[derive(Deserialize)]
+struct RareTxtParams {
+  #[serde(rename = "trait")]
+  sat_trait: Option<SatTrait>,
+}
+
+#[derive(Deserialize)]
+struct IssuanceParams {
+  interval: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SatsParams {
+  #[serde(rename = "trait")]
+  sat_trait: SatTrait,
+  from: Option<Sat>,
+}
+
+#[derive(Deserialize, Default, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Sort {
+  #[default]
+  Newest,
+  Oldest,
+}
+
 #[derive(Serialize)]
 struct MyInscriptionJson {
   number: i64,
@@ -114,6 +166,12 @@ struct SatoshiJson {
   // timestamp: i64,
 }
 
+#[derive(Serialize)]
+struct TransferJson {
+  inscription_id: InscriptionId,
+  address: String,
+}
+
 #[derive(Serialize)]
 struct StatsJson {
   highest_block_indexed: Option<u64>,
@@ -176,6 +234,12 @@ pub(crate) struct Server {
   https: bool,
   #[clap(long, help = "Redirect HTTP traffic to HTTPS.")]
   redirect_http_to_https: bool,
+  #[clap(
+    long,
+    default_value = "67108864",
+    help = "Cap the on-disk thumbnail cache at <THUMBNAIL_CACHE_SIZE> bytes."
+  )]
+  thumbnail_cache_size: u64,
 }
 
 impl Server {
@@ -213,7 +277,19 @@ impl Server {
         is_json_api_enabled: index.is_json_api_enabled(),
       });
 
-      let config = options.load_config()?;
+      let moderation = Arc::new(Moderation::new(&options)?);
+      moderation.spawn_reloader()?;
+
+      let config = moderation.config();
+      let rate_limiter = Arc::new(RateLimiter::new(
+        config.rate_limits.clone(),
+        config.api_keys.clone(),
+      ));
+      let thumbnail_cache = Arc::new(ThumbnailCache::open(
+        options.data_dir()?.join("thumbnails"),
+        self.thumbnail_cache_size,
+      )?);
+
       let acme_domains = self.acme_domains()?;
 
       let page_config = Arc::new(PageConfig {
@@ -223,6 +299,12 @@ impl Server {
 
       let router = Router::new()
         .route("/", get(Self::home))
+        .route("/address/:address", get(Self::address))
+        .route("/admin/hidden", get(Self::admin_hidden))
+        .route(
+          "/admin/hidden/:entry",
+          put(Self::admin_hide).delete(Self::admin_unhide),
+        )
         .route("/block/:query", get(Self::block))
         .route("/blockcount", get(Self::block_count))
         .route("/blockheight", get(Self::block_height))
@@ -232,6 +314,7 @@ impl Server {
         .route("/bounties", get(Self::bounties))
         .route("/clock", get(Self::clock))
         .route("/content/:inscription_id", get(Self::content))
+        .route("/content-hash/:hash", get(Self::content_hash))
         .route("/faq", get(Self::faq))
         .route("/favicon.ico", get(Self::favicon))
         .route("/feed.xml", get(Self::feed))
@@ -239,6 +322,7 @@ impl Server {
         .route("/inscription/:inscription_id", get(Self::inscription))
         .route("/inscriptions", get(Self::inscriptions))
         .route("/inscriptions/block/:n", get(Self::inscriptions_in_block))
+        .route("/inscriptions/query", get(Self::inscriptions_query))
         .route("/inscriptions/:from", get(Self::inscriptions_from))
         .route("/inscriptions/:from/:n", get(Self::inscriptions_from_n))
         .route(
@@ -250,22 +334,34 @@ impl Server {
           get(Self::inscriptions_json_start_end),
         )
         .route("/install.sh", get(Self::install_script))
+        .route("/issuance", get(Self::issuance))
+        .route("/lost", get(Self::lost))
+        .route("/metrics", get(Self::metrics))
+        .route("/openapi.json", get(Self::openapi))
         .route("/ordinal/:sat", get(Self::ordinal))
         .route("/output/:output", get(Self::output))
         .route("/preview/:inscription_id", get(Self::preview))
         .route("/range/:start/:end", get(Self::range))
         .route("/rare.txt", get(Self::rare_txt))
         .route("/sat/:sat", get(Self::sat))
+        .route("/sat/:sat/history", get(Self::sat_history))
+        .route("/sats", get(Self::sats))
         .route("/search", get(Self::search_by_query))
         .route("/search/:query", get(Self::search_by_path))
         .route("/static/*path", get(Self::static_asset))
         .route("/stats", get(Self::stats))
         .route("/status", get(Self::status))
+        .route("/thumbnail/:inscription_id", get(Self::thumbnail))
         .route("/transfers/:height", get(Self::inscriptionids_from_height))
         .route("/tx/:txid", get(Self::transaction))
+        .layer(middleware::from_fn(Self::rate_limit))
+        .layer(middleware::from_fn(Self::json_errors))
+        .layer(middleware::from_fn(Self::record_metrics))
         .layer(Extension(index))
+        .layer(Extension(rate_limiter))
         .layer(Extension(page_config))
-        .layer(Extension(Arc::new(config)))
+        .layer(Extension(moderation))
+        .layer(Extension(thumbnail_cache))
         .layer(Extension(block_index_state))
         .layer(SetResponseHeaderLayer::if_not_present(
           header::CONTENT_SECURITY_POLICY,
@@ -356,7 +452,7 @@ impl Server {
           axum_server::Server::bind(addr)
             .handle(handle)
             .acceptor(acceptor)
-            .serve(router.into_make_service())
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
             .await
         }
         SpawnConfig::Redirect(destination) => {
@@ -373,7 +469,7 @@ impl Server {
         SpawnConfig::Http => {
           axum_server::Server::bind(addr)
             .handle(handle)
-            .serve(router.into_make_service())
+            .serve(router.into_make_service_with_connect_info::<SocketAddr>())
             .await
         }
       }
@@ -468,9 +564,124 @@ impl Server {
     )
   }
 
+  fn envelope<T: Serialize>(
+    index: &Index,
+    data: T,
+    next: Option<String>,
+  ) -> ServerResult<Response> {
+    Ok(
+      Json(Envelope {
+        data,
+        next,
+        height: index.block_height()?.map(|height| height.n()),
+      })
+      .into_response(),
+    )
+  }
+
+  async fn json_errors<B>(request: http::Request<B>, next: Next<B>) -> Response {
+    let accept_json = request
+      .headers()
+      .get(header::ACCEPT)
+      .map(|value| value == "application/json")
+      .unwrap_or_default()
+      || request.uri().path().starts_with("/inscriptions_json/");
+
+    let response = next.run(request).await;
+
+    let status = response.status();
+
+    if !accept_json || !(status.is_client_error() || status.is_server_error()) {
+      return response;
+    }
+
+    let retry_after = response.headers().get(header::RETRY_AFTER).cloned();
+
+    let message = match hyper::body::to_bytes(response.into_body()).await {
+      Ok(bytes) => String::from_utf8_lossy(&bytes).into_owned(),
+      Err(err) => err.to_string(),
+    };
+
+    let mut response = (
+      status,
+      Json(ErrorJson {
+        status: status.as_u16(),
+        message,
+      }),
+    )
+      .into_response();
+
+    if let Some(retry_after) = retry_after {
+      response
+        .headers_mut()
+        .insert(header::RETRY_AFTER, retry_after);
+    }
+
+    response
+  }
+
+  async fn rate_limit<B>(
+    Extension(rate_limiter): Extension<Arc<RateLimiter>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    request: http::Request<B>,
+    next: Next<B>,
+  ) -> ServerResult<Response> {
+    let accept_json = request
+      .headers()
+      .get(header::ACCEPT)
+      .map(|value| value == "application/json")
+      .unwrap_or_default();
+
+    let Some(class) = RouteClass::of(request.uri().path(), accept_json) else {
+      return Ok(next.run(request).await);
+    };
+
+    let api_key = request
+      .headers()
+      .get("x-api-key")
+      .map(|value| value.to_str().unwrap_or_default());
+
+    let _permit = rate_limiter
+      .admit(
+        class,
+        connect_info.map(|ConnectInfo(addr)| addr.ip()),
+        api_key,
+        Instant::now(),
+      )
+      .map_err(|rejection| match rejection {
+        Rejection::TooManyRequests { retry_after } => ServerError::TooManyRequests { retry_after },
+        Rejection::UnknownApiKey => ServerError::Unauthorized("unknown API key".into()),
+      })?;
+
+    Ok(next.run(request).await)
+  }
+
+  async fn record_metrics<B>(
+    Extension(index): Extension<Arc<Index>>,
+    request: http::Request<B>,
+    next: Next<B>,
+  ) -> Response {
+    let route = request
+      .extensions()
+      .get::<MatchedPath>()
+      .map(|path| path.as_str().to_string())
+      .unwrap_or_else(|| "unmatched".into());
+
+    let start = Instant::now();
+
+    let response = next.run(request).await;
+
+    index
+      .metrics()
+      .observe_request(&route, response.status().as_u16(), start.elapsed());
+
+    response
+  }
+
   async fn sat(
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
+    Extension(moderation): Extension<Arc<Moderation>>,
     Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<Sat>>,
     accept_json: AcceptJson,
   ) -> ServerResult<Response> {
@@ -485,36 +696,68 @@ impl Server {
       })
     });
     let blocktime = index.block_time(sat.height())?;
+    let traits = SatTrait::of(sat, &Self::trait_ranges(&index, &moderation.config())?);
     Ok(if accept_json.0 {
-      Json(SatJson {
-        number: sat.0,
-        decimal: sat.decimal().to_string(),
-        degree: sat.degree().to_string(),
-        name: sat.name(),
-        block: sat.height().0,
-        cycle: sat.cycle(),
-        epoch: sat.epoch().0,
-        period: sat.period(),
-        offset: sat.third(),
-        rarity: sat.rarity(),
-        percentile: sat.percentile(),
-        satpoint,
-        timestamp: blocktime.timestamp().timestamp(),
-        inscriptions,
-      })
-      .into_response()
+      Self::envelope(
+        &index,
+        SatJson {
+          number: sat.0,
+          decimal: sat.decimal().to_string(),
+          degree: sat.degree().to_string(),
+          name: sat.name(),
+          block: sat.height().0,
+          cycle: sat.cycle(),
+          epoch: sat.epoch().0,
+          period: sat.period(),
+          offset: sat.third(),
+          rarity: sat.rarity(),
+          traits,
+          percentile: sat.percentile(),
+          satpoint,
+          timestamp: blocktime.timestamp().timestamp(),
+          inscriptions,
+        },
+        None,
+      )?
     } else {
       SatHtml {
         sat,
         satpoint,
         blocktime,
         inscriptions,
+        traits,
       }
       .page(page_config, index.has_sat_index()?)
       .into_response()
     })
   }
 
+  async fn sat_history(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(DeserializeFromStr(sat)): Path<DeserializeFromStr<Sat>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /sat/{sat}/history");
+
+    let html = SatHistoryHtml {
+      sat,
+      history: index.sat_history(sat)?.ok_or_else(|| {
+        ServerError::NotFound(
+          "sat history requires index created with `--index-sat-history` flag".into(),
+        )
+      })?,
+    };
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, SatHistoryJson::new(html), None)?
+    } else {
+      html
+        .page(page_config, index.has_sat_index()?)
+        .into_response()
+    })
+  }
+
   async fn ordinal(Path(sat): Path<String>) -> Redirect {
     log::info!("GET /ordinal/{sat}");
     Redirect::to(&format!("/sat/{sat}"))
@@ -559,14 +802,11 @@ impl Server {
     let inscriptions = index.get_inscriptions_on_output(outpoint)?;
 
     Ok(if accept_json.0 {
-      Json(OutputJson::new(
-        outpoint,
-        list,
-        page_config.chain,
-        output,
-        inscriptions,
-      ))
-      .into_response()
+      Self::envelope(
+        &index,
+        OutputJson::new(outpoint, list, page_config.chain, output, inscriptions),
+        None,
+      )?
     } else {
       OutputHtml {
         outpoint,
@@ -580,6 +820,115 @@ impl Server {
     })
   }
 
+  async fn content_hash(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(content_hash): Path<String>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /content-hash/{content_hash}");
+
+    let content_hash = content_hash
+      .parse::<sha256::Hash>()
+      .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+    let inscriptions = index.get_inscriptions_by_content_hash(content_hash.to_byte_array())?;
+
+    if inscriptions.is_empty() {
+      return Err(ServerError::NotFound(format!(
+        "no inscriptions with content hash {content_hash}"
+      )));
+    }
+
+    let html = ContentHashHtml {
+      content_hash,
+      inscriptions,
+    };
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, ContentHashJson::new(html), None)?
+    } else {
+      html
+        .page(page_config, index.has_sat_index()?)
+        .into_response()
+    })
+  }
+
+  async fn address(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Path(address): Path<String>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /address/{address}");
+
+    let address = address
+      .parse::<Address<NetworkUnchecked>>()
+      .map_err(|err| ServerError::BadRequest(err.to_string()))?
+      .require_network(page_config.chain.network())
+      .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+    let outputs = index
+      .get_address_outputs(&address.script_pubkey())?
+      .ok_or_else(|| {
+        ServerError::NotFound(
+          "looking up addresses requires index created with `--index-addresses` flag".into(),
+        )
+      })?;
+
+    let has_sat_index = index.has_sat_index()?;
+
+    let mut inscriptions = Vec::new();
+    let mut cardinal_balance = 0;
+    let mut rare_sats = if has_sat_index {
+      Some(Vec::new())
+    } else {
+      None
+    };
+
+    for (outpoint, value) in &outputs {
+      let output_inscriptions = index.get_inscriptions_on_output(*outpoint)?;
+
+      if output_inscriptions.is_empty() {
+        cardinal_balance += value;
+      }
+
+      inscriptions.extend(output_inscriptions);
+
+      if let Some(rare_sats) = &mut rare_sats {
+        if let Some(List::Unspent(ranges)) = index.list(*outpoint)? {
+          let mut offset = 0;
+          for (start, end) in ranges {
+            if !Sat(start).is_common() {
+              rare_sats.push((
+                Sat(start),
+                SatPoint {
+                  outpoint: *outpoint,
+                  offset,
+                },
+              ));
+            }
+            offset += end - start;
+          }
+        }
+      }
+    }
+
+    let html = AddressHtml {
+      address,
+      outputs,
+      inscriptions,
+      cardinal_balance,
+      rare_sats,
+    };
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, AddressJson::new(html), None)?
+    } else {
+      html.page(page_config, has_sat_index).into_response()
+    })
+  }
+
   async fn range(
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -598,13 +947,63 @@ impl Server {
     }
   }
 
-  async fn rare_txt(Extension(index): Extension<Arc<Index>>) -> ServerResult<RareTxt> {
+  async fn rare_txt(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(moderation): Extension<Arc<Moderation>>,
+    Query(params): Query<RareTxtParams>,
+  ) -> ServerResult<RareTxt> {
     log::info!("GET /rare.txt");
-    Ok(RareTxt(index.rare_sat_satpoints()?.ok_or_else(|| {
-      ServerError::NotFound(
-        "tracking rare sats requires index created with `--index-sats` flag".into(),
-      )
-    })?))
+    let trait_ranges = Self::trait_ranges(&index, &moderation.config())?;
+
+    Ok(RareTxt(
+      index
+        .rare_sat_satpoints()?
+        .ok_or_else(|| {
+          ServerError::NotFound(
+            "tracking rare sats requires index created with `--index-sats` flag".into(),
+          )
+        })?
+        .into_iter()
+        .map(|(sat, satpoint)| (sat, satpoint, SatTrait::of(sat, &trait_ranges)))
+        .filter(|(_, _, traits)| {
+          params
+            .sat_trait
+            .map_or(true, |sat_trait| traits.contains(&sat_trait))
+        })
+        .collect(),
+    ))
+  }
+
+  async fn sats(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(params): Query<SatsParams>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /sats");
+
+    let (sats, next) = index
+      .sats_with_trait(params.sat_trait, params.from, 100)?
+      .ok_or_else(|| {
+        ServerError::NotFound(
+          "finding sats by trait requires index created with `--index-traits` flag".into(),
+        )
+      })?;
+
+    let html = SatsHtml {
+      sat_trait: params.sat_trait,
+      sats,
+      next,
+    };
+
+    Ok(if accept_json.0 {
+      let next = html.next_url();
+      Self::envelope(&index, SatsJson::new(html), next)?
+    } else {
+      html
+        .page(page_config, index.has_sat_index()?)
+        .into_response()
+    })
   }
 
   async fn home(
@@ -661,9 +1060,10 @@ impl Server {
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(height): Path<u64>,
-  ) -> ServerResult<String> {
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
     log::info!("GET /transfers/{height}");
-    let mut ret = String::from("");
+    let mut transfers = Vec::new();
     for inscription_id in index.get_inscription_ids_by_height(height)? {
       let satpoint = index
         .get_inscription_satpoint_by_id(inscription_id)?
@@ -671,24 +1071,31 @@ impl Server {
       let address = if satpoint.outpoint == unbound_outpoint() {
         String::from("unbound")
       } else {
-        let output = index
-          .get_transaction(satpoint.outpoint.txid)?
-          .ok_or_not_found(|| format!("inscription {inscription_id} current transaction"))?
-          .output
-          .into_iter()
-          .nth(satpoint.outpoint.vout.try_into().unwrap())
+        let script_pubkey = index
+          .get_script_pubkey(satpoint.outpoint)?
           .ok_or_not_found(|| format!("inscription {inscription_id} current transaction output"))?;
-        if let Ok(address) = page_config.chain.address_from_script(&output.script_pubkey) {
+        if let Ok(address) = page_config.chain.address_from_script(&script_pubkey) {
           address.to_string()
         } else {
           String::from("error")
         }
       };
 
-      ret += &format!("{} {}\n", inscription_id, address);
+      transfers.push(TransferJson {
+        inscription_id,
+        address,
+      });
     }
 
-    Ok(ret)
+    Ok(if accept_json.0 {
+      Self::envelope(&index, transfers, None)?
+    } else {
+      transfers
+        .iter()
+        .map(|transfer| format!("{} {}\n", transfer.inscription_id, transfer.address))
+        .collect::<String>()
+        .into_response()
+    })
   }
 
   async fn transaction(
@@ -714,20 +1121,90 @@ impl Server {
     )
   }
 
-  async fn stats(Extension(index): Extension<Arc<Index>>) -> ServerResult<String> {
-    log::info!("GET /stats");
-    let stats = index.get_stats()?;
+  async fn openapi() -> Json<serde_json::Value> {
+    log::info!("GET /openapi.json");
+    Json(openapi::spec())
+  }
+
+  async fn metrics(Extension(index): Extension<Arc<Index>>) -> ServerResult<Response> {
     Ok(
-      serde_json::to_string_pretty(&StatsJson {
-        highest_block_indexed: stats.0,
-        lowest_inscription_number: stats.1,
-        highest_inscription_number: stats.2,
-      })
-      .ok()
-      .unwrap(),
+      (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        index.render_metrics()?,
+      )
+        .into_response(),
     )
   }
 
+  async fn stats(
+    Extension(index): Extension<Arc<Index>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /stats");
+    let stats = index.get_stats()?;
+    let stats = StatsJson {
+      highest_block_indexed: stats.0,
+      lowest_inscription_number: stats.1,
+      highest_inscription_number: stats.2,
+    };
+    Ok(if accept_json.0 {
+      Self::envelope(&index, stats, None)?
+    } else {
+      serde_json::to_string_pretty(&stats)
+        .ok()
+        .unwrap()
+        .into_response()
+    })
+  }
+
+  async fn issuance(
+    Extension(index): Extension<Arc<Index>>,
+    Query(params): Query<IssuanceParams>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /issuance");
+
+    if let Some(interval) = params.interval {
+      let mined = index.block_count()?;
+      if interval == 0 || (mined + interval - 1) / interval > 1000 {
+        return Err(ServerError::BadRequest(format!(
+          "interval must split {mined} blocks into at most 1000 ranges"
+        )));
+      }
+    }
+
+    let issuance = issuance::Output::new(&index, params.interval)?;
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, issuance, None)?
+    } else {
+      serde_json::to_string_pretty(&issuance)
+        .ok()
+        .unwrap()
+        .into_response()
+    })
+  }
+
+  async fn lost(
+    Extension(index): Extension<Arc<Index>>,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /lost");
+
+    let lost = lost_sats::Output::new(&index)?.ok_or_else(|| {
+      ServerError::NotFound("lost sats require index created with `--index-sats` flag".into())
+    })?;
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, lost, None)?
+    } else {
+      serde_json::to_string_pretty(&lost)
+        .ok()
+        .unwrap()
+        .into_response()
+    })
+  }
+
   async fn status(Extension(index): Extension<Arc<Index>>) -> (StatusCode, &'static str) {
     log::info!("GET /status");
     if index.is_unrecoverably_reorged() {
@@ -782,6 +1259,8 @@ impl Server {
       Ok(Redirect::to(&format!("/output/{query}")))
     } else if INSCRIPTION_ID.is_match(query) {
       Ok(Redirect::to(&format!("/inscription/{query}")))
+    } else if let Ok(Object::Address(_)) = query.parse::<Object>() {
+      Ok(Redirect::to(&format!("/address/{query}")))
     } else {
       Ok(Redirect::to(&format!("/sat/{query}")))
     }
@@ -958,55 +1437,257 @@ impl Server {
     Redirect::to("https://docs.ordinals.com/faq/")
   }
 
-  async fn bounties() -> Redirect {
-    log::info!("GET /bounties");
-    Redirect::to("https://docs.ordinals.com/bounty/")
+  fn is_hidden(
+    config: &Config,
+    index: &Index,
+    inscription_id: InscriptionId,
+    inscription: &Inscription,
+  ) -> ServerResult<bool> {
+    Ok(
+      Self::is_hidden_content(config, index, inscription_id, Some(inscription))?
+        || Self::is_hidden_sat(config, index, inscription_id)?,
+    )
   }
 
-  async fn content(
-    Extension(index): Extension<Arc<Index>>,
-    Extension(config): Extension<Arc<Config>>,
-    Path(inscription_id): Path<InscriptionId>,
-  ) -> ServerResult<Response> {
-    log::info!("GET /content/{inscription_id}");
-    if config.is_hidden(inscription_id) {
-      return Ok(PreviewUnknownHtml.into_response());
+  fn is_hidden_content(
+    config: &Config,
+    index: &Index,
+    inscription_id: InscriptionId,
+    inscription: Option<&Inscription>,
+  ) -> ServerResult<bool> {
+    if config.hidden_content_hashes.is_empty() {
+      return Ok(false);
     }
 
-    let inscription = index
-      .get_inscription_by_id(inscription_id)?
-      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+    let content_hash = match index.get_content_hash(inscription_id)? {
+      Some(content_hash) => Some(content_hash),
+      None => match inscription {
+        Some(inscription) => inscription.body().map(sha256::Hash::hash),
+        None => index
+          .get_inscription_by_id(inscription_id)?
+          .and_then(|inscription| inscription.body().map(sha256::Hash::hash)),
+      },
+    };
+
+    Ok(content_hash.map_or(false, |content_hash| config.is_hidden_content(content_hash)))
+  }
 
+  /// The trait ranges the trait index was built with, so that traits shown
+  /// for a sat agree with `/sats`, or the configured ones without one.
+  fn trait_ranges(index: &Index, config: &Config) -> ServerResult<TraitRanges> {
     Ok(
-      Self::content_response(inscription)
-        .ok_or_not_found(|| format!("inscription {inscription_id} content"))?
-        .into_response(),
+      index
+        .trait_ranges()?
+        .unwrap_or_else(|| config.trait_ranges.clone()),
     )
   }
 
-  fn content_response(inscription: Inscription) -> Option<(HeaderMap, Vec<u8>)> {
-    let mut headers = HeaderMap::new();
+  fn is_hidden_sat(
+    config: &Config,
+    index: &Index,
+    inscription_id: InscriptionId,
+  ) -> ServerResult<bool> {
+    if !config.hidden_sats.is_empty() {
+      if let Some(sat) = index
+        .get_inscription_entry(inscription_id)?
+        .and_then(|entry| entry.sat)
+      {
+        return Ok(config.is_hidden_sat(sat));
+      }
+    }
 
-    headers.insert(
-      header::CONTENT_TYPE,
-      inscription
-        .content_type()
-        .and_then(|content_type| content_type.parse().ok())
-        .unwrap_or(HeaderValue::from_static("application/octet-stream")),
-    );
-    headers.insert(
-      header::CONTENT_SECURITY_POLICY,
-      HeaderValue::from_static("default-src 'self' 'unsafe-eval' 'unsafe-inline' data: blob:"),
-    );
-    headers.append(
-      header::CONTENT_SECURITY_POLICY,
-      HeaderValue::from_static("default-src *:*/content/ *:*/blockheight *:*/blockhash *:*/blockhash/ *:*/blocktime 'unsafe-eval' 'unsafe-inline' data: blob:"),
-    );
+    Ok(false)
+  }
 
-    let body = inscription.into_body();
-    let cache_control = match body {
-      Some(_) => "max-age=31536000, immutable",
-      None => "max-age=600",
+  fn authorize_admin(config: &Config, headers: &HeaderMap) -> ServerResult<()> {
+    let Some(admin_token) = &config.admin_token else {
+      return Err(ServerError::NotFound(
+        "admin endpoint requires `admin_token` in config".into(),
+      ));
+    };
+
+    let token = headers
+      .get(header::AUTHORIZATION)
+      .and_then(|value| value.to_str().ok())
+      .and_then(|value| value.strip_prefix("Bearer "));
+
+    // Compare every byte, so response times don't reveal how much of a guessed
+    // token is correct.
+    let authorized = token.map_or(false, |token| {
+      token.len() == admin_token.len()
+        && token
+          .bytes()
+          .zip(admin_token.bytes())
+          .fold(0, |difference, (a, b)| difference | (a ^ b))
+          == 0
+    });
+
+    if !authorized {
+      return Err(ServerError::Unauthorized("invalid admin token".into()));
+    }
+
+    Ok(())
+  }
+
+  async fn admin_hidden(
+    Extension(moderation): Extension<Arc<Moderation>>,
+    headers: HeaderMap,
+  ) -> ServerResult<Response> {
+    log::info!("GET /admin/hidden");
+    Self::authorize_admin(&moderation.config(), &headers)?;
+    Ok(Json(moderation.hidden()).into_response())
+  }
+
+  async fn admin_hide(
+    Extension(moderation): Extension<Arc<Moderation>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(DeserializeFromStr(entry)): Path<DeserializeFromStr<HiddenEntry>>,
+  ) -> ServerResult<String> {
+    log::info!("PUT /admin/hidden/{entry}");
+    Self::admin_update(moderation, connect_info, headers, entry, true)
+  }
+
+  async fn admin_unhide(
+    Extension(moderation): Extension<Arc<Moderation>>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    Path(DeserializeFromStr(entry)): Path<DeserializeFromStr<HiddenEntry>>,
+  ) -> ServerResult<String> {
+    log::info!("DELETE /admin/hidden/{entry}");
+    Self::admin_update(moderation, connect_info, headers, entry, false)
+  }
+
+  fn admin_update(
+    moderation: Arc<Moderation>,
+    connect_info: Option<ConnectInfo<SocketAddr>>,
+    headers: HeaderMap,
+    entry: HiddenEntry,
+    hide: bool,
+  ) -> ServerResult<String> {
+    Self::authorize_admin(&moderation.config(), &headers)?;
+
+    let source = match connect_info {
+      Some(ConnectInfo(addr)) => format!("admin {}", addr.ip()),
+      None => "admin".into(),
+    };
+
+    let changed = moderation.update(entry, hide, &source)?;
+
+    Ok(match (hide, changed) {
+      (true, true) => format!("hid {entry}"),
+      (true, false) => format!("{entry} already hidden"),
+      (false, true) => format!("unhid {entry}"),
+      (false, false) => format!("{entry} not hidden"),
+    })
+  }
+
+  async fn bounties() -> Redirect {
+    log::info!("GET /bounties");
+    Redirect::to("https://docs.ordinals.com/bounty/")
+  }
+
+  async fn content(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(moderation): Extension<Arc<Moderation>>,
+    Path(inscription_id): Path<InscriptionId>,
+    request_headers: HeaderMap,
+  ) -> ServerResult<Response> {
+    log::info!("GET /content/{inscription_id}");
+    let config = moderation.config();
+
+    if config.is_hidden(inscription_id) {
+      return Ok(PreviewUnknownHtml.into_response());
+    }
+
+    let inscription = index
+      .get_inscription_by_id(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    if Self::is_hidden(&config, &index, inscription_id, &inscription)? {
+      return Ok(PreviewUnknownHtml.into_response());
+    }
+
+    let (mut headers, body) = Self::content_response(inscription)
+      .ok_or_not_found(|| format!("inscription {inscription_id} content"))?;
+
+    let content_hash = match index.get_content_hash(inscription_id)? {
+      Some(content_hash) => content_hash,
+      None => sha256::Hash::hash(&body),
+    };
+
+    let etag = format!("\"{content_hash}\"");
+
+    headers.insert(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+
+    if request_headers
+      .get(header::IF_NONE_MATCH)
+      .and_then(|value| value.to_str().ok())
+      .map(|value| {
+        value
+          .split(',')
+          .any(|tag| tag.trim() == etag || tag.trim() == "*")
+      })
+      .unwrap_or_default()
+    {
+      return Ok((StatusCode::NOT_MODIFIED, headers).into_response());
+    }
+
+    let range = request_headers
+      .get(header::RANGE)
+      .and_then(|value| value.to_str().ok())
+      .map(|value| ByteRange::parse(value, body.len()))
+      .unwrap_or(ByteRange::Full);
+
+    Ok(match range {
+      ByteRange::Full => (headers, body).into_response(),
+      ByteRange::Partial(range) => {
+        headers.insert(
+          header::CONTENT_RANGE,
+          HeaderValue::from_str(&format!(
+            "bytes {}-{}/{}",
+            range.start,
+            range.end - 1,
+            body.len()
+          ))
+          .unwrap(),
+        );
+        (StatusCode::PARTIAL_CONTENT, headers, body[range].to_vec()).into_response()
+      }
+      ByteRange::Unsatisfiable => {
+        headers.insert(
+          header::CONTENT_RANGE,
+          HeaderValue::from_str(&format!("bytes */{}", body.len())).unwrap(),
+        );
+        (StatusCode::RANGE_NOT_SATISFIABLE, headers).into_response()
+      }
+    })
+  }
+
+  fn content_response(inscription: Inscription) -> Option<(HeaderMap, Vec<u8>)> {
+    let mut headers = HeaderMap::new();
+
+    headers.insert(
+      header::CONTENT_TYPE,
+      inscription
+        .content_type()
+        .and_then(|content_type| content_type.parse().ok())
+        .unwrap_or(HeaderValue::from_static("application/octet-stream")),
+    );
+    headers.insert(
+      header::CONTENT_SECURITY_POLICY,
+      HeaderValue::from_static("default-src 'self' 'unsafe-eval' 'unsafe-inline' data: blob:"),
+    );
+    headers.append(
+      header::CONTENT_SECURITY_POLICY,
+      HeaderValue::from_static("default-src *:*/content/ *:*/blockheight *:*/blockhash *:*/blockhash/ *:*/blocktime 'unsafe-eval' 'unsafe-inline' data: blob:"),
+    );
+
+    let body = inscription.into_body();
+    let cache_control = match body {
+      Some(_) => "max-age=31536000, immutable",
+      None => "max-age=600",
     };
     headers.insert(
       header::CACHE_CONTROL,
@@ -1018,10 +1699,12 @@ impl Server {
 
   async fn preview(
     Extension(index): Extension<Arc<Index>>,
-    Extension(config): Extension<Arc<Config>>,
+    Extension(moderation): Extension<Arc<Moderation>>,
     Path(inscription_id): Path<InscriptionId>,
   ) -> ServerResult<Response> {
     log::info!("GET /preview/{inscription_id}");
+    let config = moderation.config();
+
     if config.is_hidden(inscription_id) {
       return Ok(PreviewUnknownHtml.into_response());
     }
@@ -1030,6 +1713,10 @@ impl Server {
       .get_inscription_by_id(inscription_id)?
       .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
 
+    if Self::is_hidden(&config, &index, inscription_id, &inscription)? {
+      return Ok(PreviewUnknownHtml.into_response());
+    }
+
     match inscription.media() {
       Media::Audio => Ok(PreviewAudioHtml { inscription_id }.into_response()),
       Media::Iframe => Ok(
@@ -1074,6 +1761,82 @@ impl Server {
     }
   }
 
+  async fn thumbnail(
+    Extension(index): Extension<Arc<Index>>,
+    Extension(moderation): Extension<Arc<Moderation>>,
+    Extension(thumbnail_cache): Extension<Arc<ThumbnailCache>>,
+    Path(inscription_id): Path<InscriptionId>,
+  ) -> ServerResult<Response> {
+    log::info!("GET /thumbnail/{inscription_id}");
+    let config = moderation.config();
+
+    if config.is_hidden(inscription_id) {
+      return Ok(Self::thumbnail_response(Thumbnail::placeholder(
+        Media::Unknown,
+      )));
+    }
+
+    if let Some(body) = thumbnail_cache.get(inscription_id) {
+      return Ok(Self::thumbnail_response(
+        if Self::is_hidden_content(&config, &index, inscription_id, None)?
+          || Self::is_hidden_sat(&config, &index, inscription_id)?
+        {
+          Thumbnail::placeholder(Media::Unknown)
+        } else {
+          Thumbnail::Image {
+            body,
+            content_type: "image/png".into(),
+          }
+        },
+      ));
+    }
+
+    let inscription = index
+      .get_inscription_by_id(inscription_id)?
+      .ok_or_not_found(|| format!("inscription {inscription_id}"))?;
+
+    if Self::is_hidden(&config, &index, inscription_id, &inscription)? {
+      return Ok(Self::thumbnail_response(Thumbnail::placeholder(
+        Media::Unknown,
+      )));
+    }
+
+    let thumbnail = task::block_in_place(|| Thumbnail::render(&inscription));
+
+    if let Thumbnail::Image { body, content_type } = &thumbnail {
+      if content_type == "image/png" {
+        if let Err(err) = thumbnail_cache.insert(inscription_id, body) {
+          log::warn!("failed to cache thumbnail for {inscription_id}: {err}");
+        }
+      }
+    }
+
+    Ok(Self::thumbnail_response(thumbnail))
+  }
+
+  fn thumbnail_response(thumbnail: Thumbnail) -> Response {
+    match thumbnail {
+      Thumbnail::Image { body, content_type } => (
+        [
+          (header::CONTENT_TYPE, content_type),
+          (
+            header::CACHE_CONTROL,
+            "max-age=31536000, immutable".to_string(),
+          ),
+          (header::CONTENT_SECURITY_POLICY, "default-src 'none'".into()),
+          (header::X_CONTENT_TYPE_OPTIONS, "nosniff".into()),
+        ],
+        body,
+      )
+        .into_response(),
+      Thumbnail::Placeholder(label) => (
+        [(header::CACHE_CONTROL, "max-age=600")],
+        ThumbnailSvg { label },
+      )
+        .into_response(),
+    }
+  }
+
   async fn inscription(
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1111,25 +1874,50 @@ impl Server {
 
     let next = index.get_inscription_id_by_inscription_number(entry.number + 1)?;
 
+    let content_hash = inscription.body().map(sha256::Hash::hash);
+
+    let (first_inscribed, duplicates) = match content_hash {
+      Some(content_hash) => {
+        let inscriptions = index.get_inscriptions_by_content_hash(content_hash.to_byte_array())?;
+        (
+          inscriptions
+            .first()
+            .filter(|(first, _number)| *first != inscription_id)
+            .cloned(),
+          inscriptions.len().saturating_sub(1),
+        )
+      }
+      None => (None, 0),
+    };
+
     Ok(if accept_json.0 {
-      Json(InscriptionJson::new(
-        page_config.chain,
-        entry.fee,
-        entry.height,
-        inscription,
-        inscription_id,
-        next,
-        entry.number,
-        output,
-        previous,
-        entry.sat,
-        satpoint,
-        timestamp(entry.timestamp),
-      ))
-      .into_response()
+      Self::envelope(
+        &index,
+        InscriptionJson::new(
+          page_config.chain,
+          content_hash,
+          duplicates,
+          first_inscribed,
+          entry.fee,
+          entry.height,
+          inscription,
+          inscription_id,
+          next,
+          entry.number,
+          output,
+          previous,
+          entry.sat,
+          satpoint,
+          timestamp(entry.timestamp),
+        ),
+        None,
+      )?
     } else {
       InscriptionHtml {
         chain: page_config.chain,
+        content_hash,
+        duplicates,
+        first_inscribed,
         genesis_fee: entry.fee,
         genesis_height: entry.height,
         inscription,
@@ -1156,6 +1944,76 @@ impl Server {
     Self::inscriptions_inner(page_config, index, None, 100, accept_json).await
   }
 
+  async fn inscriptions_query(
+    Extension(page_config): Extension<Arc<PageConfig>>,
+    Extension(index): Extension<Arc<Index>>,
+    Query(params): Query<InscriptionQueryParams>,
+    RawQuery(raw_query): RawQuery,
+    accept_json: AcceptJson,
+  ) -> ServerResult<Response> {
+    log::info!("GET /inscriptions/query");
+
+    let outputs = match params.address {
+      Some(address) => {
+        let address = address
+          .parse::<Address<NetworkUnchecked>>()
+          .map_err(|err| ServerError::BadRequest(err.to_string()))?
+          .require_network(page_config.chain.network())
+          .map_err(|err| ServerError::BadRequest(err.to_string()))?;
+
+        Some(
+          index
+            .get_address_outputs(&address.script_pubkey())?
+            .ok_or_else(|| {
+              ServerError::NotFound(
+                "filtering by address requires index created with `--index-addresses` flag".into(),
+              )
+            })?
+            .into_iter()
+            .map(|(outpoint, _value)| outpoint)
+            .collect(),
+        )
+      }
+      None => None,
+    };
+
+    let query = InscriptionQuery {
+      ascending: params.sort.unwrap_or_default() == Sort::Oldest,
+      content_type: params.content_type,
+      cursed: params.cursed,
+      max_height: params.max_height,
+      min_height: params.min_height,
+      outputs,
+      rarity: params.rarity,
+    };
+
+    let (inscriptions, next) = index.query_inscriptions(&query, params.from, 100)?;
+
+    let next = next.map(|next| {
+      let mut pairs = raw_query
+        .as_deref()
+        .unwrap_or_default()
+        .split('&')
+        .filter(|pair| !pair.is_empty() && !pair.starts_with("from="))
+        .collect::<Vec<&str>>()
+        .join("&");
+
+      if !pairs.is_empty() {
+        pairs.push('&');
+      }
+
+      format!("/inscriptions/query?{pairs}from={next}")
+    });
+
+    Ok(if accept_json.0 {
+      Self::envelope(&index, InscriptionQueryJson { inscriptions }, next)?
+    } else {
+      InscriptionQueryHtml { inscriptions, next }
+        .page(page_config, index.has_sat_index()?)
+        .into_response()
+    })
+  }
+
   async fn inscriptions_in_block(
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
@@ -1167,7 +2025,11 @@ impl Server {
     let inscriptions = index
       .get_inscriptions_in_block(&block_index_state.block_index.read().unwrap(), block_height)?;
     Ok(if accept_json.0 {
-      Json(InscriptionsJson::new(inscriptions, None, None, None, None)).into_response()
+      Self::envelope(
+        &index,
+        InscriptionsJson::new(inscriptions, None, None, None, None),
+        None,
+      )?
     } else {
       InscriptionsHtml {
         inscriptions,
@@ -1209,14 +2071,11 @@ impl Server {
     let (inscriptions, prev, next, lowest, highest) =
       index.get_latest_inscriptions_with_prev_and_next(n, from)?;
     Ok(if accept_json.0 {
-      Json(InscriptionsJson::new(
-        inscriptions,
-        prev,
-        next,
-        Some(lowest),
-        Some(highest),
-      ))
-      .into_response()
+      Self::envelope(
+        &index,
+        InscriptionsJson::new(inscriptions, prev, next, Some(lowest), Some(highest)),
+        prev.map(|prev| format!("/inscriptions/{prev}/{n}")),
+      )?
     } else {
       InscriptionsHtml {
         inscriptions,
@@ -1232,7 +2091,7 @@ impl Server {
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(start): Path<i64>,
-  ) -> ServerResult<String> {
+  ) -> ServerResult<Response> {
     log::info!("GET /inscriptions_json/{start}");
     Self::inscriptions_json(page_config, index, start, start + 1).await
   }
@@ -1241,7 +2100,7 @@ impl Server {
     Extension(page_config): Extension<Arc<PageConfig>>,
     Extension(index): Extension<Arc<Index>>,
     Path(path): Path<(i64, i64)>,
-  ) -> ServerResult<String> {
+  ) -> ServerResult<Response> {
     log::info!("GET /inscriptions_json/{}/{}", path.0, path.1);
     Self::inscriptions_json(page_config, index, path.0, path.1).await
   }
@@ -1251,22 +2110,18 @@ impl Server {
     index: Arc<Index>,
     start: i64,
     end: i64,
-  ) -> ServerResult<String> {
-    const MAX_JSON_INSCRIPTIONS: i64 = 1000;
+  ) -> ServerResult<Response> {
+    const PAGE_SIZE: i64 = 1000;
 
     match start.cmp(&end) {
       Ordering::Equal => Err(ServerError::BadRequest("range length == 0".to_string())),
       Ordering::Greater => Err(ServerError::BadRequest("range length < 0".to_string())),
       Ordering::Less => {
-        if end - start > MAX_JSON_INSCRIPTIONS {
-          return Err(ServerError::BadRequest(format!(
-            "range length > {MAX_JSON_INSCRIPTIONS}"
-          )));
-        }
+        let page_end = end.min(start.saturating_add(PAGE_SIZE));
 
         let mut ret = Vec::new();
 
-        for i in start..end {
+        for i in start..page_end {
           sleep(Duration::from_millis(0)).await;
           match index.get_inscription_id_by_inscription_number(i) {
             Err(_) => return Err(ServerError::BadRequest(format!("no inscription {i}"))),
@@ -1367,7 +2222,11 @@ impl Server {
           }
         }
 
-        Ok(serde_json::to_string_pretty(&ret).ok().unwrap())
+        Self::envelope(
+          &index,
+          ret,
+          (page_end < end).then(|| format!("/inscriptions_json/{page_end}/{end}")),
+        )
       }
     }
   }
@@ -1386,7 +2245,10 @@ impl Server {
 
 #[cfg(test)]
 mod tests {
-  use {super::*, reqwest::Url, std::net::TcpListener};
+  use {
+    super::*, crate::templates::sat_history::SatHistoryEntryJson, reqwest::Url,
+    std::net::TcpListener,
+  };
 
   struct TestServer {
     bitcoin_rpc_server: test_bitcoincore_rpc::Handle,
@@ -1525,6 +2387,22 @@ mod tests {
       reqwest::blocking::get(self.join_url(path.as_ref())).unwrap()
     }
 
+    fn get_with_header(
+      &self,
+      path: impl AsRef<str>,
+      name: header::HeaderName,
+      value: &str,
+    ) -> reqwest::blocking::Response {
+      if let Err(error) = self.index.update() {
+        log::error!("{error}");
+      }
+      reqwest::blocking::Client::new()
+        .get(self.join_url(path.as_ref()))
+        .header(name, value)
+        .send()
+        .unwrap()
+    }
+
     fn join_url(&self, url: &str) -> Url {
       self.url.join(url).unwrap()
     }
@@ -1848,6 +2726,14 @@ mod tests {
     );
   }
 
+  #[test]
+  fn search_for_address_returns_address() {
+    TestServer::new().assert_redirect(
+      "/search/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      "/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+    );
+  }
+
   #[test]
   fn http_to_https_redirect_with_path() {
     TestServer::new_with_args(&[], &["--redirect-http-to-https", "--https"]).assert_redirect(
@@ -2354,81 +3240,388 @@ mod tests {
     TestServer::new_with_sat_index().assert_response(
       "/rare.txt",
       StatusCode::OK,
-      "sat\tsatpoint
-0\t4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0
+      "sat\tsatpoint\ttraits
+0\t4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0\talpha,first-in-block,palindrome,vintage
 ",
     );
   }
 
   #[test]
-  fn rare_without_sat_index() {
-    TestServer::new().assert_response(
-      "/rare.txt",
-      StatusCode::NOT_FOUND,
-      "tracking rare sats requires index created with `--index-sats` flag",
-    );
-  }
-
-  #[test]
-  fn show_rare_txt_in_header_with_sat_index() {
-    TestServer::new_with_sat_index().assert_response_regex(
-      "/",
-      StatusCode::OK,
-      ".*
-      <a href=/clock>Clock</a>
-      <a href=/rare.txt>rare.txt</a>
-      <form action=/search method=get>.*",
-    );
-  }
+  fn rare_txt_can_be_filtered_by_trait() {
+    let server = TestServer::new_with_sat_index();
 
-  #[test]
-  fn rare_sat_location() {
-    TestServer::new_with_sat_index().assert_response_regex(
-      "/sat/0",
+    server.assert_response(
+      "/rare.txt?trait=palindrome",
       StatusCode::OK,
-      ".*>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0<.*",
+      "sat\tsatpoint\ttraits
+0\t4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0\talpha,first-in-block,palindrome,vintage
+",
     );
-  }
 
-  #[test]
-  fn dont_show_rare_txt_in_header_without_sat_index() {
-    TestServer::new().assert_response_regex(
-      "/",
+    server.assert_response(
+      "/rare.txt?trait=pizza",
       StatusCode::OK,
-      ".*
-      <a href=/clock>Clock</a>
-      <form action=/search method=get>.*",
+      "sat\tsatpoint\ttraits\n",
     );
-  }
 
-  #[test]
-  fn input() {
-    TestServer::new().assert_response_regex(
-      "/input/0/0/0",
-      StatusCode::OK,
-      ".*<title>Input /0/0/0</title>.*<h1>Input /0/0/0</h1>.*<dt>text</dt><dd>.*The Times 03/Jan/2009 Chancellor on brink of second bailout for banks</dd>.*",
+    server.assert_response_regex(
+      "/rare.txt?trait=foo",
+      StatusCode::BAD_REQUEST,
+      ".*invalid sat trait: foo.*",
     );
   }
 
   #[test]
-  fn input_missing() {
+  fn rare_without_sat_index() {
     TestServer::new().assert_response(
-      "/input/1/1/1",
+      "/rare.txt",
       StatusCode::NOT_FOUND,
-      "input /1/1/1 not found",
+      "tracking rare sats requires index created with `--index-sats` flag",
     );
   }
 
   #[test]
-  fn commits_are_tracked() {
-    let server = TestServer::new();
+  fn sats_with_trait() {
+    let server = TestServer::new_with_args(&["--index-traits", "--enable-json-api"], &[]);
 
-    thread::sleep(Duration::from_millis(100));
-    assert_eq!(server.index.statistic(crate::index::Statistic::Commits), 3);
+    let block = &server.mine_blocks(1)[0];
 
-    let info = server.index.info().unwrap();
-    assert_eq!(info.transactions.len(), 1);
-    assert_eq!(info.transactions[0].starting_block_count, 0);
+    server.assert_response_regex(
+      "/sats?trait=first-in-block",
+      StatusCode::OK,
+      format!(
+        ".*<h1>Sats with trait first-in-block</h1>
+<ul class=monospace>
+  <li><a href=/sat/0>0</a> at <a href=/output/4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0</a></li>
+  <li><a href=/sat/5000000000>5000000000</a> at <a href=/output/{0}:0>{0}:0:0</a></li>
+</ul>
+.*",
+        block.txdata[0].txid(),
+      ),
+    );
+
+    let response = server.get_with_header(
+      "/sats?trait=last-in-block",
+      header::ACCEPT,
+      "application/json",
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      serde_json::from_str::<Envelope<SatsJson>>(&response.text().unwrap())
+        .unwrap()
+        .data,
+      SatsJson {
+        sat_trait: SatTrait::LastInBlock,
+        sats: vec![
+          (
+            Sat(50 * COIN_VALUE - 1),
+            1,
+            SatPoint {
+              outpoint: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+                .parse()
+                .unwrap(),
+              offset: 50 * COIN_VALUE - 1,
+            }
+          ),
+          (
+            Sat(100 * COIN_VALUE - 1),
+            1,
+            SatPoint {
+              outpoint: OutPoint {
+                txid: block.txdata[0].txid(),
+                vout: 0,
+              },
+              offset: 50 * COIN_VALUE - 1,
+            }
+          ),
+        ],
+        next: None,
+      }
+    );
+
+    server.assert_response_regex(
+      "/sats?trait=first-in-block&from=5000000001",
+      StatusCode::OK,
+      ".*<p>No unspent sats with this trait.</p>\\s*</main>.*",
+    );
+  }
+
+  #[test]
+  fn sat_history() {
+    let server = TestServer::new_with_args(&["--index-sat-history", "--enable-json-api"], &[]);
+
+    let coinbase = server.mine_blocks(1)[0].txdata[0].txid();
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      fee: 0,
+      ..Default::default()
+    });
+    server.mine_blocks(1);
+
+    server.assert_response_regex(
+      "/sat/5000000001/history",
+      StatusCode::OK,
+      format!(
+        ".*<h1>Sat <a href=/sat/5000000001>5000000001</a> History</h1>
+<ol>
+  <li>block <a href=/block/1>1</a>: <a class=monospace href=/output/{coinbase}:0>{coinbase}:0:1</a></li>
+  <li>block <a href=/block/2>2</a>: <a class=monospace href=/output/{txid}:0>{txid}:0:1</a></li>
+</ol>.*"
+      ),
+    );
+
+    let response = server.get_with_header(
+      "/sat/5000000001/history",
+      header::ACCEPT,
+      "application/json",
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      serde_json::from_str::<Envelope<SatHistoryJson>>(&response.text().unwrap())
+        .unwrap()
+        .data,
+      SatHistoryJson {
+        sat: 5000000001,
+        history: vec![
+          SatHistoryEntryJson {
+            height: 1,
+            satpoint: SatPoint {
+              outpoint: OutPoint::new(coinbase, 0),
+              offset: 1,
+            },
+          },
+          SatHistoryEntryJson {
+            height: 2,
+            satpoint: SatPoint {
+              outpoint: OutPoint::new(txid, 0),
+              offset: 1,
+            },
+          },
+        ],
+      }
+    );
+  }
+
+  #[test]
+  fn sat_history_without_index() {
+    TestServer::new_with_sat_index().assert_response(
+      "/sat/0/history",
+      StatusCode::NOT_FOUND,
+      "sat history requires index created with `--index-sat-history` flag",
+    );
+  }
+
+  #[test]
+  fn sats_without_trait_index() {
+    TestServer::new_with_sat_index().assert_response(
+      "/sats?trait=vintage",
+      StatusCode::NOT_FOUND,
+      "finding sats by trait requires index created with `--index-traits` flag",
+    );
+  }
+
+  #[test]
+  fn issuance() {
+    let server = TestServer::new_with_args(&["--enable-json-api"], &[]);
+
+    server.mine_blocks(2);
+
+    let response =
+      server.get_with_header("/issuance?interval=2", header::ACCEPT, "application/json");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let issuance = serde_json::from_str::<Envelope<issuance::Output>>(&response.text().unwrap())
+      .unwrap()
+      .data;
+
+    assert_eq!(issuance.height, Some(2));
+    assert_eq!(issuance.issued, 150 * COIN_VALUE);
+    assert_eq!(issuance.remaining, Sat::SUPPLY - 150 * COIN_VALUE);
+    assert_eq!(issuance.epochs.len(), 33);
+    assert_eq!(
+      issuance
+        .next_halving
+        .map(|halving| (halving.height, halving.blocks)),
+      Some((SUBSIDY_HALVING_INTERVAL, SUBSIDY_HALVING_INTERVAL - 3))
+    );
+    assert_eq!(
+      issuance
+        .ranges
+        .iter()
+        .map(|range| (range.start, range.end, range.sats, range.rarities.uncommon))
+        .collect::<Vec<(u64, u64, u64, u64)>>(),
+      [(0, 2, 100 * COIN_VALUE, 1), (2, 3, 50 * COIN_VALUE, 1)]
+    );
+
+    server.assert_response_regex("/issuance", StatusCode::OK, r#"\{\n  "height": 2,.*"#);
+  }
+
+  #[test]
+  fn lost() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--index-sats", "--enable-json-api"],
+      &[],
+    );
+
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      fee: 50 * COIN_VALUE,
+      witness: inscription("text/plain", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks_with_subsidy(1, 0);
+
+    let response = server.get_with_header("/lost", header::ACCEPT, "application/json");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let lost = serde_json::from_str::<Envelope<lost_sats::Output>>(&response.text().unwrap())
+      .unwrap()
+      .data;
+
+    pretty_assert_eq!(
+      lost,
+      lost_sats::Output {
+        lost_sats: 50 * COIN_VALUE,
+        ranges: vec![lost_sats::LostSatRange {
+          start: 50 * COIN_VALUE,
+          end: 100 * COIN_VALUE,
+          size: 50 * COIN_VALUE,
+          offset: 0,
+          height: 2,
+          cause: LostCause::Unclaimed,
+        }],
+        inscriptions: vec![lost_sats::LostInscription {
+          inscription_id: InscriptionId::from(txid),
+          number: 0,
+          satpoint: SatPoint {
+            outpoint: OutPoint::null(),
+            offset: 0,
+          },
+          height: 2,
+          cause: lost_sats::LostInscriptionCause::Unclaimed,
+        }],
+      }
+    );
+  }
+
+  #[test]
+  fn lost_without_sat_index() {
+    TestServer::new().assert_response(
+      "/lost",
+      StatusCode::NOT_FOUND,
+      "lost sats require index created with `--index-sats` flag",
+    );
+  }
+
+  #[test]
+  fn issuance_interval_must_be_reasonable() {
+    let server = TestServer::new();
+
+    server.assert_response(
+      "/issuance?interval=0",
+      StatusCode::BAD_REQUEST,
+      "interval must split 1 blocks into at most 1000 ranges",
+    );
+  }
+
+  #[test]
+  fn address_without_address_index() {
+    TestServer::new().assert_response(
+      "/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      StatusCode::NOT_FOUND,
+      "looking up addresses requires index created with `--index-addresses` flag",
+    );
+  }
+
+  #[test]
+  fn address_for_wrong_network() {
+    TestServer::new_with_args(&["--index-addresses"], &[]).assert_response_regex(
+      "/address/tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz",
+      StatusCode::BAD_REQUEST,
+      ".*network.*",
+    );
+  }
+
+  #[test]
+  fn address_without_outputs() {
+    TestServer::new_with_args(&["--index-addresses"], &[]).assert_response_regex(
+      "/address/bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      StatusCode::OK,
+      ".*<h1>Address <span class=monospace>bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4</span></h1>
+<dl>
+  <dt>cardinal balance</dt><dd>0</dd>
+</dl>
+<h2>0 Outputs</h2>.*",
+    );
+  }
+
+  #[test]
+  fn show_rare_txt_in_header_with_sat_index() {
+    TestServer::new_with_sat_index().assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*
+      <a href=/clock>Clock</a>
+      <a href=/rare.txt>rare.txt</a>
+      <form action=/search method=get>.*",
+    );
+  }
+
+  #[test]
+  fn rare_sat_location() {
+    TestServer::new_with_sat_index().assert_response_regex(
+      "/sat/0",
+      StatusCode::OK,
+      ".*>4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0<.*",
+    );
+  }
+
+  #[test]
+  fn dont_show_rare_txt_in_header_without_sat_index() {
+    TestServer::new().assert_response_regex(
+      "/",
+      StatusCode::OK,
+      ".*
+      <a href=/clock>Clock</a>
+      <form action=/search method=get>.*",
+    );
+  }
+
+  #[test]
+  fn input() {
+    TestServer::new().assert_response_regex(
+      "/input/0/0/0",
+      StatusCode::OK,
+      ".*<title>Input /0/0/0</title>.*<h1>Input /0/0/0</h1>.*<dt>text</dt><dd>.*The Times 03/Jan/2009 Chancellor on brink of second bailout for banks</dd>.*",
+    );
+  }
+
+  #[test]
+  fn input_missing() {
+    TestServer::new().assert_response(
+      "/input/1/1/1",
+      StatusCode::NOT_FOUND,
+      "input /1/1/1 not found",
+    );
+  }
+
+  #[test]
+  fn commits_are_tracked() {
+    let server = TestServer::new();
+
+    thread::sleep(Duration::from_millis(100));
+    assert_eq!(server.index.statistic(crate::index::Statistic::Commits), 3);
+
+    let info = server.index.info().unwrap();
+    assert_eq!(info.transactions.len(), 1);
+    assert_eq!(info.transactions[0].starting_block_count, 0);
 
     server.index.update().unwrap();
 
@@ -2668,6 +3861,117 @@ mod tests {
     );
   }
 
+  #[test]
+  fn image_thumbnails_are_downscaled_and_cached() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("image/png", thumbnail::png(1024, 512)).to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let inscription_id = InscriptionId::from(txid);
+
+    let response = server.get(format!("/thumbnail/{inscription_id}"));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    assert_eq!(
+      response.headers()[header::CACHE_CONTROL],
+      "max-age=31536000, immutable"
+    );
+    assert_eq!(
+      response.headers()[header::CONTENT_SECURITY_POLICY],
+      "default-src 'none'"
+    );
+    let body = response.bytes().unwrap();
+    assert_eq!(&body[16..24], &[0, 0, 1, 0, 0, 0, 0, 128]);
+
+    let cached = server
+      .tempdir
+      .path()
+      .join(format!("regtest/thumbnails/{inscription_id}.png"));
+    assert_eq!(fs::read(&cached).unwrap(), body);
+
+    assert_eq!(
+      server
+        .get(format!("/thumbnail/{inscription_id}"))
+        .bytes()
+        .unwrap(),
+      body
+    );
+  }
+
+  #[test]
+  fn cached_thumbnails_respect_hidden_content() {
+    let bitcoin_rpc_server = test_bitcoincore_rpc::builder()
+      .network(bitcoin::network::constants::Network::Regtest)
+      .build();
+    bitcoin_rpc_server.mine_blocks(1);
+    let png = thumbnail::png(512, 512);
+    let txid = bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("image/png", png.clone()).to_witness(),
+      ..Default::default()
+    });
+    let inscription_id = InscriptionId::from(txid);
+    bitcoin_rpc_server.mine_blocks(1);
+
+    let server = TestServer::new_server(
+      bitcoin_rpc_server,
+      Some("hidden:\nadmin_token: foo\n".into()),
+      &["--chain", "regtest"],
+      &[],
+    );
+
+    let response = server.get(format!("/thumbnail/{inscription_id}"));
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/png");
+    assert!(server
+      .tempdir
+      .path()
+      .join(format!("regtest/thumbnails/{inscription_id}.png"))
+      .exists());
+
+    let hash = sha256::Hash::hash(&png);
+    let response = reqwest::blocking::Client::new()
+      .put(server.join_url(&format!("/admin/hidden/{hash}")))
+      .bearer_auth("foo")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = server.get(format!("/thumbnail/{inscription_id}"));
+    assert_eq!(response.headers()[header::CACHE_CONTROL], "max-age=600");
+  }
+
+  #[test]
+  fn text_thumbnails_are_placeholders() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let response = server.get(format!("/thumbnail/{}", InscriptionId::from(txid)));
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()[header::CONTENT_TYPE], "image/svg+xml");
+    assert_regex_match!(response.text().unwrap(), r"(?s).*<text .*>text</text>.*");
+
+    server.assert_response_regex(
+      format!("/thumbnail/{}", inscription_id(1)),
+      StatusCode::NOT_FOUND,
+      ".*",
+    );
+  }
+
   #[test]
   fn audio_preview() {
     let server = TestServer::new_with_regtest();
@@ -2953,6 +4257,534 @@ mod tests {
     );
   }
 
+  #[test]
+  fn content_is_served_from_content_index() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::builder()
+        .network(bitcoin::Network::Regtest)
+        .build(),
+      None,
+      &["--chain", "regtest", "--index-content"],
+      &[],
+    );
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/foo", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    assert!(server.index.has_content_index().unwrap());
+
+    let response = server.get(format!("/content/{}", InscriptionId::from(txid)));
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/foo"
+    );
+    assert_eq!(
+      response.headers().get(header::ETAG).unwrap(),
+      &format!("\"{}\"", sha256::Hash::hash(b"hello"))
+    );
+    assert_eq!(response.text().unwrap(), "hello");
+  }
+
+  #[test]
+  fn content_responses_have_etags() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/foo", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let path = format!("/content/{}", InscriptionId::from(txid));
+
+    let etag = format!("\"{}\"", sha256::Hash::hash(b"hello"));
+
+    let response = server.get(&path);
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers().get(header::ETAG).unwrap(), &etag);
+    assert_eq!(
+      response.headers().get(header::ACCEPT_RANGES).unwrap(),
+      "bytes"
+    );
+
+    let response = server.get_with_header(&path, header::IF_NONE_MATCH, &etag);
+    assert_eq!(response.status(), StatusCode::NOT_MODIFIED);
+    assert_eq!(response.text().unwrap(), "");
+
+    let response = server.get_with_header(&path, header::IF_NONE_MATCH, "\"foo\"");
+    assert_eq!(response.status(), StatusCode::OK);
+  }
+
+  #[test]
+  fn content_range_requests() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(1);
+
+    let txid = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/foo", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let path = format!("/content/{}", InscriptionId::from(txid));
+
+    let response = server.get_with_header(&path, header::RANGE, "bytes=1-3");
+    assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+    assert_eq!(
+      response.headers().get(header::CONTENT_RANGE).unwrap(),
+      "bytes 1-3/5"
+    );
+    assert_eq!(response.text().unwrap(), "ell");
+
+    let response = server.get_with_header(&path, header::RANGE, "bytes=5-");
+    assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+    assert_eq!(
+      response.headers().get(header::CONTENT_RANGE).unwrap(),
+      "bytes */5"
+    );
+  }
+
+  #[test]
+  fn inscription_page_shows_duplicates() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let first = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/foo", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let second = server.bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(2, 0, 0)],
+      witness: inscription("text/bar", "hello").to_witness(),
+      ..Default::default()
+    });
+
+    server.mine_blocks(1);
+
+    let hash = "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824";
+
+    server.assert_response_regex(
+      format!("/inscription/{}", InscriptionId::from(first)),
+      StatusCode::OK,
+      format!(
+        ".*<dt>content hash</dt>
+  <dd><a class=monospace href=/content-hash/{hash}>{hash}</a></dd>
+  <dt>duplicates</dt>
+  <dd><a href=/content-hash/{hash}>1</a></dd>.*"
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/inscription/{}", InscriptionId::from(second)),
+      StatusCode::OK,
+      format!(
+        ".*<dt>first inscribed as</dt>\n  <dd><a href=/inscription/{}>#0</a></dd>.*",
+        InscriptionId::from(first)
+      ),
+    );
+
+    server.assert_response_regex(
+      format!("/content-hash/{hash}"),
+      StatusCode::OK,
+      format!(
+        ".*<h2>2 Inscriptions</h2>.*<li><a href=/inscription/{}>#0</a> first inscribed</li>\n  <li><a href=/inscription/{}>#1</a></li>.*",
+        InscriptionId::from(first),
+        InscriptionId::from(second),
+      ),
+    );
+  }
+
+  #[test]
+  fn inscriptions_query() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let mut inscriptions = Vec::new();
+    for (input, content_type) in [(1, "text/foo"), (2, "text/bar")] {
+      inscriptions.push(InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+        TransactionTemplate {
+          inputs: &[(input, 0, 0)],
+          witness: inscription(content_type, "hello").to_witness(),
+          ..Default::default()
+        },
+      )));
+      server.mine_blocks(1);
+    }
+
+    server.assert_response_regex(
+      "/inscriptions/query?content_type=text/foo",
+      StatusCode::OK,
+      format!(
+        ".*<h1>Inscriptions</h1>
+<div class=thumbnails>
+  <a href=/inscription/{}>.*</a>
+</div>
+.*",
+        inscriptions[0]
+      ),
+    );
+
+    server.assert_response_regex(
+      "/inscriptions/query?cursed=false&from=5",
+      StatusCode::OK,
+      format!(
+        ".*<h1>Inscriptions</h1>
+<div class=thumbnails>
+  <a href=/inscription/{}>.*</a>
+  <a href=/inscription/{}>.*</a>
+</div>
+.*",
+        inscriptions[1], inscriptions[0]
+      ),
+    );
+
+    server.assert_response_regex(
+      "/inscriptions/query?cursed=true",
+      StatusCode::OK,
+      ".*<h1>Inscriptions</h1>\n<p>No matching inscriptions.</p>\n.*",
+    );
+
+    server.assert_response_regex(
+      "/inscriptions/query?rarity=foo",
+      StatusCode::BAD_REQUEST,
+      ".*",
+    );
+  }
+
+  #[test]
+  fn inscriptions_query_by_address_requires_address_index() {
+    TestServer::new().assert_response(
+      "/inscriptions/query?address=bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+      StatusCode::NOT_FOUND,
+      "filtering by address requires index created with `--index-addresses` flag",
+    );
+  }
+
+  #[test]
+  fn openapi_spec_describes_json_routes() {
+    let response = TestServer::new().get("/openapi.json");
+
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let spec = serde_json::from_str::<serde_json::Value>(&response.text().unwrap()).unwrap();
+
+    assert_eq!(spec["openapi"], "3.0.3");
+    assert_eq!(
+      spec["paths"]["/inscription/{inscription_id}"]["get"]["responses"]["200"]["content"]
+        ["application/json"]["schema"]["properties"]["data"]["$ref"],
+      "#/components/schemas/InscriptionJson"
+    );
+  }
+
+  #[test]
+  fn metrics() {
+    let server = TestServer::new();
+
+    server.assert_response_regex("/sat/0", StatusCode::OK, ".*");
+    server.assert_response_regex("/sat/1", StatusCode::OK, ".*");
+    server.assert_response_regex("/sat/2099999997690000", StatusCode::BAD_REQUEST, ".*");
+
+    let response = server.get("/metrics");
+
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+      response.headers().get(header::CONTENT_TYPE).unwrap(),
+      "text/plain; version=0.0.4"
+    );
+
+    let metrics = response.text().unwrap();
+
+    for line in [
+      "ord_index_height 0",
+      "ord_bitcoind_height 0",
+      "ord_index_statistic{statistic=\"lost_sats\"} 0",
+      "ord_http_requests_total{route=\"/sat/:sat\",status=\"200\"} 2",
+      "ord_http_requests_total{route=\"/sat/:sat\",status=\"400\"} 1",
+      "ord_http_request_duration_seconds_count{route=\"/sat/:sat\"} 3",
+    ] {
+      assert!(
+        metrics.lines().any(|l| l == line),
+        "missing `{line}` in:\n{metrics}"
+      );
+    }
+
+    assert_regex_match!(
+      metrics,
+      r#"(?s).*\nord_rpc_calls_total\{method="getblockcount"\} [1-9]\d*\n.*"#
+    );
+  }
+
+  #[test]
+  fn requests_are_rate_limited() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::spawn(),
+      Some("hidden:\nrate_limits:\n  html:\n    requests_per_minute: 2\napi_keys:\n  foo:\n    requests_per_minute: 10\n".into()),
+      &["--enable-json-api"],
+      &[],
+    );
+
+    server.assert_response_regex("/sat/0", StatusCode::OK, ".*");
+    server.assert_response_regex("/sat/0", StatusCode::OK, ".*");
+
+    let response = server.get("/sat/0");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "30");
+    assert_eq!(
+      response.text().unwrap(),
+      "rate limit exceeded, retry after 30 seconds"
+    );
+
+    let response = server.get_with_header("/sat/0", header::ACCEPT, "application/json");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    server.assert_response_regex("/status", StatusCode::OK, ".*");
+
+    let response = server.get_with_header(
+      "/sat/0",
+      header::HeaderName::from_static("x-api-key"),
+      "foo",
+    );
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = server.get_with_header(
+      "/sat/0",
+      header::HeaderName::from_static("x-api-key"),
+      "bar",
+    );
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(response.text().unwrap(), "unknown API key");
+  }
+
+  #[test]
+  fn inscriptions_json_is_enveloped() {
+    let server = TestServer::new_with_regtest();
+    server.mine_blocks(2);
+
+    let mut inscriptions = Vec::new();
+    for input in 1..=2 {
+      inscriptions.push(InscriptionId::from(server.bitcoin_rpc_server.broadcast_tx(
+        TransactionTemplate {
+          inputs: &[(input, 0, 0)],
+          witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+          ..Default::default()
+        },
+      )));
+      server.mine_blocks(1);
+    }
+
+    let response = server.get("/inscriptions_json/0/2");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let envelope =
+      serde_json::from_str::<Envelope<Vec<serde_json::Value>>>(&response.text().unwrap()).unwrap();
+
+    assert_eq!(
+      envelope
+        .data
+        .iter()
+        .map(|inscription| inscription["id"].as_str().unwrap().parse().unwrap())
+        .collect::<Vec<InscriptionId>>(),
+      inscriptions
+    );
+    assert_eq!(envelope.next, None);
+    assert_eq!(envelope.height, Some(4));
+
+    let response = server.get("/inscriptions_json/1/1");
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(
+      serde_json::from_str::<ErrorJson>(&response.text().unwrap()).unwrap(),
+      ErrorJson {
+        status: 400,
+        message: "range length == 0".into(),
+      }
+    );
+  }
+
+  #[test]
+  fn rate_limited_json_errors_keep_retry_hint() {
+    let server = TestServer::new_server(
+      test_bitcoincore_rpc::spawn(),
+      Some("hidden:\nrate_limits:\n  json:\n    requests_per_minute: 1\n".into()),
+      &["--enable-json-api"],
+      &[],
+    );
+
+    let response = server.get_with_header("/sat/0", header::ACCEPT, "application/json");
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = server.get_with_header("/sat/0", header::ACCEPT, "application/json");
+    assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+    assert_eq!(response.headers().get(header::RETRY_AFTER).unwrap(), "60");
+    assert_eq!(
+      serde_json::from_str::<ErrorJson>(&response.text().unwrap()).unwrap(),
+      ErrorJson {
+        status: 429,
+        message: "rate limit exceeded, retry after 60 seconds".into(),
+      }
+    );
+  }
+
+  #[test]
+  fn inscriptions_can_be_hidden_by_admin_endpoint() {
+    let bitcoin_rpc_server = test_bitcoincore_rpc::builder()
+      .network(bitcoin::network::constants::Network::Regtest)
+      .build();
+    bitcoin_rpc_server.mine_blocks(1);
+    let txid = bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription = InscriptionId::from(txid);
+    bitcoin_rpc_server.mine_blocks(1);
+
+    let server = TestServer::new_server(
+      bitcoin_rpc_server,
+      Some("hidden:\nadmin_token: foo\n".into()),
+      &["--chain", "regtest"],
+      &[],
+    );
+
+    let client = reqwest::blocking::Client::new();
+
+    let hash = sha256::Hash::hash(b"hello");
+
+    let response = client
+      .put(server.join_url(&format!("/admin/hidden/{hash}")))
+      .bearer_auth("bar")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    server.assert_response(format!("/content/{inscription}"), StatusCode::OK, "hello");
+
+    let response = client
+      .put(server.join_url(&format!("/admin/hidden/{hash}")))
+      .bearer_auth("foo")
+      .send()
+      .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.text().unwrap(), format!("hid {hash}"));
+
+    server.assert_response(
+      format!("/content/{inscription}"),
+      StatusCode::OK,
+      &fs::read_to_string("templates/preview-unknown.html").unwrap(),
+    );
+
+    let response = server.get_with_header("/admin/hidden", header::AUTHORIZATION, "Bearer foo");
+    assert_eq!(
+      serde_json::from_str::<moderation::HiddenJson>(&response.text().unwrap()).unwrap(),
+      moderation::HiddenJson {
+        content_hashes: vec![hash],
+        inscriptions: Vec::new(),
+        sats: Vec::new(),
+      }
+    );
+
+    let response = client
+      .delete(server.join_url(&format!("/admin/hidden/{hash}")))
+      .bearer_auth("foo")
+      .send()
+      .unwrap();
+    assert_eq!(response.text().unwrap(), format!("unhid {hash}"));
+
+    server.assert_response(format!("/content/{inscription}"), StatusCode::OK, "hello");
+
+    let log = fs::read_to_string(server.tempdir.path().join("regtest/moderation.log")).unwrap();
+    assert_eq!(log.lines().count(), 2);
+    assert_regex_match!(
+      log,
+      format!(
+        r#"\{{"action":"hide","entry":"{hash}","source":"admin 127.0.0.1","timestamp":\d+}}\n.*\n"#
+      )
+    );
+  }
+
+  #[test]
+  fn admin_endpoint_requires_token_in_config() {
+    TestServer::new().assert_response(
+      "/admin/hidden",
+      StatusCode::NOT_FOUND,
+      "admin endpoint requires `admin_token` in config",
+    );
+  }
+
+  #[test]
+  fn hidden_inscriptions_are_reloaded_when_config_changes() {
+    let bitcoin_rpc_server = test_bitcoincore_rpc::builder()
+      .network(bitcoin::network::constants::Network::Regtest)
+      .build();
+    bitcoin_rpc_server.mine_blocks(1);
+    let txid = bitcoin_rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(1, 0, 0)],
+      witness: inscription("text/plain;charset=utf-8", "hello").to_witness(),
+      ..Default::default()
+    });
+    let inscription = InscriptionId::from(txid);
+    bitcoin_rpc_server.mine_blocks(1);
+
+    let server = TestServer::new_server(
+      bitcoin_rpc_server,
+      Some("hidden:\n".into()),
+      &["--chain", "regtest"],
+      &[],
+    );
+
+    server.assert_response(format!("/content/{inscription}"), StatusCode::OK, "hello");
+
+    fs::write(
+      server.tempdir.path().join("ord.yaml"),
+      format!("hidden:\n- {inscription}\n"),
+    )
+    .unwrap();
+
+    for attempt in 0.. {
+      if server
+        .get(format!("/content/{inscription}"))
+        .text()
+        .unwrap()
+        != "hello"
+      {
+        break;
+      }
+
+      assert!(attempt < 100, "config was not reloaded");
+
+      thread::sleep(Duration::from_millis(100));
+    }
+  }
+
+  #[test]
+  fn content_hash_not_found() {
+    let server = TestServer::new();
+
+    server.assert_response(
+      "/content-hash/2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+      StatusCode::NOT_FOUND,
+      "no inscriptions with content hash 2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824",
+    );
+
+    server.assert_response_regex("/content-hash/foo", StatusCode::BAD_REQUEST, ".*");
+  }
+
   #[test]
   fn inscriptions_page_with_no_prev_or_next() {
     TestServer::new_with_regtest_with_index_sats().assert_response_regex(
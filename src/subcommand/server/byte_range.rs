@@ -0,0 +1,70 @@
+use {super::*, std::ops::Range};
+
+#[derive(Debug, PartialEq)]
+pub(super) enum ByteRange {
+  Full,
+  Partial(Range<usize>),
+  Unsatisfiable,
+}
+
+impl ByteRange {
+  /// Parse a `Range` header for a body of `len` bytes. Multiple ranges and
+  /// malformed headers are ignored and the full body is served.
+  pub(super) fn parse(header: &str, len: usize) -> Self {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+      return Self::Full;
+    };
+
+    if spec.contains(',') {
+      return Self::Full;
+    }
+
+    let Some((start, end)) = spec.trim().split_once('-') else {
+      return Self::Full;
+    };
+
+    let range = match (start.parse::<usize>(), end.parse::<usize>()) {
+      (Ok(start), Ok(end)) if start <= end => start..(end + 1).min(len),
+      (Ok(_), Ok(_)) => return Self::Full,
+      (Ok(start), Err(_)) if end.is_empty() => start..len,
+      (Err(_), Ok(suffix)) if start.is_empty() => {
+        if suffix == 0 {
+          return Self::Unsatisfiable;
+        }
+        len.saturating_sub(suffix)..len
+      }
+      _ => return Self::Full,
+    };
+
+    if range.start >= len {
+      Self::Unsatisfiable
+    } else {
+      Self::Partial(range)
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn parse() {
+    #[track_caller]
+    fn case(header: &str, expected: ByteRange) {
+      assert_eq!(ByteRange::parse(header, 10), expected);
+    }
+
+    case("bytes=0-4", ByteRange::Partial(0..5));
+    case("bytes=5-", ByteRange::Partial(5..10));
+    case("bytes=-3", ByteRange::Partial(7..10));
+    case("bytes=-20", ByteRange::Partial(0..10));
+    case("bytes=8-100", ByteRange::Partial(8..10));
+    case("bytes=10-", ByteRange::Unsatisfiable);
+    case("bytes=-0", ByteRange::Unsatisfiable);
+    case("bytes=4-2", ByteRange::Full);
+    case("bytes=0-1,4-5", ByteRange::Full);
+    case("items=0-4", ByteRange::Full);
+    case("bytes=a-b", ByteRange::Full);
+  }
+}
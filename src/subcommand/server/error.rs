@@ -4,6 +4,8 @@ pub(super) enum ServerError {
   Internal(Error),
   BadRequest(String),
   NotFound(String),
+  TooManyRequests { retry_after: u64 },
+  Unauthorized(String),
 }
 
 pub(super) type ServerResult<T> = Result<T, ServerError>;
@@ -23,6 +25,13 @@ impl IntoResponse for ServerError {
       }
       Self::NotFound(message) => (StatusCode::NOT_FOUND, message).into_response(),
       Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message).into_response(),
+      Self::TooManyRequests { retry_after } => (
+        StatusCode::TOO_MANY_REQUESTS,
+        [(header::RETRY_AFTER, retry_after.to_string())],
+        format!("rate limit exceeded, retry after {retry_after} seconds"),
+      )
+        .into_response(),
+      Self::Unauthorized(message) => (StatusCode::UNAUTHORIZED, message).into_response(),
     }
   }
 }
@@ -0,0 +1,560 @@
+use super::*;
+
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub(super) enum HiddenEntry {
+  ContentHash(sha256::Hash),
+  Inscription(InscriptionId),
+  Sat(Sat),
+}
+
+impl FromStr for HiddenEntry {
+  type Err = Error;
+
+  fn from_str(s: &str) -> Result<Self, Self::Err> {
+    if let Ok(inscription_id) = s.parse() {
+      Ok(Self::Inscription(inscription_id))
+    } else if s.len() == 64 && s.chars().all(|c| c.is_ascii_hexdigit()) {
+      Ok(Self::ContentHash(s.parse()?))
+    } else {
+      Ok(Self::Sat(s.parse().map_err(|_| {
+        anyhow!("`{s}` is not an inscription ID, content hash, or sat")
+      })?))
+    }
+  }
+}
+
+impl Display for HiddenEntry {
+  fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+    match self {
+      Self::ContentHash(content_hash) => write!(f, "{content_hash}"),
+      Self::Inscription(inscription_id) => write!(f, "{inscription_id}"),
+      Self::Sat(sat) => write!(f, "{}", sat.n()),
+    }
+  }
+}
+
+impl Serialize for HiddenEntry {
+  fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+  where
+    S: Serializer,
+  {
+    serializer.collect_str(self)
+  }
+}
+
+impl<'de> Deserialize<'de> for HiddenEntry {
+  fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+  where
+    D: Deserializer<'de>,
+  {
+    Ok(DeserializeFromStr::deserialize(deserializer)?.0)
+  }
+}
+
+impl HiddenEntry {
+  fn is_in(self, config: &Config) -> bool {
+    match self {
+      Self::ContentHash(hash) => config.hidden_content_hashes.contains(&hash),
+      Self::Inscription(inscription_id) => config.hidden.contains(&inscription_id),
+      Self::Sat(sat) => config.hidden_sats.contains(&sat),
+    }
+  }
+
+  /// Add or remove this entry from `config`, returning false if it was
+  /// already in that state.
+  fn set(self, config: &mut Config, hide: bool) -> bool {
+    match (self, hide) {
+      (Self::ContentHash(hash), true) => config.hidden_content_hashes.insert(hash),
+      (Self::ContentHash(hash), false) => config.hidden_content_hashes.remove(&hash),
+      (Self::Inscription(inscription_id), true) => config.hidden.insert(inscription_id),
+      (Self::Inscription(inscription_id), false) => config.hidden.remove(&inscription_id),
+      (Self::Sat(sat), true) => config.hidden_sats.insert(sat),
+      (Self::Sat(sat), false) => config.hidden_sats.remove(&sat),
+    }
+  }
+}
+
+/// Entries hidden and unhidden through the admin endpoint, which differ from
+/// the config file. They are kept in their own file and applied on top of the
+/// config file whenever it is loaded, so the config file is never rewritten.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AdminState {
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  hidden: Vec<HiddenEntry>,
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  unhidden: Vec<HiddenEntry>,
+}
+
+impl AdminState {
+  fn load(path: &std::path::Path) -> Result<Self> {
+    match fs::read_to_string(path) {
+      Ok(state) => serde_yaml::from_str(&state)
+        .with_context(|| format!("failed to parse moderation state `{}`", path.display())),
+      Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+      Err(err) => Err(err.into()),
+    }
+  }
+
+  fn save(&self, path: &std::path::Path) -> Result {
+    if let Some(parent) = path.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    fs::write(path, serde_yaml::to_string(self)?)
+      .with_context(|| format!("failed to write moderation state `{}`", path.display()))
+  }
+
+  fn apply(&self, mut config: Config) -> Config {
+    for entry in &self.hidden {
+      entry.set(&mut config, true);
+    }
+
+    for entry in &self.unhidden {
+      entry.set(&mut config, false);
+    }
+
+    config
+  }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(super) struct HiddenJson {
+  pub(super) content_hashes: Vec<sha256::Hash>,
+  pub(super) inscriptions: Vec<InscriptionId>,
+  pub(super) sats: Vec<Sat>,
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+  action: &'a str,
+  entry: String,
+  source: &'a str,
+  timestamp: u64,
+}
+
+/// The server's view of the config file, with admin changes applied, which
+/// can be reloaded and edited while the server is running.
+pub(super) struct Moderation {
+  admin: Mutex<AdminState>,
+  admin_path: PathBuf,
+  audit_log: PathBuf,
+  config: RwLock<Arc<Config>>,
+  file_config: Mutex<Config>,
+  modified: Mutex<Option<(SystemTime, u64)>>,
+  path: Option<PathBuf>,
+}
+
+impl Moderation {
+  pub(super) fn new(options: &Options) -> Result<Self> {
+    let path = options.config_path();
+    let file_config = options.load_config()?;
+
+    let audit_log = match &file_config.audit_log {
+      Some(audit_log) => audit_log.clone(),
+      None => options.data_dir()?.join("moderation.log"),
+    };
+
+    let admin_path = options.data_dir()?.join("moderation.yaml");
+    let admin = AdminState::load(&admin_path)?;
+
+    Ok(Self {
+      config: RwLock::new(Arc::new(admin.apply(file_config.clone()))),
+      admin: Mutex::new(admin),
+      admin_path,
+      audit_log,
+      file_config: Mutex::new(file_config),
+      modified: Mutex::new(path.as_deref().and_then(Self::modified)),
+      path,
+    })
+  }
+
+  pub(super) fn config(&self) -> Arc<Config> {
+    self.config.read().unwrap().clone()
+  }
+
+  pub(super) fn hidden(&self) -> HiddenJson {
+    let config = self.config();
+
+    let mut inscriptions = config
+      .hidden
+      .iter()
+      .copied()
+      .collect::<Vec<InscriptionId>>();
+    inscriptions.sort_by_key(|inscription_id| inscription_id.to_string());
+
+    HiddenJson {
+      content_hashes: config.hidden_content_hashes.iter().copied().collect(),
+      inscriptions,
+      sats: config.hidden_sats.iter().copied().collect(),
+    }
+  }
+
+  /// Reload the config file if it has changed since it was last read.
+  pub(super) fn reload_if_modified(&self) -> Result {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+
+    if Self::modified(path) != *self.modified.lock().unwrap() {
+      self.reload("file change")?;
+    }
+
+    Ok(())
+  }
+
+  pub(super) fn reload(&self, source: &str) -> Result {
+    let Some(path) = &self.path else {
+      return Ok(());
+    };
+
+    let mut modified = self.modified.lock().unwrap();
+
+    *modified = Self::modified(path);
+
+    let file_config: Config = serde_yaml::from_reader(File::open(path)?)
+      .with_context(|| format!("failed to parse config file `{}`", path.display()))?;
+
+    let mut current_file_config = self.file_config.lock().unwrap();
+
+    let config = self.admin.lock().unwrap().apply(file_config.clone());
+
+    *current_file_config = file_config;
+
+    let changes = Self::diff(&Self::entries(&self.config()), &Self::entries(&config));
+
+    *self.config.write().unwrap() = Arc::new(config);
+
+    log::info!("Reloaded config from `{}` on {source}", path.display());
+
+    for (hide, entry) in changes {
+      self.audit(hide, entry, source)?;
+    }
+
+    Ok(())
+  }
+
+  /// Hide or unhide an entry, saving the change to the admin state file.
+  /// Returns false if the entry was already in that state.
+  pub(super) fn update(&self, entry: HiddenEntry, hide: bool, source: &str) -> Result<bool> {
+    let file_config = self.file_config.lock().unwrap();
+    let mut admin = self.admin.lock().unwrap();
+
+    let mut config = (*self.config()).clone();
+
+    if !entry.set(&mut config, hide) {
+      return Ok(false);
+    }
+
+    admin.hidden.retain(|hidden| *hidden != entry);
+    admin.unhidden.retain(|unhidden| *unhidden != entry);
+
+    if entry.is_in(&file_config) != hide {
+      if hide {
+        admin.hidden.push(entry);
+      } else {
+        admin.unhidden.push(entry);
+      }
+    }
+
+    admin.save(&self.admin_path)?;
+
+    *self.config.write().unwrap() = Arc::new(config);
+
+    self.audit(hide, entry, source)?;
+
+    Ok(true)
+  }
+
+  fn audit(&self, hide: bool, entry: HiddenEntry, source: &str) -> Result {
+    let action = if hide { "hide" } else { "unhide" };
+
+    log::info!("Moderation: {action} {entry} ({source})");
+
+    let mut line = serde_json::to_string(&AuditEntry {
+      action,
+      entry: entry.to_string(),
+      source,
+      timestamp: SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default(),
+    })?;
+    line.push('\n');
+
+    if let Some(parent) = self.audit_log.parent() {
+      fs::create_dir_all(parent)?;
+    }
+
+    io::Write::write_all(
+      &mut fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&self.audit_log)
+        .with_context(|| format!("failed to open audit log `{}`", self.audit_log.display()))?,
+      line.as_bytes(),
+    )?;
+
+    Ok(())
+  }
+
+  fn entries(config: &Config) -> Vec<HiddenEntry> {
+    let mut inscriptions = config
+      .hidden
+      .iter()
+      .copied()
+      .collect::<Vec<InscriptionId>>();
+    inscriptions.sort_by_key(|inscription_id| inscription_id.to_string());
+
+    inscriptions
+      .into_iter()
+      .map(HiddenEntry::Inscription)
+      .chain(
+        config
+          .hidden_content_hashes
+          .iter()
+          .copied()
+          .map(HiddenEntry::ContentHash),
+      )
+      .chain(config.hidden_sats.iter().copied().map(HiddenEntry::Sat))
+      .collect()
+  }
+
+  fn diff(old: &[HiddenEntry], new: &[HiddenEntry]) -> Vec<(bool, HiddenEntry)> {
+    new
+      .iter()
+      .filter(|entry| !old.contains(entry))
+      .map(|entry| (true, *entry))
+      .chain(
+        old
+          .iter()
+          .filter(|entry| !new.contains(entry))
+          .map(|entry| (false, *entry)),
+      )
+      .collect()
+  }
+
+  fn modified(path: &std::path::Path) -> Option<(SystemTime, u64)> {
+    let metadata = fs::metadata(path).ok()?;
+    Some((metadata.modified().ok()?, metadata.len()))
+  }
+
+  /// Reload the config file when it changes or, on unix, on SIGHUP.
+  pub(super) fn spawn_reloader(self: &Arc<Self>) -> Result {
+    {
+      let moderation = self.clone();
+      tokio::spawn(async move {
+        loop {
+          sleep(Duration::from_secs(1)).await;
+          if let Err(err) = moderation.reload_if_modified() {
+            log::warn!("Reloading config: {err}");
+          }
+        }
+      });
+    }
+
+    #[cfg(unix)]
+    {
+      let moderation = self.clone();
+      let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())?;
+      tokio::spawn(async move {
+        while hangup.recv().await.is_some() {
+          if let Err(err) = moderation.reload("SIGHUP") {
+            log::warn!("Reloading config: {err}");
+          }
+        }
+      });
+    }
+
+    Ok(())
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn moderation(tempdir: &TempDir, config: &str) -> Moderation {
+    let path = tempdir.path().join("ord.yaml");
+    fs::write(&path, config).unwrap();
+
+    Moderation::new(
+      &Arguments::try_parse_from([
+        "ord",
+        "--config",
+        path.to_str().unwrap(),
+        "--data-dir",
+        tempdir.path().to_str().unwrap(),
+        "server",
+      ])
+      .unwrap()
+      .options,
+    )
+    .unwrap()
+  }
+
+  fn audit_log(tempdir: &TempDir) -> Vec<serde_json::Value> {
+    fs::read_to_string(tempdir.path().join("moderation.log"))
+      .unwrap_or_default()
+      .lines()
+      .map(|line| serde_json::from_str(line).unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn parse_hidden_entry() {
+    assert_eq!(
+      "1111111111111111111111111111111111111111111111111111111111111111i1"
+        .parse::<HiddenEntry>()
+        .unwrap(),
+      HiddenEntry::Inscription(inscription_id(1))
+    );
+    assert_eq!(
+      "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        .parse::<HiddenEntry>()
+        .unwrap(),
+      HiddenEntry::ContentHash(sha256::Hash::hash(b"hello"))
+    );
+    assert_eq!(
+      "5000000000".parse::<HiddenEntry>().unwrap(),
+      HiddenEntry::Sat(Sat(5000000000))
+    );
+    assert!("foo-bar".parse::<HiddenEntry>().is_err());
+  }
+
+  #[test]
+  fn updates_are_saved_apart_from_config_and_audited() {
+    let tempdir = TempDir::new().unwrap();
+    let config = "hidden:\nbitcoin_rpc_user: foo # comment\n";
+    let moderation = moderation(&tempdir, config);
+
+    assert!(moderation
+      .update(HiddenEntry::Sat(Sat(7)), true, "admin")
+      .unwrap());
+    assert!(!moderation
+      .update(HiddenEntry::Sat(Sat(7)), true, "admin")
+      .unwrap());
+    assert!(moderation
+      .update(HiddenEntry::Inscription(inscription_id(1)), true, "admin")
+      .unwrap());
+    assert!(moderation
+      .update(HiddenEntry::Inscription(inscription_id(1)), false, "admin")
+      .unwrap());
+
+    let config = moderation.config();
+    assert!(config.is_hidden_sat(Sat(7)));
+    assert!(!config.is_hidden(inscription_id(1)));
+
+    assert_eq!(
+      fs::read_to_string(tempdir.path().join("ord.yaml")).unwrap(),
+      "hidden:\nbitcoin_rpc_user: foo # comment\n"
+    );
+
+    assert_eq!(
+      AdminState::load(&tempdir.path().join("moderation.yaml")).unwrap(),
+      AdminState {
+        hidden: vec![HiddenEntry::Sat(Sat(7))],
+        unhidden: Vec::new(),
+      }
+    );
+
+    let log = audit_log(&tempdir);
+    assert_eq!(log.len(), 3);
+    assert_eq!(log[0]["action"], "hide");
+    assert_eq!(log[0]["entry"], "7");
+    assert_eq!(log[0]["source"], "admin");
+    assert_eq!(log[2]["action"], "unhide");
+    assert_eq!(log[2]["entry"], inscription_id(1).to_string());
+  }
+
+  #[test]
+  fn admin_state_is_merged_with_config() {
+    let tempdir = TempDir::new().unwrap();
+    let config = format!("hidden:\n- {}\n", inscription_id(1));
+    let moderation = moderation(&tempdir, &config);
+
+    assert!(moderation
+      .update(HiddenEntry::Inscription(inscription_id(1)), false, "admin")
+      .unwrap());
+    assert!(moderation
+      .update(HiddenEntry::Sat(Sat(7)), true, "admin")
+      .unwrap());
+
+    let moderation = self::moderation(&tempdir, &config);
+
+    let config = moderation.config();
+    assert!(!config.is_hidden(inscription_id(1)));
+    assert!(config.is_hidden_sat(Sat(7)));
+
+    fs::write(
+      tempdir.path().join("ord.yaml"),
+      format!(
+        "hidden:\n- {}\n- {}\n",
+        inscription_id(1),
+        inscription_id(2)
+      ),
+    )
+    .unwrap();
+
+    moderation.reload("SIGHUP").unwrap();
+
+    let config = moderation.config();
+    assert!(!config.is_hidden(inscription_id(1)));
+    assert!(config.is_hidden(inscription_id(2)));
+    assert!(config.is_hidden_sat(Sat(7)));
+  }
+
+  #[test]
+  fn reload_audits_changes() {
+    let tempdir = TempDir::new().unwrap();
+    let moderation = moderation(&tempdir, &format!("hidden:\n- {}\n", inscription_id(1)));
+
+    assert!(moderation.config().is_hidden(inscription_id(1)));
+
+    moderation.reload_if_modified().unwrap();
+    assert!(audit_log(&tempdir).is_empty());
+
+    fs::write(
+      tempdir.path().join("ord.yaml"),
+      format!("hidden:\n- {}\nhidden_sats:\n- 3\n", inscription_id(2)),
+    )
+    .unwrap();
+
+    moderation.reload_if_modified().unwrap();
+
+    let config = moderation.config();
+    assert!(!config.is_hidden(inscription_id(1)));
+    assert!(config.is_hidden(inscription_id(2)));
+    assert!(config.is_hidden_sat(Sat(3)));
+
+    let log = audit_log(&tempdir)
+      .into_iter()
+      .map(|entry| {
+        (
+          entry["action"].as_str().unwrap().to_string(),
+          entry["entry"].as_str().unwrap().to_string(),
+        )
+      })
+      .collect::<Vec<(String, String)>>();
+
+    assert_eq!(
+      log,
+      [
+        ("hide".into(), inscription_id(2).to_string()),
+        ("hide".into(), "3".into()),
+        ("unhide".into(), inscription_id(1).to_string()),
+      ]
+    );
+  }
+
+  #[test]
+  fn invalid_config_is_not_loaded() {
+    let tempdir = TempDir::new().unwrap();
+    let moderation = moderation(&tempdir, &format!("hidden:\n- {}\n", inscription_id(1)));
+
+    fs::write(tempdir.path().join("ord.yaml"), "hidden: foo").unwrap();
+
+    assert!(moderation.reload("SIGHUP").is_err());
+    assert!(moderation.config().is_hidden(inscription_id(1)));
+  }
+}
@@ -0,0 +1,827 @@
+use {super::*, serde_json::json};
+
+/// OpenAPI description of the JSON API. Component schemas mirror the
+/// serialized JSON types and are checked against them in tests.
+pub(super) fn spec() -> serde_json::Value {
+  json!({
+    "openapi": "3.0.3",
+    "info": {
+      "title": "ord",
+      "description": "JSON API served by `ord server --enable-json-api` to requests with an `Accept: application/json` header. Responses are wrapped in an envelope with the data, the path of the next page, if any, and the index height.",
+      "version": env!("CARGO_PKG_VERSION"),
+    },
+    "paths": paths(),
+    "components": {
+      "schemas": schemas(),
+      "responses": {
+        "Error": {
+          "description": "Error",
+          "content": {
+            "application/json": {
+              "schema": reference("ErrorJson"),
+            },
+          },
+        },
+      },
+    },
+  })
+}
+
+fn paths() -> serde_json::Value {
+  let inscriptions = enveloped(reference("InscriptionsJson"));
+
+  json!({
+    "/address/{address}": get(
+      "Outputs, inscriptions and rare sats of an address",
+      &[path("address", string())],
+      enveloped(reference("AddressJson")),
+    ),
+    "/content-hash/{hash}": get(
+      "Inscriptions with the given content SHA-256 hash",
+      &[path("hash", string())],
+      enveloped(reference("ContentHashJson")),
+    ),
+    "/inscription/{inscription_id}": get(
+      "Inscription",
+      &[path("inscription_id", string())],
+      enveloped(reference("InscriptionJson")),
+    ),
+    "/inscriptions": get("Latest inscriptions", &[], inscriptions.clone()),
+    "/inscriptions/{from}": get(
+      "Inscriptions numbered from `from` downwards",
+      &[path("from", integer())],
+      inscriptions.clone(),
+    ),
+    "/inscriptions/{from}/{n}": get(
+      "`n` inscriptions numbered from `from` downwards",
+      &[path("from", integer()), path("n", integer())],
+      inscriptions.clone(),
+    ),
+    "/inscriptions/block/{height}": get(
+      "Inscriptions in a block",
+      &[path("height", integer())],
+      inscriptions,
+    ),
+    "/inscriptions/query": get(
+      "Inscriptions matching a query",
+      &[
+        query("address", string()),
+        query("content_type", string()),
+        query("cursed", json!({ "type": "boolean" })),
+        query("from", integer()),
+        query("max_height", integer()),
+        query("min_height", integer()),
+        query("rarity", reference("Rarity")),
+        query("sort", json!({ "type": "string", "enum": ["newest", "oldest"] })),
+      ],
+      enveloped(reference("InscriptionQueryJson")),
+    ),
+    "/inscriptions_json/{start}": get(
+      "Inscription number `start`",
+      &[path("start", integer())],
+      enveloped(array(reference("MyInscriptionJson"))),
+    ),
+    "/inscriptions_json/{start}/{end}": get(
+      "Inscriptions numbered from `start` up to `end`, a page at a time",
+      &[path("start", integer()), path("end", integer())],
+      enveloped(array(reference("MyInscriptionJson"))),
+    ),
+    "/issuance": get(
+      "Sat issuance by epoch and, optionally, every `interval` mined blocks",
+      &[query("interval", integer())],
+      enveloped(reference("Issuance")),
+    ),
+    "/lost": get(
+      "Lost sat ranges, and lost and unbound inscriptions",
+      &[],
+      enveloped(reference("LostSats")),
+    ),
+    "/output/{output}": get(
+      "Output",
+      &[path("output", string())],
+      enveloped(reference("OutputJson")),
+    ),
+    "/sat/{sat}": get(
+      "Sat",
+      &[path("sat", string())],
+      enveloped(reference("SatJson")),
+    ),
+    "/sat/{sat}/history": get(
+      "Outputs a sat has been in, oldest first",
+      &[path("sat", string())],
+      enveloped(reference("SatHistoryJson")),
+    ),
+    "/sats": get(
+      "Unspent sats with a trait, 100 ranges per page",
+      &[query("trait", reference("SatTrait")), query("from", integer())],
+      enveloped(reference("SatsJson")),
+    ),
+    "/stats": get("Index statistics", &[], enveloped(reference("StatsJson"))),
+    "/transfers/{height}": get(
+      "Inscriptions transferred in a block",
+      &[path("height", integer())],
+      enveloped(array(reference("TransferJson"))),
+    ),
+  })
+}
+
+fn schemas() -> serde_json::Value {
+  json!({
+    "AddressJson": object(&[
+      ("address", string()),
+      ("outputs", array(string())),
+      ("inscriptions", array(string())),
+      ("cardinal_balance", integer()),
+      ("rare_sats", nullable(array(array(json!({}))))),
+    ]),
+    "ContentHashJson": object(&[
+      ("content_hash", string()),
+      ("inscriptions", array(string())),
+    ]),
+    "ErrorJson": object(&[
+      ("status", integer()),
+      ("message", string()),
+    ]),
+    "InscriptionJson": object(&[
+      ("inscription_id", string()),
+      ("number", integer()),
+      ("genesis_height", integer()),
+      ("genesis_fee", integer()),
+      ("output_value", nullable(integer())),
+      ("address", nullable(string())),
+      ("sat", nullable(integer())),
+      ("satpoint", string()),
+      ("content_type", nullable(string())),
+      ("content_length", nullable(integer())),
+      ("timestamp", integer()),
+      ("previous", nullable(string())),
+      ("next", nullable(string())),
+      ("content_hash", nullable(string())),
+      ("first_inscribed", nullable(string())),
+      ("duplicates", integer()),
+    ]),
+    "InscriptionQueryJson": object(&[
+      ("inscriptions", array(string())),
+    ]),
+    "InscriptionsJson": object(&[
+      ("inscriptions", array(string())),
+      ("prev", nullable(integer())),
+      ("next", nullable(integer())),
+      ("lowest", nullable(integer())),
+      ("highest", nullable(integer())),
+    ]),
+    "Issuance": issuance(),
+    "LostCause": {
+      "type": "string",
+      "enum": ["unclaimed", "duplicate-txid"],
+    },
+    "LostInscriptionCause": {
+      "type": "string",
+      "enum": ["unclaimed", "duplicate-txid", "unbound"],
+    },
+    "LostSats": object(&[
+      ("lost_sats", integer()),
+      ("ranges", array(object(&[
+        ("start", integer()),
+        ("end", integer()),
+        ("size", integer()),
+        ("offset", integer()),
+        ("height", integer()),
+        ("cause", reference("LostCause")),
+      ]))),
+      ("inscriptions", array(object(&[
+        ("inscription_id", string()),
+        ("number", integer()),
+        ("satpoint", string()),
+        ("height", integer()),
+        ("cause", reference("LostInscriptionCause")),
+      ]))),
+    ]),
+    "MyInscriptionJson": object(&[
+      ("number", integer()),
+      ("id", string()),
+      ("address", nullable(string())),
+      ("output_value", nullable(integer())),
+      ("sat", nullable(reference("SatoshiJson"))),
+      ("content_length", nullable(integer())),
+      ("content_type", string()),
+      ("timestamp", integer()),
+      ("genesis_height", integer()),
+      ("genesis_fee", integer()),
+      ("genesis_transaction", string()),
+      ("location", string()),
+      ("output", string()),
+      ("offset", integer()),
+    ]),
+    "OutputJson": object(&[
+      ("value", integer()),
+      ("script_pubkey", string()),
+      ("address", nullable(string())),
+      ("transaction", string()),
+      ("sat_ranges", nullable(array(array(integer())))),
+      ("inscriptions", array(string())),
+    ]),
+    "Rarity": {
+      "type": "string",
+      "enum": ["common", "uncommon", "rare", "epic", "legendary", "mythic"],
+    },
+    "SatJson": object(&[
+      ("number", integer()),
+      ("decimal", string()),
+      ("degree", string()),
+      ("name", string()),
+      ("block", integer()),
+      ("cycle", integer()),
+      ("epoch", integer()),
+      ("period", integer()),
+      ("offset", integer()),
+      ("rarity", reference("Rarity")),
+      ("traits", array(reference("SatTrait"))),
+      ("percentile", string()),
+      ("satpoint", nullable(string())),
+      ("timestamp", integer()),
+      ("inscriptions", array(string())),
+    ]),
+    "SatHistoryJson": object(&[
+      ("sat", integer()),
+      ("history", array(object(&[
+        ("height", integer()),
+        ("satpoint", string()),
+      ]))),
+    ]),
+    "SatsJson": object(&[
+      ("sat_trait", reference("SatTrait")),
+      ("sats", array(array(json!({})))),
+      ("next", nullable(integer())),
+    ]),
+    "SatTrait": {
+      "type": "string",
+      "enum": SatTrait::ALL.map(|sat_trait| sat_trait.to_string()),
+    },
+    "SatoshiJson": object(&[
+      ("number", integer()),
+      ("decimal", string()),
+      ("degree", string()),
+      ("percentile", string()),
+      ("name", string()),
+      ("cycle", integer()),
+      ("epoch", integer()),
+      ("period", integer()),
+      ("block", integer()),
+      ("offset", integer()),
+      ("rarity", reference("Rarity")),
+    ]),
+    "StatsJson": object(&[
+      ("highest_block_indexed", nullable(integer())),
+      ("lowest_inscription_number", nullable(integer())),
+      ("highest_inscription_number", nullable(integer())),
+    ]),
+    "TransferJson": object(&[
+      ("inscription_id", string()),
+      ("address", string()),
+    ]),
+  })
+}
+
+fn issuance() -> serde_json::Value {
+  let rarities = object(&[
+    ("common", integer()),
+    ("uncommon", integer()),
+    ("rare", integer()),
+    ("epic", integer()),
+    ("legendary", integer()),
+    ("mythic", integer()),
+  ]);
+  let blocks = [
+    ("start", integer()),
+    ("end", integer()),
+    ("first_sat", integer()),
+    ("sats", integer()),
+    ("rarities", rarities),
+  ];
+  object(&[
+    ("height", nullable(integer())),
+    ("supply", integer()),
+    ("issued", integer()),
+    ("remaining", integer()),
+    (
+      "next_halving",
+      nullable(object(&[
+        ("height", integer()),
+        ("blocks", integer()),
+        ("timestamp", integer()),
+      ])),
+    ),
+    (
+      "epochs",
+      array(object(
+        &[("epoch", integer()), ("subsidy", integer())]
+          .into_iter()
+          .chain(blocks.clone())
+          .collect::<Vec<(&str, serde_json::Value)>>(),
+      )),
+    ),
+    ("ranges", array(object(&blocks))),
+  ])
+}
+
+fn get(
+  summary: &str,
+  parameters: &[serde_json::Value],
+  schema: serde_json::Value,
+) -> serde_json::Value {
+  json!({
+    "get": {
+      "summary": summary,
+      "parameters": parameters,
+      "responses": {
+        "200": {
+          "description": "OK",
+          "content": {
+            "application/json": {
+              "schema": schema,
+            },
+          },
+        },
+        "default": {
+          "$ref": "#/components/responses/Error",
+        },
+      },
+    },
+  })
+}
+
+fn path(name: &str, schema: serde_json::Value) -> serde_json::Value {
+  json!({ "name": name, "in": "path", "required": true, "schema": schema })
+}
+
+fn query(name: &str, schema: serde_json::Value) -> serde_json::Value {
+  json!({ "name": name, "in": "query", "required": false, "schema": schema })
+}
+
+fn enveloped(data: serde_json::Value) -> serde_json::Value {
+  object(&[
+    ("data", data),
+    ("next", nullable(string())),
+    ("height", nullable(integer())),
+  ])
+}
+
+fn object(properties: &[(&str, serde_json::Value)]) -> serde_json::Value {
+  json!({
+    "type": "object",
+    "required": properties.iter().map(|(name, _schema)| name).collect::<Vec<&&str>>(),
+    "properties": properties
+      .iter()
+      .map(|(name, schema)| (name.to_string(), schema.clone()))
+      .collect::<serde_json::Map<String, serde_json::Value>>(),
+  })
+}
+
+fn array(items: serde_json::Value) -> serde_json::Value {
+  json!({ "type": "array", "items": items })
+}
+
+fn integer() -> serde_json::Value {
+  json!({ "type": "integer" })
+}
+
+fn string() -> serde_json::Value {
+  json!({ "type": "string" })
+}
+
+fn reference(name: &str) -> serde_json::Value {
+  json!({ "$ref": format!("#/components/schemas/{name}") })
+}
+
+fn nullable(schema: serde_json::Value) -> serde_json::Value {
+  if schema.get("$ref").is_some() {
+    json!({ "allOf": [schema], "nullable": true })
+  } else {
+    let mut schema = schema;
+    schema["nullable"] = true.into();
+    schema
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {
+    super::*,
+    crate::templates::{sat_history::SatHistoryEntryJson, *},
+    std::collections::BTreeSet,
+  };
+
+  #[track_caller]
+  fn check(schema: &serde_json::Value, value: &serde_json::Value, path: &str) {
+    if value.is_null() {
+      assert_eq!(schema["nullable"], true, "{path} is null but not nullable");
+      return;
+    }
+
+    if let Some(all_of) = schema.get("allOf") {
+      return check(&all_of[0], value, path);
+    }
+
+    if let Some(reference) = schema.get("$ref") {
+      let name = reference
+        .as_str()
+        .unwrap()
+        .strip_prefix("#/components/schemas/")
+        .unwrap();
+      return check(&schemas()[name], value, path);
+    }
+
+    match schema["type"].as_str() {
+      Some("object") => {
+        let properties = schema["properties"].as_object().unwrap();
+        let object = value.as_object().unwrap();
+        assert_eq!(
+          properties.keys().collect::<BTreeSet<&String>>(),
+          object.keys().collect::<BTreeSet<&String>>(),
+          "{path} fields differ from schema",
+        );
+        for (name, value) in object {
+          check(&properties[name], value, &format!("{path}.{name}"));
+        }
+      }
+      Some("array") => {
+        for (i, item) in value.as_array().unwrap().iter().enumerate() {
+          check(&schema["items"], item, &format!("{path}[{i}]"));
+        }
+      }
+      Some("integer") => assert!(value.is_i64() || value.is_u64(), "{path} is not an integer"),
+      Some("boolean") => assert!(value.is_boolean(), "{path} is not a boolean"),
+      Some("string") => {
+        assert!(value.is_string(), "{path} is not a string");
+        if let Some(variants) = schema.get("enum") {
+          assert!(
+            variants.as_array().unwrap().contains(value),
+            "{path} is not a variant"
+          );
+        }
+      }
+      Some(ty) => panic!("{path} has unknown type {ty}"),
+      None => {}
+    }
+  }
+
+  #[track_caller]
+  fn case(checked: &mut BTreeSet<String>, name: &str, value: impl Serialize) {
+    check(
+      &reference(name),
+      &serde_json::to_value(value).unwrap(),
+      name,
+    );
+    checked.insert(name.into());
+  }
+
+  fn satoshi() -> SatoshiJson {
+    let sat = Sat(50 * COIN_VALUE);
+    SatoshiJson {
+      number: sat.n(),
+      decimal: sat.decimal().to_string(),
+      degree: sat.degree().to_string(),
+      percentile: sat.percentile(),
+      name: sat.name(),
+      cycle: sat.cycle(),
+      epoch: sat.epoch().0,
+      period: sat.period(),
+      block: sat.height().0,
+      offset: sat.third(),
+      rarity: sat.rarity(),
+    }
+  }
+
+  #[test]
+  fn schemas_match_serialized_types() {
+    let mut checked = BTreeSet::new();
+
+    case(
+      &mut checked,
+      "AddressJson",
+      AddressJson {
+        address: recipient().to_string(),
+        outputs: vec![outpoint(1)],
+        inscriptions: vec![inscription_id(1)],
+        cardinal_balance: 1,
+        rare_sats: Some(vec![(Sat(0), satpoint(1, 0))]),
+      },
+    );
+
+    case(
+      &mut checked,
+      "ContentHashJson",
+      ContentHashJson {
+        content_hash: "00".into(),
+        inscriptions: vec![inscription_id(1)],
+      },
+    );
+
+    case(
+      &mut checked,
+      "ErrorJson",
+      ErrorJson {
+        status: 404,
+        message: "not found".into(),
+      },
+    );
+
+    case(
+      &mut checked,
+      "InscriptionJson",
+      InscriptionJson {
+        inscription_id: inscription_id(1),
+        number: 1,
+        genesis_height: 1,
+        genesis_fee: 1,
+        output_value: Some(1),
+        address: Some(recipient().to_string()),
+        sat: Some(Sat(1)),
+        satpoint: satpoint(1, 0),
+        content_type: Some("text/plain".into()),
+        content_length: Some(1),
+        timestamp: 1,
+        previous: Some(inscription_id(0)),
+        next: Some(inscription_id(2)),
+        content_hash: Some("00".into()),
+        first_inscribed: Some(inscription_id(0)),
+        duplicates: 1,
+      },
+    );
+
+    case(
+      &mut checked,
+      "InscriptionQueryJson",
+      InscriptionQueryJson {
+        inscriptions: vec![inscription_id(1)],
+      },
+    );
+
+    case(
+      &mut checked,
+      "InscriptionsJson",
+      InscriptionsJson::new(vec![inscription_id(1)], Some(0), Some(2), Some(-1), Some(3)),
+    );
+
+    case(
+      &mut checked,
+      "MyInscriptionJson",
+      MyInscriptionJson {
+        number: 1,
+        id: inscription_id(1),
+        address: Some(recipient().to_string()),
+        output_value: Some(1),
+        sat: Some(satoshi()),
+        content_length: Some(1),
+        content_type: "text/plain".into(),
+        timestamp: 1,
+        genesis_height: 1,
+        genesis_fee: 1,
+        genesis_transaction: txid(1),
+        location: satpoint(1, 0).to_string(),
+        output: outpoint(1).to_string(),
+        offset: 0,
+      },
+    );
+
+    case(
+      &mut checked,
+      "OutputJson",
+      OutputJson {
+        value: 1,
+        script_pubkey: String::new(),
+        address: Some(recipient().to_string()),
+        transaction: txid(1).to_string(),
+        sat_ranges: Some(vec![(0, 1)]),
+        inscriptions: vec![inscription_id(1)],
+      },
+    );
+
+    let sat = Sat(50 * COIN_VALUE);
+    case(
+      &mut checked,
+      "SatJson",
+      SatJson {
+        number: sat.n(),
+        decimal: sat.decimal().to_string(),
+        degree: sat.degree().to_string(),
+        name: sat.name(),
+        block: sat.height().0,
+        cycle: sat.cycle(),
+        epoch: sat.epoch().0,
+        period: sat.period(),
+        offset: sat.third(),
+        rarity: sat.rarity(),
+        traits: vec![SatTrait::Alpha],
+        percentile: sat.percentile(),
+        satpoint: Some(satpoint(1, 0)),
+        timestamp: 1,
+        inscriptions: vec![inscription_id(1)],
+      },
+    );
+
+    {
+      let blocks = || issuance::BlockRangeIssuance {
+        start: 0,
+        end: 1,
+        first_sat: 0,
+        sats: 50 * COIN_VALUE,
+        rarities: issuance::RarityCounts::default(),
+      };
+
+      case(
+        &mut checked,
+        "Issuance",
+        issuance::Output {
+          height: Some(0),
+          supply: Sat::SUPPLY,
+          issued: 50 * COIN_VALUE,
+          remaining: Sat::SUPPLY - 50 * COIN_VALUE,
+          next_halving: Some(issuance::NextHalving {
+            height: SUBSIDY_HALVING_INTERVAL,
+            blocks: SUBSIDY_HALVING_INTERVAL - 1,
+            timestamp: 1,
+          }),
+          epochs: vec![issuance::EpochIssuance {
+            epoch: 0,
+            subsidy: 50 * COIN_VALUE,
+            blocks: blocks(),
+          }],
+          ranges: vec![blocks()],
+        },
+      );
+    }
+
+    case(
+      &mut checked,
+      "LostSats",
+      lost_sats::Output {
+        lost_sats: 1,
+        ranges: vec![lost_sats::LostSatRange {
+          start: 0,
+          end: 1,
+          size: 1,
+          offset: 0,
+          height: 1,
+          cause: LostCause::Unclaimed,
+        }],
+        inscriptions: vec![lost_sats::LostInscription {
+          inscription_id: inscription_id(1),
+          number: 1,
+          satpoint: satpoint(1, 0),
+          height: 1,
+          cause: lost_sats::LostInscriptionCause::Unbound,
+        }],
+      },
+    );
+
+    case(
+      &mut checked,
+      "SatHistoryJson",
+      SatHistoryJson {
+        sat: 1,
+        history: vec![SatHistoryEntryJson {
+          height: 0,
+          satpoint: satpoint(1, 0),
+        }],
+      },
+    );
+
+    case(
+      &mut checked,
+      "SatsJson",
+      SatsJson {
+        sat_trait: SatTrait::Vintage,
+        sats: vec![(Sat(0), 1, satpoint(1, 0))],
+        next: Some(Sat(5)),
+      },
+    );
+
+    case(&mut checked, "SatoshiJson", satoshi());
+
+    case(
+      &mut checked,
+      "StatsJson",
+      StatsJson {
+        highest_block_indexed: Some(1),
+        lowest_inscription_number: Some(-1),
+        highest_inscription_number: Some(1),
+      },
+    );
+
+    case(
+      &mut checked,
+      "TransferJson",
+      TransferJson {
+        inscription_id: inscription_id(1),
+        address: "unbound".into(),
+      },
+    );
+
+    assert_eq!(
+      checked,
+      schemas()
+        .as_object()
+        .unwrap()
+        .keys()
+        .filter(
+          |name| !["LostCause", "LostInscriptionCause", "Rarity", "SatTrait"]
+            .contains(&name.as_str())
+        )
+        .cloned()
+        .collect::<BTreeSet<String>>(),
+    );
+  }
+
+  #[test]
+  fn optional_fields_are_nullable() {
+    case(
+      &mut BTreeSet::new(),
+      "InscriptionJson",
+      InscriptionJson {
+        inscription_id: inscription_id(1),
+        number: 1,
+        genesis_height: 1,
+        genesis_fee: 1,
+        output_value: None,
+        address: None,
+        sat: None,
+        satpoint: satpoint(1, 0),
+        content_type: None,
+        content_length: None,
+        timestamp: 1,
+        previous: None,
+        next: None,
+        content_hash: None,
+        first_inscribed: None,
+        duplicates: 0,
+      },
+    );
+
+    case(
+      &mut BTreeSet::new(),
+      "StatsJson",
+      StatsJson {
+        highest_block_indexed: None,
+        lowest_inscription_number: None,
+        highest_inscription_number: None,
+      },
+    );
+  }
+
+  /// Checks the spec against the routes registered in `Server::run`, with
+  /// path parameter names ignored, since the spec names some of them after
+  /// what they hold.
+  #[test]
+  fn json_routes_are_documented() {
+    let source = include_str!("../server.rs");
+
+    let router = &source[source.find("Router::new()").unwrap()..];
+    let router = &router[..router.find(".layer(").unwrap()];
+
+    let json_handlers = Regex::new(r"(?s)async fn (\w+)(?:<[^>]*>)?\((.*?)\)\s*->")
+      .unwrap()
+      .captures_iter(source)
+      .filter(|captures| captures[2].contains("AcceptJson"))
+      .map(|captures| captures[1].to_string())
+      .collect::<BTreeSet<String>>();
+
+    let routes = Regex::new(r#"\.route\(\s*"([^"]+)",\s*get\(Self::(\w+)\)"#)
+      .unwrap()
+      .captures_iter(router)
+      .map(|captures| (captures[1].to_string(), captures[2].to_string()))
+      .collect::<Vec<(String, String)>>();
+
+    assert!(!json_handlers.is_empty());
+    assert!(!routes.is_empty());
+
+    let route_parameter = Regex::new(r":\w+").unwrap();
+    let spec_parameter = Regex::new(r"\{\w+\}").unwrap();
+
+    let documented = spec()["paths"]
+      .as_object()
+      .unwrap()
+      .keys()
+      .map(|path| spec_parameter.replace_all(path, "{}").into_owned())
+      .collect::<BTreeSet<String>>();
+
+    let registered = routes
+      .iter()
+      .map(|(path, _handler)| route_parameter.replace_all(path, "{}").into_owned())
+      .collect::<BTreeSet<String>>();
+
+    for (path, handler) in &routes {
+      if json_handlers.contains(handler) {
+        assert!(
+          documented.contains(route_parameter.replace_all(path, "{}").as_ref()),
+          "JSON route {path} is not in the spec",
+        );
+      }
+    }
+
+    for path in &documented {
+      assert!(registered.contains(path), "spec path {path} is not a route");
+    }
+  }
+}
@@ -0,0 +1,389 @@
+use {
+  super::*,
+  crate::config::{ApiKey, RateLimit, RateLimits},
+  std::{collections::HashMap, net::IpAddr},
+  tokio::sync::{OwnedSemaphorePermit, Semaphore},
+};
+
+const MAX_BUCKETS: usize = 100_000;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub(super) enum RouteClass {
+  Content,
+  Html,
+  Json,
+}
+
+impl RouteClass {
+  /// Classify a request, returning `None` for routes that are never limited.
+  pub(super) fn of(path: &str, accept_json: bool) -> Option<Self> {
+    if path == "/status" || path == "/metrics" {
+      None
+    } else if path.starts_with("/content/")
+      || path.starts_with("/preview/")
+      || path.starts_with("/thumbnail/")
+    {
+      Some(Self::Content)
+    } else if accept_json || path.starts_with("/inscriptions_json/") || path == "/openapi.json" {
+      Some(Self::Json)
+    } else {
+      Some(Self::Html)
+    }
+  }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum Client {
+  ApiKey(String),
+  Ip(IpAddr),
+}
+
+struct Bucket {
+  capacity: f64,
+  tokens: f64,
+  updated: Instant,
+}
+
+impl Bucket {
+  /// Tokens in the bucket at `now`, refilled at its own rate.
+  fn tokens_at(&self, now: Instant) -> f64 {
+    (self.tokens + now.duration_since(self.updated).as_secs_f64() * self.capacity / 60.0)
+      .min(self.capacity)
+  }
+}
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Rejection {
+  TooManyRequests { retry_after: u64 },
+  UnknownApiKey,
+}
+
+pub(super) struct RateLimiter {
+  api_keys: BTreeMap<String, ApiKey>,
+  buckets: Mutex<HashMap<(RouteClass, Client), Bucket>>,
+  content: Option<Arc<Semaphore>>,
+  html: Option<Arc<Semaphore>>,
+  json: Option<Arc<Semaphore>>,
+  limits: RateLimits,
+  max_buckets: usize,
+}
+
+impl RateLimiter {
+  pub(super) fn new(limits: RateLimits, api_keys: BTreeMap<String, ApiKey>) -> Self {
+    let semaphore = |limit: Option<RateLimit>| {
+      limit
+        .and_then(|limit| limit.concurrency)
+        .map(|concurrency| Arc::new(Semaphore::new(concurrency)))
+    };
+
+    Self {
+      content: semaphore(limits.content),
+      html: semaphore(limits.html),
+      json: semaphore(limits.json),
+      api_keys,
+      buckets: Mutex::new(HashMap::new()),
+      limits,
+      max_buckets: MAX_BUCKETS,
+    }
+  }
+
+  /// Admit a request, returning a permit to hold while it is served if the
+  /// route class has a concurrency cap.
+  pub(super) fn admit(
+    &self,
+    class: RouteClass,
+    ip: Option<IpAddr>,
+    api_key: Option<&str>,
+    now: Instant,
+  ) -> Result<Option<OwnedSemaphorePermit>, Rejection> {
+    let limit = match class {
+      RouteClass::Content => self.limits.content,
+      RouteClass::Html => self.limits.html,
+      RouteClass::Json => self.limits.json,
+    };
+
+    let (client, requests_per_minute) = match api_key {
+      Some(key) => {
+        let api_key = self.api_keys.get(key).ok_or(Rejection::UnknownApiKey)?;
+        (
+          Some(Client::ApiKey(key.into())),
+          Some(api_key.requests_per_minute),
+        )
+      }
+      None => (
+        ip.map(Client::Ip),
+        limit.and_then(|limit| limit.requests_per_minute),
+      ),
+    };
+
+    if let (Some(client), Some(requests_per_minute)) = (client, requests_per_minute) {
+      self.take(class, client, requests_per_minute, now)?;
+    }
+
+    let semaphore = match class {
+      RouteClass::Content => &self.content,
+      RouteClass::Html => &self.html,
+      RouteClass::Json => &self.json,
+    };
+
+    match semaphore {
+      Some(semaphore) => semaphore
+        .clone()
+        .try_acquire_owned()
+        .map(Some)
+        .map_err(|_| Rejection::TooManyRequests { retry_after: 1 }),
+      None => Ok(None),
+    }
+  }
+
+  fn take(
+    &self,
+    class: RouteClass,
+    client: Client,
+    requests_per_minute: u32,
+    now: Instant,
+  ) -> Result<(), Rejection> {
+    let capacity = f64::from(requests_per_minute);
+    let rate = capacity / 60.0;
+
+    let mut buckets = self.buckets.lock().unwrap();
+
+    if buckets.len() >= self.max_buckets {
+      Self::evict(&mut buckets, self.max_buckets, now);
+    }
+
+    let bucket = buckets.entry((class, client)).or_insert(Bucket {
+      capacity,
+      tokens: capacity,
+      updated: now,
+    });
+
+    bucket.tokens = bucket.tokens_at(now).min(capacity);
+    bucket.capacity = capacity;
+    bucket.updated = now;
+
+    if bucket.tokens >= 1.0 {
+      bucket.tokens -= 1.0;
+      Ok(())
+    } else if rate > 0.0 {
+      let wait = Duration::from_secs_f64((1.0 - bucket.tokens) / rate);
+      Err(Rejection::TooManyRequests {
+        retry_after: (wait.as_secs() + u64::from(wait.subsec_millis() > 0)).max(1),
+      })
+    } else {
+      Err(Rejection::TooManyRequests { retry_after: 60 })
+    }
+  }
+
+  /// Drops buckets that have refilled, since a new bucket starts full, and
+  /// then the least recently updated buckets until the map is below
+  /// `max_buckets`, leaving room so that a full map isn't scanned on every
+  /// request.
+  fn evict(buckets: &mut HashMap<(RouteClass, Client), Bucket>, max_buckets: usize, now: Instant) {
+    buckets.retain(|_, bucket| bucket.tokens_at(now) < bucket.capacity);
+
+    let target = (max_buckets - max_buckets / 10).min(max_buckets - 1);
+
+    if buckets.len() > target {
+      let mut updated = buckets
+        .values()
+        .map(|bucket| bucket.updated)
+        .collect::<Vec<Instant>>();
+
+      let excess = buckets.len() - target;
+      let cutoff = *updated.select_nth_unstable(excess - 1).1;
+
+      buckets.retain(|_, bucket| bucket.updated > cutoff);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, std::collections::BTreeSet};
+
+  fn limiter(requests_per_minute: u32, concurrency: Option<usize>) -> RateLimiter {
+    RateLimiter::new(
+      RateLimits {
+        json: Some(RateLimit {
+          concurrency,
+          requests_per_minute: Some(requests_per_minute),
+        }),
+        ..Default::default()
+      },
+      [(
+        "key".to_string(),
+        ApiKey {
+          requests_per_minute: 5,
+        },
+      )]
+      .into_iter()
+      .collect(),
+    )
+  }
+
+  fn ip(n: u8) -> Option<IpAddr> {
+    Some(IpAddr::from([10, 0, 0, n]))
+  }
+
+  #[test]
+  fn classify() {
+    assert_eq!(RouteClass::of("/status", true), None);
+    assert_eq!(RouteClass::of("/metrics", false), None);
+    assert_eq!(
+      RouteClass::of("/content/foo", true),
+      Some(RouteClass::Content)
+    );
+    assert_eq!(
+      RouteClass::of("/preview/foo", false),
+      Some(RouteClass::Content)
+    );
+    assert_eq!(
+      RouteClass::of("/thumbnail/foo", true),
+      Some(RouteClass::Content)
+    );
+    assert_eq!(RouteClass::of("/sat/0", true), Some(RouteClass::Json));
+    assert_eq!(
+      RouteClass::of("/inscriptions_json/0", false),
+      Some(RouteClass::Json)
+    );
+    assert_eq!(RouteClass::of("/sat/0", false), Some(RouteClass::Html));
+  }
+
+  #[test]
+  fn requests_are_limited_per_ip() {
+    let limiter = limiter(2, None);
+    let now = Instant::now();
+
+    assert!(limiter.admit(RouteClass::Json, ip(1), None, now).is_ok());
+    assert!(limiter.admit(RouteClass::Json, ip(1), None, now).is_ok());
+    assert_eq!(
+      limiter
+        .admit(RouteClass::Json, ip(1), None, now)
+        .unwrap_err(),
+      Rejection::TooManyRequests { retry_after: 30 }
+    );
+
+    assert!(limiter.admit(RouteClass::Json, ip(2), None, now).is_ok());
+    assert!(limiter.admit(RouteClass::Html, ip(1), None, now).is_ok());
+
+    assert!(limiter
+      .admit(RouteClass::Json, ip(1), None, now + Duration::from_secs(30))
+      .is_ok());
+  }
+
+  #[test]
+  fn api_keys_have_their_own_quota() {
+    let limiter = limiter(1, None);
+    let now = Instant::now();
+
+    assert!(limiter.admit(RouteClass::Json, ip(1), None, now).is_ok());
+    assert!(limiter.admit(RouteClass::Json, ip(1), None, now).is_err());
+
+    for _ in 0..5 {
+      assert!(limiter
+        .admit(RouteClass::Json, ip(1), Some("key"), now)
+        .is_ok());
+    }
+
+    assert_eq!(
+      limiter
+        .admit(RouteClass::Json, ip(1), Some("key"), now)
+        .unwrap_err(),
+      Rejection::TooManyRequests { retry_after: 12 }
+    );
+
+    assert_eq!(
+      limiter
+        .admit(RouteClass::Json, ip(1), Some("other"), now)
+        .unwrap_err(),
+      Rejection::UnknownApiKey
+    );
+  }
+
+  fn buckets(limiter: &RateLimiter) -> BTreeSet<String> {
+    limiter
+      .buckets
+      .lock()
+      .unwrap()
+      .keys()
+      .map(|(_class, client)| match client {
+        Client::ApiKey(key) => key.clone(),
+        Client::Ip(ip) => ip.to_string(),
+      })
+      .collect()
+  }
+
+  #[test]
+  fn buckets_are_evicted_when_refilled_at_their_own_rate() {
+    let mut limiter = limiter(1, None);
+    limiter.max_buckets = 2;
+    let now = Instant::now();
+
+    assert!(limiter
+      .admit(RouteClass::Json, ip(1), Some("key"), now)
+      .is_ok());
+    assert!(limiter.admit(RouteClass::Json, ip(1), None, now).is_ok());
+
+    // the key's bucket refills at 5 requests per minute, the IP's at 1
+    assert!(limiter
+      .admit(RouteClass::Json, ip(2), None, now + Duration::from_secs(15))
+      .is_ok());
+
+    assert_eq!(
+      buckets(&limiter),
+      ["10.0.0.1".to_string(), "10.0.0.2".to_string()]
+        .into_iter()
+        .collect()
+    );
+  }
+
+  #[test]
+  fn least_recently_updated_buckets_are_evicted() {
+    let mut limiter = limiter(1, None);
+    limiter.max_buckets = 3;
+    let now = Instant::now();
+
+    for n in 1..=4 {
+      assert!(limiter
+        .admit(
+          RouteClass::Json,
+          ip(n),
+          None,
+          now + Duration::from_secs(n.into())
+        )
+        .is_ok());
+    }
+
+    assert_eq!(
+      buckets(&limiter),
+      [
+        "10.0.0.2".to_string(),
+        "10.0.0.3".to_string(),
+        "10.0.0.4".to_string()
+      ]
+      .into_iter()
+      .collect()
+    );
+  }
+
+  #[test]
+  fn concurrency_is_capped() {
+    let limiter = limiter(100, Some(1));
+    let now = Instant::now();
+
+    let permit = limiter.admit(RouteClass::Json, ip(1), None, now).unwrap();
+    assert!(permit.is_some());
+
+    assert_eq!(
+      limiter
+        .admit(RouteClass::Json, ip(2), None, now)
+        .unwrap_err(),
+      Rejection::TooManyRequests { retry_after: 1 }
+    );
+
+    drop(permit);
+
+    assert!(limiter.admit(RouteClass::Json, ip(2), None, now).is_ok());
+  }
+}
@@ -0,0 +1,387 @@
+use {
+  super::*,
+  image::{
+    imageops::FilterType,
+    io::{Limits, Reader},
+    DynamicImage, ImageOutputFormat,
+  },
+  std::{collections::HashMap, io::Cursor},
+};
+
+const MAX_DIMENSION: u32 = 4096;
+const MAX_PASSTHROUGH: usize = 64 * 1024;
+const PASSTHROUGH_CONTENT_TYPES: &[&str] = &[
+  "image/apng",
+  "image/avif",
+  "image/gif",
+  "image/jpeg",
+  "image/png",
+  "image/webp",
+];
+pub(super) const SIZE: u32 = 256;
+
+#[derive(Debug, PartialEq)]
+pub(super) enum Thumbnail {
+  Image { body: Vec<u8>, content_type: String },
+  Placeholder(&'static str),
+}
+
+impl Thumbnail {
+  pub(super) fn placeholder(media: Media) -> Self {
+    Self::Placeholder(match media {
+      Media::Audio => "audio",
+      Media::Iframe => "document",
+      Media::Image => "image",
+      Media::Pdf => "pdf",
+      Media::Text => "text",
+      Media::Unknown => "unknown",
+      Media::Video => "video",
+    })
+  }
+
+  /// Render a thumbnail for `inscription`. PNG, JPEG, GIF, and WebP images
+  /// are decoded and downscaled to a PNG, other small raster images are
+  /// passed through, and everything else, including SVG, which may carry
+  /// scripts, gets a placeholder card.
+  pub(super) fn render(inscription: &Inscription) -> Self {
+    let media = inscription.media();
+
+    let (Media::Image, Some(body), Some(content_type)) =
+      (media, inscription.body(), inscription.content_type())
+    else {
+      return Self::placeholder(media);
+    };
+
+    if let Ok(image) = decode(body) {
+      if let Ok(body) = encode(&fit(image, SIZE)) {
+        return Self::Image {
+          body,
+          content_type: "image/png".into(),
+        };
+      }
+    }
+
+    let raster = content_type
+      .split(';')
+      .next()
+      .map(|essence| PASSTHROUGH_CONTENT_TYPES.contains(&essence.trim()))
+      .unwrap_or_default();
+
+    if raster && body.len() <= MAX_PASSTHROUGH {
+      Self::Image {
+        body: body.to_vec(),
+        content_type: content_type.into(),
+      }
+    } else {
+      Self::placeholder(media)
+    }
+  }
+}
+
+fn decode(body: &[u8]) -> Result<DynamicImage> {
+  let mut limits = Limits::default();
+  limits.max_image_width = Some(MAX_DIMENSION);
+  limits.max_image_height = Some(MAX_DIMENSION);
+  limits.max_alloc = Some(u64::from(MAX_DIMENSION).pow(2) * 4);
+
+  let mut reader = Reader::new(Cursor::new(body)).with_guessed_format()?;
+  reader.limits(limits);
+
+  Ok(reader.decode()?)
+}
+
+fn fit(image: DynamicImage, size: u32) -> DynamicImage {
+  if image.width() <= size && image.height() <= size {
+    image
+  } else {
+    image.resize(size, size, FilterType::Triangle)
+  }
+}
+
+fn encode(image: &DynamicImage) -> Result<Vec<u8>> {
+  let mut png = Cursor::new(Vec::new());
+  image.write_to(&mut png, ImageOutputFormat::Png)?;
+  Ok(png.into_inner())
+}
+
+#[derive(Default)]
+struct Entries {
+  bytes: u64,
+  lru: BTreeMap<u64, InscriptionId>,
+  sizes: HashMap<InscriptionId, (u64, u64)>,
+  tick: u64,
+}
+
+impl Entries {
+  fn touch(&mut self, inscription_id: InscriptionId, size: u64) {
+    self.tick += 1;
+    if let Some((old_size, old_tick)) = self.sizes.insert(inscription_id, (size, self.tick)) {
+      self.lru.remove(&old_tick);
+      self.bytes -= old_size;
+    }
+    self.lru.insert(self.tick, inscription_id);
+    self.bytes += size;
+  }
+
+  fn remove(&mut self, inscription_id: InscriptionId) {
+    if let Some((size, tick)) = self.sizes.remove(&inscription_id) {
+      self.lru.remove(&tick);
+      self.bytes -= size;
+    }
+  }
+}
+
+/// Generated thumbnails stored on disk, evicting the least recently used
+/// once they exceed `capacity` bytes.
+pub(super) struct ThumbnailCache {
+  capacity: u64,
+  dir: PathBuf,
+  entries: Mutex<Entries>,
+}
+
+impl ThumbnailCache {
+  pub(super) fn open(dir: PathBuf, capacity: u64) -> Result<Self> {
+    fs::create_dir_all(&dir)
+      .with_context(|| format!("failed to create thumbnail cache at {}", dir.display()))?;
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(&dir)? {
+      let entry = entry?;
+      let path = entry.path();
+      let Some(inscription_id) = path
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .and_then(|stem| stem.parse::<InscriptionId>().ok())
+      else {
+        continue;
+      };
+      let metadata = entry.metadata()?;
+      files.push((metadata.modified()?, inscription_id, metadata.len()));
+    }
+
+    files.sort_by_key(|(modified, _, _)| *modified);
+
+    let cache = Self {
+      capacity,
+      dir,
+      entries: Mutex::default(),
+    };
+
+    {
+      let mut entries = cache.entries.lock().unwrap();
+      for (_, inscription_id, size) in files {
+        entries.touch(inscription_id, size);
+      }
+      cache.evict(&mut entries);
+    }
+
+    Ok(cache)
+  }
+
+  fn path(&self, inscription_id: InscriptionId) -> PathBuf {
+    self.dir.join(format!("{inscription_id}.png"))
+  }
+
+  pub(super) fn get(&self, inscription_id: InscriptionId) -> Option<Vec<u8>> {
+    let mut entries = self.entries.lock().unwrap();
+    let (size, _) = *entries.sizes.get(&inscription_id)?;
+
+    match fs::read(self.path(inscription_id)) {
+      Ok(body) => {
+        entries.touch(inscription_id, size);
+        Some(body)
+      }
+      Err(_) => {
+        entries.remove(inscription_id);
+        None
+      }
+    }
+  }
+
+  pub(super) fn insert(&self, inscription_id: InscriptionId, body: &[u8]) -> Result {
+    let size = u64::try_from(body.len()).unwrap();
+
+    if size > self.capacity {
+      return Ok(());
+    }
+
+    let path = self.path(inscription_id);
+    let partial = path.with_extension("partial");
+    fs::write(&partial, body)?;
+    fs::rename(&partial, &path)?;
+
+    let mut entries = self.entries.lock().unwrap();
+    entries.touch(inscription_id, size);
+    self.evict(&mut entries);
+
+    Ok(())
+  }
+
+  fn evict(&self, entries: &mut Entries) {
+    while entries.bytes > self.capacity {
+      let Some((_, inscription_id)) = entries.lru.pop_first() else {
+        break;
+      };
+      if let Some((size, _)) = entries.sizes.remove(&inscription_id) {
+        entries.bytes -= size;
+      }
+      if let Err(err) = fs::remove_file(self.path(inscription_id)) {
+        log::warn!("failed to evict thumbnail {inscription_id}: {err}");
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+pub(super) fn png(width: u32, height: u32) -> Vec<u8> {
+  encode(&tests::image(width, height)).unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+  use {super::*, image::RgbaImage};
+
+  pub(super) fn image(width: u32, height: u32) -> DynamicImage {
+    RgbaImage::from_fn(width, height, |x, y| {
+      image::Rgba([
+        u8::try_from(x % 256).unwrap(),
+        u8::try_from(y % 256).unwrap(),
+        0xff,
+        0xff,
+      ])
+    })
+    .into()
+  }
+
+  fn encode_as(image: &DynamicImage, format: ImageOutputFormat) -> Vec<u8> {
+    let mut body = Cursor::new(Vec::new());
+    image.write_to(&mut body, format).unwrap();
+    body.into_inner()
+  }
+
+  #[test]
+  fn png_round_trips() {
+    let image = image(7, 3);
+    assert_eq!(decode(&encode(&image).unwrap()).unwrap(), image);
+  }
+
+  #[test]
+  fn decode_webp() {
+    let image = image(7, 3);
+    assert_eq!(
+      decode(&encode_as(&image, ImageOutputFormat::WebP)).unwrap(),
+      image
+    );
+  }
+
+  #[test]
+  fn decode_rejects_invalid_images() {
+    assert!(decode(b"GIF89a").is_err());
+
+    let mut png = png(2, 2);
+    png.truncate(png.len() - 20);
+    assert!(decode(&png).is_err());
+  }
+
+  #[test]
+  fn decode_enforces_dimension_limits() {
+    assert!(decode(&png(MAX_DIMENSION + 1, 1)).is_err());
+    assert!(decode(&png(1, MAX_DIMENSION + 1)).is_err());
+    assert!(decode(&png(MAX_DIMENSION, 1)).is_ok());
+  }
+
+  #[test]
+  fn fit_preserves_aspect_ratio() {
+    let thumbnail = fit(image(1000, 500), SIZE);
+    assert_eq!((thumbnail.width(), thumbnail.height()), (256, 128));
+
+    assert_eq!(fit(image(10, 20), SIZE), image(10, 20));
+  }
+
+  #[test]
+  fn render() {
+    for (content_type, format) in [
+      ("image/png", ImageOutputFormat::Png),
+      ("image/webp", ImageOutputFormat::WebP),
+      ("image/gif", ImageOutputFormat::Gif),
+    ] {
+      let Thumbnail::Image { body, content_type } = Thumbnail::render(&Inscription::new(
+        Some(content_type.into()),
+        Some(encode_as(&image(512, 512), format)),
+      )) else {
+        panic!("expected image thumbnail");
+      };
+      assert_eq!(content_type, "image/png");
+      let thumbnail = decode(&body).unwrap();
+      assert_eq!((thumbnail.width(), thumbnail.height()), (256, 256));
+    }
+
+    assert_eq!(
+      Thumbnail::render(&Inscription::new(
+        Some("image/avif".into()),
+        Some(vec![1, 2, 3])
+      )),
+      Thumbnail::Image {
+        body: vec![1, 2, 3],
+        content_type: "image/avif".into(),
+      }
+    );
+
+    assert_eq!(
+      Thumbnail::render(&Inscription::new(
+        Some("image/avif".into()),
+        Some(vec![0; MAX_PASSTHROUGH + 1])
+      )),
+      Thumbnail::Placeholder("image")
+    );
+
+    assert_eq!(
+      Thumbnail::render(&Inscription::new(
+        Some("image/svg+xml".into()),
+        Some(b"<svg><script>alert(1)</script></svg>".to_vec())
+      )),
+      Thumbnail::Placeholder("document")
+    );
+
+    assert_eq!(
+      Thumbnail::render(&Inscription::new(
+        Some("text/plain".into()),
+        Some(b"hello".to_vec())
+      )),
+      Thumbnail::Placeholder("text")
+    );
+
+    assert_eq!(
+      Thumbnail::render(&Inscription::new(
+        Some("audio/flac".into()),
+        Some(Vec::new())
+      )),
+      Thumbnail::Placeholder("audio")
+    );
+  }
+
+  #[test]
+  fn cache_evicts_least_recently_used() {
+    let tempdir = TempDir::new().unwrap();
+    let dir = tempdir.path().join("thumbnails");
+    let cache = ThumbnailCache::open(dir.clone(), 10).unwrap();
+
+    cache.insert(inscription_id(1), &[1; 4]).unwrap();
+    cache.insert(inscription_id(2), &[2; 4]).unwrap();
+    assert_eq!(cache.get(inscription_id(1)), Some(vec![1; 4]));
+
+    cache.insert(inscription_id(3), &[3; 4]).unwrap();
+    assert_eq!(cache.get(inscription_id(2)), None);
+    assert!(!dir.join(format!("{}.png", inscription_id(2))).exists());
+    assert_eq!(cache.get(inscription_id(1)), Some(vec![1; 4]));
+    assert_eq!(cache.get(inscription_id(3)), Some(vec![3; 4]));
+
+    cache.insert(inscription_id(4), &[4; 11]).unwrap();
+    assert_eq!(cache.get(inscription_id(4)), None);
+
+    drop(cache);
+
+    let cache = ThumbnailCache::open(dir, 4).unwrap();
+    assert_eq!(cache.entries.lock().unwrap().sizes.len(), 1);
+  }
+}
@@ -18,10 +18,13 @@ pub struct Output {
   pub period: u64,
   pub offset: u64,
   pub rarity: Rarity,
+  pub traits: Vec<SatTrait>,
 }
 
 impl Traits {
-  pub(crate) fn run(self) -> Result {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let config = options.load_config()?;
+
     print_json(Output {
       number: self.sat.n(),
       decimal: self.sat.decimal().to_string(),
@@ -33,6 +36,7 @@ impl Traits {
       period: self.sat.period(),
       offset: self.sat.third(),
       rarity: self.sat.rarity(),
+      traits: SatTrait::of(self.sat, &config.trait_ranges),
     })?;
 
     Ok(())
@@ -15,8 +15,11 @@ use {
 };
 
 pub mod balance;
+pub mod bump;
 pub mod cardinals;
+pub mod consolidate;
 pub mod create;
+pub mod extract;
 pub(crate) mod inscribe;
 pub mod inscriptions;
 pub mod outputs;
@@ -24,6 +27,8 @@ pub mod receive;
 mod restore;
 pub mod sats;
 pub mod send;
+pub mod send_batch;
+pub mod split;
 pub(crate) mod transaction_builder;
 pub mod transactions;
 
@@ -31,8 +36,14 @@ pub mod transactions;
 pub(crate) enum Wallet {
   #[clap(about = "Get wallet balance")]
   Balance,
+  #[clap(about = "Bump the fee of an unconfirmed transaction")]
+  Bump(bump::Bump),
+  #[clap(about = "Consolidate cardinal outputs into one")]
+  Consolidate(consolidate::Consolidate),
   #[clap(about = "Create new wallet")]
   Create(create::Create),
+  #[clap(about = "Extract sats into their own output")]
+  Extract(extract::Extract),
   #[clap(about = "Create inscription")]
   Inscribe(inscribe::Inscribe),
   #[clap(about = "List wallet inscriptions")]
@@ -45,6 +56,10 @@ pub(crate) enum Wallet {
   Sats(sats::Sats),
   #[clap(about = "Send sat or inscription")]
   Send(send::Send),
+  #[clap(about = "Send inscriptions or satpoints to many recipients in one transaction")]
+  SendBatch(send_batch::SendBatch),
+  #[clap(about = "Split cardinal outputs into outputs of a fixed value")]
+  Split(split::Split),
   #[clap(about = "See wallet transactions")]
   Transactions(transactions::Transactions),
   #[clap(about = "List all unspent outputs in wallet")]
@@ -57,13 +72,18 @@ impl Wallet {
   pub(crate) fn run(self, options: Options) -> Result {
     match self {
       Self::Balance => balance::run(options),
+      Self::Bump(bump) => bump.run(options),
+      Self::Consolidate(consolidate) => consolidate.run(options),
       Self::Create(create) => create.run(options),
+      Self::Extract(extract) => extract.run(options),
       Self::Inscribe(inscribe) => inscribe.run(options),
       Self::Inscriptions(inscriptions) => inscriptions.run(options),
       Self::Receive => receive::run(options),
       Self::Restore(restore) => restore.run(options),
       Self::Sats(sats) => sats.run(options),
       Self::Send(send) => send.run(options),
+      Self::SendBatch(send_batch) => send_batch.run(options),
+      Self::Split(split) => split.run(options),
       Self::Transactions(transactions) => transactions.run(options),
       Self::Outputs => outputs::run(options),
       Self::Cardinals => cardinals::run(options),
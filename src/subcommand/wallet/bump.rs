@@ -0,0 +1,597 @@
+use {
+  super::*,
+  crate::wallet::Wallet,
+  bitcoin::{blockdata::locktime::absolute::LockTime, Witness},
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Bump {
+  #[clap(help = "Bump the fee of unconfirmed transaction <TXID>.")]
+  txid: Txid,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Method {
+  Cpfp,
+  Rbf,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub method: Method,
+  pub transaction: Txid,
+  pub fee: u64,
+}
+
+impl Bump {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let info = client.get_raw_transaction_info(&self.txid, None)?;
+
+    if info.confirmations.unwrap_or_default() > 0 {
+      bail!("transaction {} is already confirmed", self.txid);
+    }
+
+    let parent = info.transaction()?;
+
+    let mut input_values = Vec::new();
+    for input in &parent.input {
+      let outpoint = input.previous_output;
+      input_values.push(Amount::from_sat(
+        client.get_raw_transaction(&outpoint.txid, None)?.output[outpoint.vout as usize].value,
+      ));
+    }
+
+    let fee = input_values
+      .iter()
+      .copied()
+      .sum::<Amount>()
+      .checked_sub(
+        parent
+          .output
+          .iter()
+          .map(|output| Amount::from_sat(output.value))
+          .sum(),
+      )
+      .ok_or(transaction_builder::Error::ValueOverflow)?;
+
+    if fee >= self.fee_rate.fee(parent.weight()) {
+      bail!(
+        "transaction {} already pays a fee of {} sats, at least {} sat/vB",
+        self.txid,
+        fee.to_sat(),
+        self.fee_rate.rate(),
+      );
+    }
+
+    let inscribed = inscribed_offsets(&index, &parent, &input_values)?;
+
+    let is_mine = |output: &TxOut| -> Result<bool> {
+      let Ok(address) = Address::from_script(&output.script_pubkey, options.chain().network())
+      else {
+        return Ok(false);
+      };
+
+      Ok(
+        client
+          .get_address_info(&address)?
+          .is_mine
+          .unwrap_or_default(),
+      )
+    };
+
+    // Reveal transactions are signed with an ephemeral key the wallet does not
+    // hold, so they can only be bumped with a child. Replacing a transaction
+    // evicts its unconfirmed children, such as the reveal transaction spending
+    // a commit transaction's first output, so those are bumped with a child too.
+    if parent.is_explicitly_rbf()
+      && Inscription::from_transaction(&parent).is_empty()
+      && !has_unconfirmed_children(&client, &parent)?
+      && is_mine(parent.output.last().unwrap())?
+    {
+      if let Some((replacement, replacement_fee)) = replace(&parent, fee, &inscribed, self.fee_rate)
+      {
+        let signed = client.sign_raw_transaction_with_wallet(&replacement, None, None)?;
+
+        if signed.complete {
+          let txid = client.send_raw_transaction(&signed.hex)?;
+
+          print_json(Output {
+            method: Method::Rbf,
+            transaction: txid,
+            fee: replacement_fee.to_sat(),
+          })?;
+
+          return Ok(());
+        }
+      }
+    }
+
+    let mut cardinal = None;
+    let mut inscribed_output = None;
+    let mut start = 0;
+
+    for (vout, output) in parent.output.iter().enumerate() {
+      let end = start + output.value;
+
+      if is_mine(output)? {
+        let outpoint = OutPoint {
+          txid: self.txid,
+          vout: vout.try_into().unwrap(),
+        };
+
+        let inscriptions = inscribed
+          .iter()
+          .filter(|(offset, _id)| (start..end).contains(offset))
+          .map(|(offset, id)| {
+            (
+              SatPoint {
+                outpoint,
+                offset: offset - start,
+              },
+              *id,
+            )
+          })
+          .collect::<Vec<(SatPoint, InscriptionId)>>();
+
+        if inscriptions.is_empty() {
+          if cardinal.map_or(true, |(_outpoint, value)| output.value > value) {
+            cardinal = Some((outpoint, output.value));
+          }
+        } else if inscribed_output.is_none() {
+          inscribed_output = Some((outpoint, output.value, inscriptions));
+        }
+      }
+
+      start = end;
+    }
+
+    let spent = parent
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let mut unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+    unspent_outputs.retain(|outpoint, _amount| !spent.contains(outpoint));
+
+    let mut inscriptions = index.get_inscriptions(unspent_outputs.clone())?;
+
+    let (child, child_fee) = if let Some((outpoint, value)) = cardinal {
+      let cardinal_utxos = unspent_outputs
+        .into_iter()
+        .filter(|(outpoint, _amount)| {
+          !inscriptions
+            .keys()
+            .any(|satpoint| satpoint.outpoint == *outpoint)
+        })
+        .collect();
+
+      let (child, child_fee) = child_transaction(
+        parent.weight(),
+        fee,
+        (outpoint, Amount::from_sat(value)),
+        cardinal_utxos,
+        get_change_address(&client, &options)?,
+        self.fee_rate,
+      )?;
+
+      (
+        client.sign_raw_transaction_with_wallet(&child, None, None)?,
+        child_fee,
+      )
+    } else if let Some((outpoint, value, output_inscriptions)) = inscribed_output {
+      unspent_outputs.insert(outpoint, Amount::from_sat(value));
+      inscriptions.extend(output_inscriptions.iter().copied());
+
+      let recipient = get_change_address(&client, &options)?;
+      let change = [
+        get_change_address(&client, &options)?,
+        get_change_address(&client, &options)?,
+      ];
+
+      let mut child_fee_rate = self.fee_rate;
+
+      // The child's weight depends on the inputs the builder selects, so raise
+      // its fee rate until the package pays the target rate.
+      let mut signed = None;
+      for _ in 0..4 {
+        let child = TransactionBuilder::build_transaction_with_postage(
+          output_inscriptions[0].0,
+          inscriptions.clone(),
+          unspent_outputs.clone(),
+          recipient.clone(),
+          None,
+          change.clone(),
+          child_fee_rate,
+          None,
+          TransactionBuilder::DEFAULT_TARGET_POSTAGE,
+          TransactionBuilder::DEFAULT_MAX_POSTAGE,
+        )?;
+
+        let child_fee = child
+          .input
+          .iter()
+          .map(|input| unspent_outputs[&input.previous_output])
+          .sum::<Amount>()
+          - child
+            .output
+            .iter()
+            .map(|output| Amount::from_sat(output.value))
+            .sum::<Amount>();
+
+        let result = client.sign_raw_transaction_with_wallet(&child, None, None)?;
+
+        let weight = result.transaction()?.weight();
+
+        let required = self
+          .fee_rate
+          .fee(parent.weight() + weight)
+          .checked_sub(fee)
+          .unwrap_or(Amount::ZERO);
+
+        if child_fee >= required {
+          signed = Some((result, child_fee));
+          break;
+        }
+
+        #[allow(clippy::cast_precision_loss)]
+        let rate = required.to_sat() as f64 * 4.0 / weight.to_wu() as f64;
+
+        child_fee_rate = FeeRate::try_from(rate)?;
+      }
+
+      signed.ok_or_else(|| {
+        anyhow!(
+          "could not build child transaction paying {} sat/vB for transaction {}",
+          self.fee_rate.rate(),
+          self.txid
+        )
+      })?
+    } else {
+      bail!(
+        "transaction {} cannot be replaced and has no outputs spendable by this wallet",
+        self.txid
+      );
+    };
+
+    let txid = client.send_raw_transaction(&child.hex)?;
+
+    print_json(Output {
+      method: Method::Cpfp,
+      transaction: txid,
+      fee: child_fee.to_sat(),
+    })?;
+
+    Ok(())
+  }
+}
+
+/// Whether any output of `tx` is spent by a transaction in the mempool.
+fn has_unconfirmed_children(client: &Client, tx: &Transaction) -> Result<bool> {
+  #[derive(Deserialize)]
+  struct Spending {
+    spendingtxid: Option<Txid>,
+  }
+
+  let txid = tx.txid();
+
+  let outputs = (0..tx.output.len())
+    .map(|vout| serde_json::json!({ "txid": txid, "vout": vout }))
+    .collect::<Vec<serde_json::Value>>();
+
+  Ok(
+    client
+      .call::<Vec<Spending>>("gettxspendingprevout", &[outputs.into()])?
+      .iter()
+      .any(|spending| spending.spendingtxid.is_some()),
+  )
+}
+
+/// Offsets into the transaction's input sats of inscriptions the transaction
+/// moves or creates.
+fn inscribed_offsets(
+  index: &Index,
+  tx: &Transaction,
+  input_values: &[Amount],
+) -> Result<Vec<(u64, InscriptionId)>> {
+  let mut inscribed = Vec::new();
+  let mut input_offsets = Vec::new();
+  let mut offset = 0;
+
+  for (input, value) in tx.input.iter().zip(input_values) {
+    for (satpoint, inscription_id) in
+      index.get_inscriptions_on_output_with_satpoints(input.previous_output)?
+    {
+      inscribed.push((offset + satpoint.offset, inscription_id));
+    }
+
+    input_offsets.push(offset);
+    offset += value.to_sat();
+  }
+
+  for (i, inscription) in Inscription::from_transaction(tx).into_iter().enumerate() {
+    inscribed.push((
+      input_offsets[usize::try_from(inscription.tx_in_index).unwrap()],
+      InscriptionId {
+        txid: tx.txid(),
+        index: i.try_into().unwrap(),
+      },
+    ));
+  }
+
+  Ok(inscribed)
+}
+
+/// Pay a higher fee out of the last output, which must carry no inscribed
+/// sats, leaving every other output, and thus every inscription, where it is.
+fn replace(
+  parent: &Transaction,
+  fee: Amount,
+  inscribed: &[(u64, InscriptionId)],
+  fee_rate: FeeRate,
+) -> Option<(Transaction, Amount)> {
+  let (last, rest) = parent.output.split_last()?;
+
+  let start = rest.iter().map(|output| output.value).sum::<u64>();
+
+  if inscribed.iter().any(|(offset, _id)| *offset >= start) {
+    return None;
+  }
+
+  // BIP 125 requires a replacement to pay for its own relay on top of the
+  // fee of the transaction it replaces.
+  let replacement_fee = cmp::max(
+    fee_rate.fee(parent.weight()),
+    fee + Amount::from_sat(parent.vsize().try_into().unwrap()),
+  );
+
+  let value = Amount::from_sat(last.value).checked_sub(replacement_fee - fee)?;
+
+  if value < last.script_pubkey.dust_value() {
+    return None;
+  }
+
+  let mut replacement = parent.clone();
+
+  for input in &mut replacement.input {
+    input.witness = Witness::new();
+  }
+
+  replacement.output.last_mut().unwrap().value = value.to_sat();
+
+  Some((replacement, replacement_fee))
+}
+
+/// Spend `parent_output` and as many cardinal UTXOs as needed to pay for both
+/// the child and the parent at `fee_rate`.
+fn child_transaction(
+  parent_weight: Weight,
+  parent_fee: Amount,
+  parent_output: (OutPoint, Amount),
+  cardinal_utxos: BTreeMap<OutPoint, Amount>,
+  change: Address,
+  fee_rate: FeeRate,
+) -> Result<(Transaction, Amount)> {
+  let mut utxos = cardinal_utxos
+    .into_iter()
+    .collect::<Vec<(OutPoint, Amount)>>();
+
+  utxos.sort_by_key(|(_outpoint, amount)| *amount);
+
+  let (outpoint, mut total) = parent_output;
+  let mut inputs = vec![outpoint];
+
+  loop {
+    let child_fee = fee_rate
+      .fee(
+        parent_weight
+          + TransactionBuilder::estimate_weight_with(inputs.len(), vec![change.clone()]),
+      )
+      .checked_sub(parent_fee)
+      .unwrap_or(Amount::ZERO);
+
+    if let Some(value) = total.checked_sub(child_fee) {
+      if value >= change.script_pubkey().dust_value() {
+        let child = Transaction {
+          version: 1,
+          lock_time: LockTime::ZERO,
+          input: inputs
+            .into_iter()
+            .map(|outpoint| TxIn {
+              previous_output: outpoint,
+              script_sig: ScriptBuf::new(),
+              sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+              witness: Witness::new(),
+            })
+            .collect(),
+          output: vec![TxOut {
+            value: value.to_sat(),
+            script_pubkey: change.script_pubkey(),
+          }],
+        };
+
+        return Ok((child, child_fee));
+      }
+    }
+
+    let (outpoint, amount) = utxos
+      .pop()
+      .ok_or(transaction_builder::Error::NotEnoughCardinalUtxos)?;
+
+    inputs.push(outpoint);
+    total += amount;
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn parent(outputs: &[(Address, u64)]) -> Transaction {
+    Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+        witness: Witness::from_slice(&[&[0; 64]]),
+      }],
+      output: outputs
+        .iter()
+        .map(|(address, value)| TxOut {
+          value: *value,
+          script_pubkey: address.script_pubkey(),
+        })
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn replacement_pays_fee_from_last_output() {
+    let parent = parent(&[(recipient(), 10_000), (change(0), 20_000)]);
+    let fee_rate = FeeRate::try_from(10.0).unwrap();
+
+    let (replacement, fee) = replace(
+      &parent,
+      Amount::from_sat(100),
+      &[(0, inscription_id(1))],
+      fee_rate,
+    )
+    .unwrap();
+
+    assert_eq!(fee, fee_rate.fee(parent.weight()));
+    assert_eq!(replacement.input, {
+      let mut input = parent.input.clone();
+      input[0].witness = Witness::new();
+      input
+    });
+    assert_eq!(replacement.output[0], parent.output[0]);
+    assert_eq!(replacement.output[1].value, 20_000 - (fee.to_sat() - 100));
+  }
+
+  #[test]
+  fn replacement_pays_for_its_own_relay() {
+    let parent = parent(&[(recipient(), 10_000), (change(0), 20_000)]);
+
+    let (_replacement, fee) = replace(
+      &parent,
+      Amount::from_sat(150),
+      &[],
+      FeeRate::try_from(1.1).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(fee.to_sat(), 150 + u64::try_from(parent.vsize()).unwrap());
+  }
+
+  #[test]
+  fn do_not_replace_if_last_output_is_inscribed() {
+    assert_eq!(
+      replace(
+        &parent(&[(recipient(), 10_000), (change(0), 20_000)]),
+        Amount::from_sat(100),
+        &[(15_000, inscription_id(1))],
+        FeeRate::try_from(10.0).unwrap(),
+      ),
+      None,
+    );
+  }
+
+  #[test]
+  fn do_not_replace_if_last_output_would_be_dust() {
+    assert_eq!(
+      replace(
+        &parent(&[(recipient(), 10_000), (change(0), 1_000)]),
+        Amount::from_sat(100),
+        &[],
+        FeeRate::try_from(10.0).unwrap(),
+      ),
+      None,
+    );
+  }
+
+  #[test]
+  fn child_pays_for_parent() {
+    let parent = parent(&[(recipient(), 10_000), (change(0), 20_000)]);
+    let fee_rate = FeeRate::try_from(10.0).unwrap();
+
+    let (child, child_fee) = child_transaction(
+      parent.weight(),
+      Amount::from_sat(100),
+      (outpoint(2), Amount::from_sat(20_000)),
+      BTreeMap::new(),
+      change(1),
+      fee_rate,
+    )
+    .unwrap();
+
+    assert_eq!(child.input.len(), 1);
+    assert_eq!(child.input[0].previous_output, outpoint(2));
+
+    assert_eq!(child_fee.to_sat(), 20_000 - child.output[0].value);
+
+    assert_eq!(
+      child_fee.to_sat() + 100,
+      fee_rate
+        .fee(parent.weight() + TransactionBuilder::estimate_weight_with(1, vec![change(1)]))
+        .to_sat()
+    );
+  }
+
+  #[test]
+  fn child_adds_cardinal_inputs_if_needed() {
+    let parent = parent(&[(recipient(), 10_000), (change(0), 500)]);
+
+    let (child, _fee) = child_transaction(
+      parent.weight(),
+      Amount::from_sat(100),
+      (outpoint(2), Amount::from_sat(500)),
+      [
+        (outpoint(3), Amount::from_sat(1_000)),
+        (outpoint(4), Amount::from_sat(50_000)),
+      ]
+      .into_iter()
+      .collect(),
+      change(1),
+      FeeRate::try_from(10.0).unwrap(),
+    )
+    .unwrap();
+
+    assert_eq!(
+      child
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(2), outpoint(4)],
+    );
+  }
+
+  #[test]
+  fn child_fails_without_enough_cardinals() {
+    assert_eq!(
+      child_transaction(
+        parent(&[(recipient(), 10_000), (change(0), 500)]).weight(),
+        Amount::from_sat(100),
+        (outpoint(2), Amount::from_sat(500)),
+        BTreeMap::new(),
+        change(1),
+        FeeRate::try_from(10.0).unwrap(),
+      )
+      .unwrap_err()
+      .downcast::<transaction_builder::Error>()
+      .unwrap(),
+      transaction_builder::Error::NotEnoughCardinalUtxos,
+    );
+  }
+}
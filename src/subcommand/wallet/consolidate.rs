@@ -0,0 +1,286 @@
+use {
+  super::*,
+  crate::wallet::Wallet,
+  bitcoin::{blockdata::locktime::absolute::LockTime, Witness},
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Consolidate {
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[clap(
+    long,
+    help = "Use at most <MAX_INPUTS> inputs, consolidating the smallest cardinal outputs first."
+  )]
+  max_inputs: Option<usize>,
+  #[clap(long, help = "Send consolidated output to <DESTINATION>.")]
+  destination: Option<Address<NetworkUnchecked>>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub inputs: usize,
+  pub value: u64,
+}
+
+impl Consolidate {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+    let inscribed_utxos = index
+      .get_inscriptions(unspent_outputs.clone())?
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let cardinal_utxos = unspent_outputs
+      .into_iter()
+      .filter(|(outpoint, _amount)| !inscribed_utxos.contains(outpoint))
+      .collect();
+
+    let destination = match self.destination {
+      Some(destination) => destination.require_network(options.chain().network())?,
+      None => get_change_address(&client, &options)?,
+    };
+
+    let unsigned_transaction =
+      Self::build_transaction(cardinal_utxos, destination, self.fee_rate, self.max_inputs)?;
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    print_json(Output {
+      transaction: txid,
+      inputs: unsigned_transaction.input.len(),
+      value: unsigned_transaction.output[0].value,
+    })?;
+
+    Ok(())
+  }
+
+  /// Spend the smallest cardinal UTXOs that pay for their own inclusion into
+  /// a single output.
+  fn build_transaction(
+    cardinal_utxos: BTreeMap<OutPoint, Amount>,
+    destination: Address,
+    fee_rate: FeeRate,
+    max_inputs: Option<usize>,
+  ) -> Result<Transaction> {
+    let additional_fee = fee_rate.fee(TransactionBuilder::ADDITIONAL_INPUT_WEIGHT);
+
+    let mut utxos = cardinal_utxos
+      .into_iter()
+      .filter(|(_outpoint, amount)| *amount > additional_fee)
+      .collect::<Vec<(OutPoint, Amount)>>();
+
+    utxos.sort_by_key(|(_outpoint, amount)| *amount);
+
+    if let Some(max_inputs) = max_inputs {
+      utxos.truncate(max_inputs);
+    }
+
+    if utxos.len() < 2 {
+      bail!("wallet contains fewer than two cardinal outputs worth consolidating");
+    }
+
+    let total = utxos
+      .iter()
+      .map(|(_outpoint, amount)| *amount)
+      .sum::<Amount>();
+
+    let fee = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+      utxos.len(),
+      vec![destination.clone()],
+    ));
+
+    let value = total
+      .checked_sub(fee)
+      .ok_or(transaction_builder::Error::NotEnoughCardinalUtxos)?;
+
+    let dust_value = destination.script_pubkey().dust_value();
+
+    if value < dust_value {
+      return Err(
+        transaction_builder::Error::Dust {
+          output_value: value,
+          dust_value,
+        }
+        .into(),
+      );
+    }
+
+    Ok(Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: utxos
+        .into_iter()
+        .map(|(outpoint, _amount)| TxIn {
+          previous_output: outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: vec![TxOut {
+        value: value.to_sat(),
+        script_pubkey: destination.script_pubkey(),
+      }],
+    })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn fee(inputs: usize, fee_rate: FeeRate) -> u64 {
+    fee_rate
+      .fee(TransactionBuilder::estimate_weight_with(
+        inputs,
+        vec![recipient()],
+      ))
+      .to_sat()
+  }
+
+  #[test]
+  fn consolidate_all_cardinals() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let transaction = Consolidate::build_transaction(
+      [
+        (outpoint(1), Amount::from_sat(5_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+        (outpoint(3), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+      recipient(),
+      fee_rate,
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(
+      transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(1), outpoint(2), outpoint(3)],
+    );
+
+    assert_eq!(transaction.output.len(), 1);
+    assert_eq!(transaction.output[0].value, 35_000 - fee(3, fee_rate));
+    assert_eq!(
+      transaction.output[0].script_pubkey,
+      recipient().script_pubkey()
+    );
+  }
+
+  #[test]
+  fn consolidate_smallest_cardinals_first() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let transaction = Consolidate::build_transaction(
+      [
+        (outpoint(1), Amount::from_sat(30_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+        (outpoint(3), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+      recipient(),
+      fee_rate,
+      Some(2),
+    )
+    .unwrap();
+
+    assert_eq!(
+      transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(2), outpoint(3)],
+    );
+
+    assert_eq!(transaction.output[0].value, 30_000 - fee(2, fee_rate));
+  }
+
+  #[test]
+  fn do_not_consolidate_outputs_which_do_not_pay_for_their_own_fee() {
+    let transaction = Consolidate::build_transaction(
+      [
+        (outpoint(1), Amount::from_sat(500)),
+        (outpoint(2), Amount::from_sat(10_000)),
+        (outpoint(3), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+      recipient(),
+      FeeRate::try_from(10.0).unwrap(),
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(
+      transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(2), outpoint(3)],
+    );
+  }
+
+  #[test]
+  fn consolidate_requires_two_outputs() {
+    assert_eq!(
+      Consolidate::build_transaction(
+        [(outpoint(1), Amount::from_sat(10_000))]
+          .into_iter()
+          .collect(),
+        recipient(),
+        FeeRate::try_from(1.0).unwrap(),
+        None,
+      )
+      .unwrap_err()
+      .to_string(),
+      "wallet contains fewer than two cardinal outputs worth consolidating",
+    );
+  }
+
+  #[test]
+  fn consolidated_output_must_not_be_dust() {
+    assert_eq!(
+      Consolidate::build_transaction(
+        [
+          (outpoint(1), Amount::from_sat(600)),
+          (outpoint(2), Amount::from_sat(600)),
+        ]
+        .into_iter()
+        .collect(),
+        recipient(),
+        FeeRate::try_from(6.0).unwrap(),
+        None,
+      )
+      .unwrap_err()
+      .downcast::<transaction_builder::Error>()
+      .unwrap(),
+      transaction_builder::Error::Dust {
+        output_value: Amount::from_sat(1200 - fee(2, FeeRate::try_from(6.0).unwrap())),
+        dust_value: Amount::from_sat(294),
+      },
+    );
+  }
+}
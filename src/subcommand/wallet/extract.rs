@@ -0,0 +1,259 @@
+use {
+  super::*,
+  crate::{
+    sat_range::{SatRange, SatRangeSet},
+    wallet::Wallet,
+  },
+  std::iter,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Extract {
+  #[clap(help = "Extract <SAT> into its own output.")]
+  sat: Sat,
+  #[clap(help = "Extract all sats in the range <SAT>-<END> into their own output.")]
+  end: Option<Sat>,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[clap(long, help = "Send extracted sats to <DESTINATION>.")]
+  destination: Option<Address<NetworkUnchecked>>,
+  #[clap(long, help = "Send any alignment output to <ALIGNMENT>.")]
+  alignment: Option<Address<NetworkUnchecked>>,
+  #[clap(long, help = "Send any change output to <CHANGE>.")]
+  change: Option<Address<NetworkUnchecked>>,
+  #[clap(
+    long,
+    help = "Amount of postage to include in the extracted output if the extracted sats are worth less. Default `10000 sats`"
+  )]
+  postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Use at most <MAX_INPUTS> inputs to build the transaction."
+  )]
+  max_inputs: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub satpoint: SatPoint,
+  pub size: u64,
+}
+
+impl Extract {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let end = self.end.unwrap_or(self.sat + 1);
+
+    if self.sat >= end {
+      bail!("range is empty");
+    }
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+    let satpoint = locate(
+      index.get_unspent_output_ranges(Wallet::load(&options)?)?,
+      self.sat.n(),
+      end.n(),
+    )?;
+
+    let size = end.n() - self.sat.n();
+
+    if satpoint.offset == 0 && unspent_outputs[&satpoint.outpoint].to_sat() == size {
+      bail!("sats are already in their own output {}", satpoint.outpoint);
+    }
+
+    let inscriptions = index.get_inscriptions(unspent_outputs.clone())?;
+
+    let destination = match self.destination {
+      Some(destination) => destination.require_network(options.chain().network())?,
+      None => client
+        .get_new_address(None, Some(bitcoincore_rpc::json::AddressType::Bech32m))?
+        .require_network(options.chain().network())?,
+    };
+
+    let change = [
+      get_change_address(&client, &options)?,
+      match self.change {
+        Some(change) => change.require_network(options.chain().network())?,
+        None => get_change_address(&client, &options)?,
+      },
+    ];
+
+    let alignment = self
+      .alignment
+      .map(|alignment| alignment.require_network(options.chain().network()))
+      .transpose()?;
+
+    let postage = self
+      .postage
+      .unwrap_or(TransactionBuilder::DEFAULT_TARGET_POSTAGE);
+
+    let unsigned_transaction = TransactionBuilder::build_transaction_with_value(
+      satpoint,
+      inscriptions,
+      unspent_outputs,
+      destination.clone(),
+      alignment,
+      change,
+      self.fee_rate,
+      self.max_inputs,
+      cmp::max(Amount::from_sat(size), postage),
+    )?;
+
+    let vout = unsigned_transaction
+      .output
+      .iter()
+      .position(|output| output.script_pubkey == destination.script_pubkey())
+      .expect("extracted sats are sent to destination");
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    print_json(Output {
+      transaction: txid,
+      satpoint: SatPoint {
+        outpoint: OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        },
+        offset: 0,
+      },
+      size,
+    })?;
+
+    Ok(())
+  }
+}
+
+/// Find the satpoint of sat `start` in the wallet, and check that the sats up
+/// to `end` follow it contiguously in the same output.
+fn locate(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>, start: u64, end: u64) -> Result<SatPoint> {
+  let sats = iter::once(SatRange::new(start, end)).collect::<SatRangeSet>();
+
+  for (outpoint, ranges) in utxos {
+    let located = sats.locate(
+      &ranges
+        .into_iter()
+        .map(SatRange::from)
+        .collect::<Vec<SatRange>>(),
+    );
+
+    let offset = match located.iter().find(|(_offset, range)| range.start == start) {
+      Some((offset, _range)) => *offset,
+      None => continue,
+    };
+
+    let contiguous = located
+      .iter()
+      .all(|(located_offset, range)| *located_offset == offset + range.start - start)
+      && located
+        .iter()
+        .map(|(_offset, range)| range.size())
+        .sum::<u64>()
+        == end - start;
+
+    if !contiguous {
+      bail!("sats {start}-{end} are not contiguous in output {outpoint}");
+    }
+
+    return Ok(SatPoint { outpoint, offset });
+  }
+
+  bail!("sat {start} not found in wallet")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn locate_sat_in_first_range() {
+    assert_eq!(
+      locate(vec![(outpoint(1), vec![(10, 20)])], 15, 16).unwrap(),
+      SatPoint {
+        outpoint: outpoint(1),
+        offset: 5,
+      }
+    );
+  }
+
+  #[test]
+  fn locate_sat_in_later_range() {
+    assert_eq!(
+      locate(vec![(outpoint(1), vec![(0, 10), (50, 100)])], 60, 61).unwrap(),
+      SatPoint {
+        outpoint: outpoint(1),
+        offset: 20,
+      }
+    );
+  }
+
+  #[test]
+  fn locate_sat_in_later_output() {
+    assert_eq!(
+      locate(
+        vec![(outpoint(1), vec![(0, 10)]), (outpoint(2), vec![(10, 20)])],
+        10,
+        20
+      )
+      .unwrap(),
+      SatPoint {
+        outpoint: outpoint(2),
+        offset: 0,
+      }
+    );
+  }
+
+  #[test]
+  fn locate_range_spanning_adjacent_ranges() {
+    assert_eq!(
+      locate(vec![(outpoint(1), vec![(0, 10), (10, 20)])], 5, 15).unwrap(),
+      SatPoint {
+        outpoint: outpoint(1),
+        offset: 5,
+      }
+    );
+  }
+
+  #[test]
+  fn locate_range_must_be_contiguous() {
+    assert_eq!(
+      locate(vec![(outpoint(1), vec![(0, 10), (11, 20)])], 5, 15)
+        .unwrap_err()
+        .to_string(),
+      format!("sats 5-15 are not contiguous in output {}", outpoint(1)),
+    );
+  }
+
+  #[test]
+  fn locate_range_must_not_extend_past_output() {
+    assert_eq!(
+      locate(
+        vec![(outpoint(1), vec![(0, 10)]), (outpoint(2), vec![(10, 20)])],
+        5,
+        15
+      )
+      .unwrap_err()
+      .to_string(),
+      format!("sats 5-15 are not contiguous in output {}", outpoint(1)),
+    );
+  }
+
+  #[test]
+  fn locate_sat_not_in_wallet() {
+    assert_eq!(
+      locate(vec![(outpoint(1), vec![(0, 10)])], 10, 11)
+        .unwrap_err()
+        .to_string(),
+      "sat 10 not found in wallet",
+    );
+  }
+}
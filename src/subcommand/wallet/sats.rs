@@ -1,4 +1,11 @@
-use {super::*, crate::wallet::Wallet};
+use {
+  super::*,
+  crate::{
+    sat_range::{SatRange, SatRangeSet},
+    sat_trait::TraitRanges,
+    wallet::Wallet,
+  },
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Sats {
@@ -7,6 +14,12 @@ pub(crate) struct Sats {
     help = "Find satoshis listed in first column of tab-separated value file <TSV>."
   )]
   tsv: Option<PathBuf>,
+  #[clap(
+    long = "trait",
+    conflicts_with = "tsv",
+    help = "Only list satoshis with <TRAIT>. May be given more than once."
+  )]
+  traits: Vec<SatTrait>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -21,6 +34,7 @@ pub struct OutputRare {
   pub output: OutPoint,
   pub offset: u64,
   pub rarity: Rarity,
+  pub traits: Vec<SatTrait>,
 }
 
 impl Sats {
@@ -45,12 +59,16 @@ impl Sats {
       print_json(output)?;
     } else {
       let mut output = Vec::new();
-      for (outpoint, sat, offset, rarity) in rare_sats(utxos) {
+      let config = options.load_config()?;
+      for (outpoint, sat, offset, rarity, traits) in
+        rare_sats(utxos, &config.trait_ranges, &self.traits)
+      {
         output.push(OutputRare {
           sat,
           output: outpoint,
           offset,
           rarity,
+          traits,
         });
       }
       print_json(output)?;
@@ -60,24 +78,45 @@ impl Sats {
   }
 }
 
-fn rare_sats(utxos: Vec<(OutPoint, Vec<(u64, u64)>)>) -> Vec<(OutPoint, Sat, u64, Rarity)> {
-  utxos
-    .into_iter()
-    .flat_map(|(outpoint, sat_ranges)| {
-      let mut offset = 0;
-      sat_ranges.into_iter().filter_map(move |(start, end)| {
-        let sat = Sat(start);
+/// Without `filter`, list sats rarer than common. With it, list sats that
+/// have every trait in `filter`. Only the first and last sat of each range
+/// are checked.
+fn rare_sats(
+  utxos: Vec<(OutPoint, Vec<(u64, u64)>)>,
+  trait_ranges: &TraitRanges,
+  filter: &[SatTrait],
+) -> Vec<(OutPoint, Sat, u64, Rarity, Vec<SatTrait>)> {
+  let mut rare_sats = Vec::new();
+
+  for (outpoint, sat_ranges) in utxos {
+    let mut offset = 0;
+    for range in sat_ranges.into_iter().map(SatRange::from) {
+      let candidates = if range.size() > 1 {
+        vec![
+          (range.start, offset),
+          (range.end - 1, offset + range.size() - 1),
+        ]
+      } else {
+        vec![(range.start, offset)]
+      };
+
+      for (n, offset) in candidates {
+        let sat = Sat(n);
         let rarity = sat.rarity();
-        let start_offset = offset;
-        offset += end - start;
-        if rarity > Rarity::Common {
-          Some((outpoint, sat, start_offset, rarity))
-        } else {
-          None
+        let traits = SatTrait::of(sat, trait_ranges);
+
+        if filter.is_empty() && rarity > Rarity::Common
+          || !filter.is_empty() && filter.iter().all(|sat_trait| traits.contains(sat_trait))
+        {
+          rare_sats.push((outpoint, sat, offset, rarity, traits));
         }
-      })
-    })
-    .collect()
+      }
+
+      offset += range.size();
+    }
+  }
+
+  rare_sats
 }
 
 fn sats_from_tsv(
@@ -103,35 +142,37 @@ fn sats_from_tsv(
   }
   needles.sort();
 
-  let mut haystacks = utxos
-    .into_iter()
-    .flat_map(|(outpoint, ranges)| {
-      ranges
-        .into_iter()
-        .map(move |(start, end)| (start, end, outpoint))
-    })
-    .collect::<Vec<(u64, u64, OutPoint)>>();
-  haystacks.sort();
+  let sats = needles
+    .iter()
+    .map(|(sat, _value)| SatRange::new(sat.n(), sat.n() + 1))
+    .collect::<SatRangeSet>();
 
-  let mut i = 0;
-  let mut j = 0;
   let mut results = Vec::new();
-  while i < needles.len() && j < haystacks.len() {
-    let (needle, value) = needles[i];
-    let (start, end, outpoint) = haystacks[j];
-
-    if needle >= start && needle < end {
-      results.push((outpoint, value));
-    }
-
-    if needle >= end {
-      j += 1;
-    } else {
-      i += 1;
+  for (outpoint, ranges) in utxos {
+    let ranges = ranges
+      .into_iter()
+      .map(SatRange::from)
+      .collect::<Vec<SatRange>>();
+
+    for (_offset, range) in sats.locate(&ranges) {
+      let i = needles.partition_point(|(sat, _value)| sat.n() < range.start);
+      results.extend(
+        needles[i..]
+          .iter()
+          .take_while(|(sat, _value)| sat.n() < range.end)
+          .map(|(sat, value)| (*sat, outpoint, *value)),
+      );
     }
   }
 
-  Ok(results)
+  results.sort_by_key(|(sat, _outpoint, _value)| *sat);
+
+  Ok(
+    results
+      .into_iter()
+      .map(|(_sat, outpoint, value)| (outpoint, value))
+      .collect(),
+  )
 }
 
 #[cfg(test)]
@@ -141,10 +182,14 @@ mod tests {
   #[test]
   fn identify_no_rare_sats() {
     assert_eq!(
-      rare_sats(vec![(
-        outpoint(1),
-        vec![(51 * COIN_VALUE, 100 * COIN_VALUE), (1234, 5678)],
-      )]),
+      rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(51 * COIN_VALUE, 100 * COIN_VALUE), (1234, 5678)],
+        )],
+        &TraitRanges::new(),
+        &[]
+      ),
       Vec::new()
     )
   }
@@ -152,24 +197,60 @@ mod tests {
   #[test]
   fn identify_one_rare_sat() {
     assert_eq!(
-      rare_sats(vec![(
+      rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(10, 80), (50 * COIN_VALUE, 100 * COIN_VALUE)],
+        )],
+        &TraitRanges::new(),
+        &[]
+      ),
+      vec![(
         outpoint(1),
-        vec![(10, 80), (50 * COIN_VALUE, 100 * COIN_VALUE)],
-      )]),
-      vec![(outpoint(1), Sat(50 * COIN_VALUE), 70, Rarity::Uncommon)]
+        Sat(50 * COIN_VALUE),
+        70,
+        Rarity::Uncommon,
+        vec![
+          SatTrait::Alpha,
+          SatTrait::FirstInBlock,
+          SatTrait::RoundNumber,
+          SatTrait::Vintage
+        ]
+      )]
     )
   }
 
   #[test]
   fn identify_two_rare_sats() {
     assert_eq!(
-      rare_sats(vec![(
-        outpoint(1),
-        vec![(0, 100), (1050000000000000, 1150000000000000)],
-      )]),
+      rare_sats(
+        vec![(
+          outpoint(1),
+          vec![(0, 100), (1050000000000000, 1150000000000000)],
+        )],
+        &TraitRanges::new(),
+        &[]
+      ),
       vec![
-        (outpoint(1), Sat(0), 0, Rarity::Mythic),
-        (outpoint(1), Sat(1050000000000000), 100, Rarity::Epic)
+        (
+          outpoint(1),
+          Sat(0),
+          0,
+          Rarity::Mythic,
+          vec![
+            SatTrait::Alpha,
+            SatTrait::FirstInBlock,
+            SatTrait::Palindrome,
+            SatTrait::Vintage
+          ]
+        ),
+        (
+          outpoint(1),
+          Sat(1050000000000000),
+          100,
+          Rarity::Epic,
+          vec![SatTrait::Alpha, SatTrait::FirstInBlock]
+        )
       ]
     )
   }
@@ -177,17 +258,82 @@ mod tests {
   #[test]
   fn identify_rare_sats_in_different_outpoints() {
     assert_eq!(
-      rare_sats(vec![
-        (outpoint(1), vec![(50 * COIN_VALUE, 55 * COIN_VALUE)]),
-        (outpoint(2), vec![(100 * COIN_VALUE, 111 * COIN_VALUE)],),
-      ]),
+      rare_sats(
+        vec![
+          (outpoint(1), vec![(50 * COIN_VALUE, 55 * COIN_VALUE)]),
+          (outpoint(2), vec![(100 * COIN_VALUE, 111 * COIN_VALUE)],),
+        ],
+        &TraitRanges::new(),
+        &[]
+      ),
       vec![
-        (outpoint(1), Sat(50 * COIN_VALUE), 0, Rarity::Uncommon),
-        (outpoint(2), Sat(100 * COIN_VALUE), 0, Rarity::Uncommon)
+        (
+          outpoint(1),
+          Sat(50 * COIN_VALUE),
+          0,
+          Rarity::Uncommon,
+          vec![
+            SatTrait::Alpha,
+            SatTrait::FirstInBlock,
+            SatTrait::RoundNumber,
+            SatTrait::Vintage
+          ]
+        ),
+        (
+          outpoint(2),
+          Sat(100 * COIN_VALUE),
+          0,
+          Rarity::Uncommon,
+          vec![
+            SatTrait::Alpha,
+            SatTrait::FirstInBlock,
+            SatTrait::RoundNumber,
+            SatTrait::Vintage
+          ]
+        )
       ]
     )
   }
 
+  #[test]
+  fn identify_sats_by_trait() {
+    let utxos = vec![(
+      outpoint(1),
+      vec![(10, 20), (50 * COIN_VALUE, 100 * COIN_VALUE)],
+    )];
+
+    assert_eq!(
+      rare_sats(utxos.clone(), &TraitRanges::new(), &[SatTrait::LastInBlock]),
+      vec![(
+        outpoint(1),
+        Sat(100 * COIN_VALUE - 1),
+        10 + 50 * COIN_VALUE - 1,
+        Rarity::Common,
+        vec![
+          SatTrait::LastInBlock,
+          SatTrait::Omega,
+          SatTrait::Palindrome,
+          SatTrait::Vintage,
+        ],
+      )]
+    );
+
+    assert_eq!(
+      rare_sats(
+        utxos,
+        &[(SatTrait::Pizza, vec![(15, 25)])].into_iter().collect(),
+        &[SatTrait::Pizza, SatTrait::Vintage],
+      ),
+      vec![(
+        outpoint(1),
+        Sat(19),
+        9,
+        Rarity::Common,
+        vec![SatTrait::Pizza, SatTrait::Vintage],
+      )]
+    );
+  }
+
   #[test]
   fn identify_from_tsv_none() {
     assert_eq!(
@@ -0,0 +1,653 @@
+use {
+  super::*,
+  crate::wallet::Wallet,
+  bitcoin::{blockdata::locktime::absolute::LockTime, Witness},
+  std::collections::BTreeSet,
+  transaction_builder::Error,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct SendBatch {
+  #[clap(
+    help = "Send the inscription ID or satpoint on each line of <FILE> to the address that follows it."
+  )]
+  file: PathBuf,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[clap(
+    long,
+    help = "Target amount of postage to include in each sent output. Default `10000 sats`"
+  )]
+  target_postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Maximum amount of postage to include in each sent output. Default `20000 sats`"
+  )]
+  max_postage: Option<Amount>,
+  #[clap(
+    long,
+    help = "Use at most <MAX_INPUTS> inputs to build the transaction."
+  )]
+  max_inputs: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub outputs: Vec<OutPoint>,
+}
+
+impl SendBatch {
+  pub(crate) fn run(self, options: Options) -> Result {
+    let batch = parse(
+      &fs::read_to_string(&self.file)
+        .with_context(|| format!("I/O error reading `{}`", self.file.display()))?,
+      options.chain().network(),
+    )?;
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+    let inscriptions = index.get_inscriptions(unspent_outputs.clone())?;
+
+    let mut outgoing = Vec::new();
+    for (entry, address) in batch {
+      let satpoint = match entry {
+        Outgoing::SatPoint(satpoint) => {
+          if inscriptions.contains_key(&satpoint) {
+            bail!("inscriptions must be sent by inscription ID");
+          }
+          satpoint
+        }
+        Outgoing::InscriptionId(id) => index
+          .get_inscription_satpoint_by_id(id)?
+          .ok_or_else(|| anyhow!("Inscription {id} not found"))?,
+        _ => unreachable!("batch entries are inscription IDs or satpoints"),
+      };
+
+      outgoing.push((satpoint, address));
+    }
+
+    let unsigned_transaction = build_transaction(
+      outgoing,
+      inscriptions,
+      unspent_outputs,
+      [
+        get_change_address(&client, &options)?,
+        get_change_address(&client, &options)?,
+      ],
+      self.fee_rate,
+      self.max_inputs,
+      self
+        .target_postage
+        .unwrap_or(TransactionBuilder::DEFAULT_TARGET_POSTAGE),
+      self
+        .max_postage
+        .unwrap_or(TransactionBuilder::DEFAULT_MAX_POSTAGE),
+    )?;
+
+    let recipients = unsigned_transaction.recipients.clone();
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction.transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    print_json(Output {
+      transaction: txid,
+      outputs: recipients
+        .into_iter()
+        .map(|vout| OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        })
+        .collect(),
+    })?;
+
+    Ok(())
+  }
+}
+
+/// Parse a batch file. Each non-empty line that isn't a `#` comment contains
+/// an inscription ID or satpoint followed by a recipient address.
+fn parse(contents: &str, network: Network) -> Result<Vec<(Outgoing, Address)>> {
+  let mut batch = Vec::new();
+
+  for (i, line) in contents.lines().enumerate() {
+    let line = line.trim();
+
+    if line.is_empty() || line.starts_with('#') {
+      continue;
+    }
+
+    let n = i + 1;
+
+    let fields = line.split_whitespace().collect::<Vec<&str>>();
+
+    let [outgoing, address] = fields.as_slice() else {
+      bail!("line {n}: expected `<INSCRIPTION_ID|SATPOINT> <ADDRESS>`");
+    };
+
+    let outgoing = outgoing
+      .parse::<Outgoing>()
+      .with_context(|| format!("line {n}: invalid inscription ID or satpoint `{outgoing}`"))?;
+
+    if !matches!(outgoing, Outgoing::InscriptionId(_) | Outgoing::SatPoint(_)) {
+      bail!("line {n}: only inscription IDs and satpoints can be sent in a batch");
+    }
+
+    let address = address
+      .parse::<Address<NetworkUnchecked>>()
+      .with_context(|| format!("line {n}: invalid address `{address}`"))?
+      .require_network(network)
+      .with_context(|| format!("line {n}: address `{address}` is not valid for {network}"))?;
+
+    batch.push((outgoing, address));
+  }
+
+  if batch.is_empty() {
+    bail!("batch file contains no entries");
+  }
+
+  Ok(batch)
+}
+
+#[derive(Debug)]
+struct Batch {
+  transaction: Transaction,
+  recipients: Vec<usize>,
+}
+
+/// Build a transaction sending each outgoing satpoint to its recipient in a
+/// postage output of its own. Sats preceding an outgoing satpoint in its
+/// output are returned to `change[0]`, excess postage and fee change to
+/// `change[1]`.
+fn build_transaction(
+  outgoing: Vec<(SatPoint, Address)>,
+  inscriptions: BTreeMap<SatPoint, InscriptionId>,
+  amounts: BTreeMap<OutPoint, Amount>,
+  change: [Address; 2],
+  fee_rate: FeeRate,
+  max_inputs: Option<usize>,
+  target_postage: Amount,
+  max_postage: Amount,
+) -> Result<Batch> {
+  TransactionBuilder::check_postage(target_postage, max_postage)?;
+
+  let dust_limit = change[1].script_pubkey().dust_value();
+
+  let mut outgoing_outpoints = BTreeSet::new();
+
+  for (satpoint, recipient) in &outgoing {
+    if change.contains(recipient) {
+      return Err(Error::DuplicateAddress(recipient.clone()).into());
+    }
+
+    TransactionBuilder::check_additional_inscriptions(*satpoint, &inscriptions, dust_limit)?;
+
+    TransactionBuilder::outgoing_value(*satpoint, &amounts)?;
+
+    if !outgoing_outpoints.insert(satpoint.outpoint) {
+      bail!(
+        "cannot send more than one satpoint from output {} in a batch",
+        satpoint.outpoint
+      );
+    }
+  }
+
+  let mut cardinals = amounts
+    .keys()
+    .filter(|outpoint| !outgoing_outpoints.contains(outpoint))
+    .copied()
+    .collect::<BTreeSet<OutPoint>>();
+
+  let mut select_cardinal = |target_value, prefer_under| {
+    TransactionBuilder::select_cardinal(
+      &mut cardinals,
+      &amounts,
+      &inscriptions,
+      target_value,
+      prefer_under,
+    )
+  };
+
+  let mut inputs = Vec::new();
+  let mut outputs = Vec::new();
+  let mut recipients = Vec::new();
+
+  for (satpoint, recipient) in &outgoing {
+    if satpoint.offset > 0 {
+      let (padding, alignment) = TransactionBuilder::pad_alignment(
+        Amount::from_sat(satpoint.offset),
+        dust_limit,
+        |target_value| select_cardinal(target_value, true),
+      )?;
+
+      inputs.extend(padding);
+      outputs.push((change[0].clone(), alignment));
+    }
+
+    inputs.push(satpoint.outpoint);
+
+    let value = amounts[&satpoint.outpoint] - Amount::from_sat(satpoint.offset);
+
+    TransactionBuilder::check_dust(recipient, value)?;
+
+    recipients.push(outputs.len());
+
+    match TransactionBuilder::excess_postage(
+      value,
+      Amount::ZERO,
+      Amount::ZERO,
+      target_postage,
+      max_postage,
+      dust_limit,
+    ) {
+      Some(excess) => {
+        outputs.push((recipient.clone(), value - excess));
+        outputs.push((change[1].clone(), excess));
+      }
+      None => outputs.push((recipient.clone(), value)),
+    }
+  }
+
+  let mut funding = Amount::ZERO;
+
+  loop {
+    let addresses = outputs
+      .iter()
+      .map(|(address, _amount)| address.clone())
+      .collect::<Vec<Address>>();
+
+    let fee_with_change = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+      inputs.len(),
+      addresses
+        .iter()
+        .cloned()
+        .chain(std::iter::once(change[1].clone()))
+        .collect(),
+    ));
+
+    if let Some(change_value) = funding.checked_sub(fee_with_change) {
+      if change_value >= dust_limit {
+        outputs.push((change[1].clone(), change_value));
+        break;
+      }
+    }
+
+    let fee = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+      inputs.len(),
+      addresses,
+    ));
+
+    if funding > Amount::ZERO && funding >= fee {
+      break;
+    }
+
+    let (outpoint, amount) = select_cardinal(
+      (fee_with_change + dust_limit)
+        .checked_sub(funding)
+        .unwrap_or_default(),
+      false,
+    )?;
+
+    inputs.push(outpoint);
+    funding += amount;
+  }
+
+  if let Some(max_inputs) = max_inputs {
+    if inputs.len() > max_inputs {
+      return Err(Error::TooManyInputs(max_inputs).into());
+    }
+  }
+
+  for (address, amount) in &outputs {
+    assert!(
+      *amount >= address.script_pubkey().dust_value(),
+      "invariant: all outputs are above dust limit",
+    );
+  }
+
+  for ((satpoint, recipient), vout) in outgoing.iter().zip(&recipients) {
+    let sat_offset = inputs
+      .iter()
+      .take_while(|outpoint| **outpoint != satpoint.outpoint)
+      .map(|outpoint| amounts[outpoint])
+      .sum::<Amount>()
+      + Amount::from_sat(satpoint.offset);
+
+    let output_offset = outputs[..*vout]
+      .iter()
+      .map(|(_address, amount)| *amount)
+      .sum::<Amount>();
+
+    assert_eq!(
+      sat_offset, output_offset,
+      "invariant: outgoing sat is aligned"
+    );
+
+    assert_eq!(
+      outputs[*vout].0, *recipient,
+      "invariant: outgoing sat is sent to recipient"
+    );
+  }
+
+  Ok(Batch {
+    transaction: Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: inputs
+        .into_iter()
+        .map(|outpoint| TxIn {
+          previous_output: outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: outputs
+        .into_iter()
+        .map(|(address, amount)| TxOut {
+          value: amount.to_sat(),
+          script_pubkey: address.script_pubkey(),
+        })
+        .collect(),
+    },
+    recipients,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn build(
+    outgoing: Vec<(SatPoint, Address)>,
+    inscriptions: BTreeMap<SatPoint, InscriptionId>,
+    amounts: BTreeMap<OutPoint, Amount>,
+  ) -> Result<Batch> {
+    build_transaction(
+      outgoing,
+      inscriptions,
+      amounts,
+      [change(0), change(1)],
+      FeeRate::try_from(1.0).unwrap(),
+      None,
+      TransactionBuilder::DEFAULT_TARGET_POSTAGE,
+      TransactionBuilder::DEFAULT_MAX_POSTAGE,
+    )
+  }
+
+  #[test]
+  fn parse_batch_file() {
+    assert_eq!(
+      parse(
+        &format!(
+          "# airdrop\n\n{}i0 {}\n{} {}\n",
+          txid(1),
+          recipient(),
+          satpoint(2, 0),
+          recipient(),
+        ),
+        Network::Testnet,
+      )
+      .unwrap(),
+      [
+        (
+          Outgoing::InscriptionId(format!("{}i0", txid(1)).parse().unwrap()),
+          recipient()
+        ),
+        (Outgoing::SatPoint(satpoint(2, 0)), recipient()),
+      ],
+    );
+  }
+
+  #[test]
+  fn parse_errors_report_line_number() {
+    assert_eq!(
+      parse(&format!("{}i0\n", txid(1)), Network::Testnet)
+        .unwrap_err()
+        .to_string(),
+      "line 1: expected `<INSCRIPTION_ID|SATPOINT> <ADDRESS>`",
+    );
+
+    assert_eq!(
+      parse(
+        &format!("{}i0 {}\n1000sat {}\n", txid(1), recipient(), recipient()),
+        Network::Testnet
+      )
+      .unwrap_err()
+      .to_string(),
+      "line 2: only inscription IDs and satpoints can be sent in a batch",
+    );
+
+    assert_eq!(
+      parse(
+        &format!("{}i0 {}\n", txid(1), recipient()),
+        Network::Bitcoin
+      )
+      .unwrap_err()
+      .to_string(),
+      format!("line 1: address `{}` is not valid for bitcoin", recipient()),
+    );
+
+    assert_eq!(
+      parse("# nothing\n", Network::Testnet)
+        .unwrap_err()
+        .to_string(),
+      "batch file contains no entries",
+    );
+  }
+
+  #[test]
+  fn one_postage_output_per_recipient() {
+    let batch = build(
+      vec![(satpoint(1, 0), recipient()), (satpoint(2, 0), recipient())],
+      BTreeMap::new(),
+      [
+        (outpoint(1), Amount::from_sat(10_000)),
+        (outpoint(2), Amount::from_sat(10_000)),
+        (outpoint(3), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+    )
+    .unwrap();
+
+    assert_eq!(batch.recipients, [0, 1]);
+
+    assert_eq!(
+      batch
+        .transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(1), outpoint(2), outpoint(3)],
+    );
+
+    let fee = FeeRate::try_from(1.0)
+      .unwrap()
+      .fee(TransactionBuilder::estimate_weight_with(
+        3,
+        vec![recipient(), recipient(), change(1)],
+      ));
+
+    assert_eq!(
+      batch
+        .transaction
+        .output
+        .iter()
+        .map(|output| (output.script_pubkey.clone(), output.value))
+        .collect::<Vec<(ScriptBuf, u64)>>(),
+      [
+        (recipient().script_pubkey(), 10_000),
+        (recipient().script_pubkey(), 10_000),
+        (change(1).script_pubkey(), 20_000 - fee.to_sat()),
+      ],
+    );
+  }
+
+  #[test]
+  fn excess_postage_is_stripped() {
+    let batch = build(
+      vec![(satpoint(1, 0), recipient())],
+      BTreeMap::new(),
+      [
+        (outpoint(1), Amount::from_sat(50_000)),
+        (outpoint(2), Amount::from_sat(20_000)),
+      ]
+      .into_iter()
+      .collect(),
+    )
+    .unwrap();
+
+    assert_eq!(batch.transaction.output[0].value, 10_000);
+    assert_eq!(batch.transaction.output[1].value, 40_000);
+    assert_eq!(
+      batch.transaction.output[1].script_pubkey,
+      change(1).script_pubkey()
+    );
+  }
+
+  #[test]
+  fn outgoing_sats_are_aligned() {
+    let batch = build(
+      vec![
+        (satpoint(1, 1_000), recipient()),
+        (satpoint(2, 100), recipient()),
+      ],
+      BTreeMap::new(),
+      [
+        (outpoint(1), Amount::from_sat(11_000)),
+        (outpoint(2), Amount::from_sat(10_100)),
+        (outpoint(3), Amount::from_sat(20_000)),
+        (outpoint(4), Amount::from_sat(500)),
+      ]
+      .into_iter()
+      .collect(),
+    )
+    .unwrap();
+
+    assert_eq!(batch.recipients, [1, 3]);
+
+    assert_eq!(
+      batch
+        .transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(1), outpoint(4), outpoint(2), outpoint(3)],
+    );
+
+    assert_eq!(batch.transaction.output[0].value, 1_000);
+    assert_eq!(batch.transaction.output[1].value, 10_000);
+    assert_eq!(batch.transaction.output[2].value, 600);
+    assert_eq!(batch.transaction.output[3].value, 10_000);
+  }
+
+  #[test]
+  fn do_not_send_additional_inscriptions() {
+    let inscriptions = [
+      (satpoint(1, 0), inscription_id(1)),
+      (satpoint(1, 5_000), inscription_id(2)),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      build(
+        vec![(satpoint(1, 0), recipient())],
+        inscriptions,
+        [
+          (outpoint(1), Amount::from_sat(10_000)),
+          (outpoint(2), Amount::from_sat(20_000)),
+        ]
+        .into_iter()
+        .collect(),
+      )
+      .unwrap_err()
+      .downcast::<Error>()
+      .unwrap(),
+      Error::UtxoContainsAdditionalInscription {
+        outgoing_satpoint: satpoint(1, 0),
+        inscribed_satpoint: satpoint(1, 5_000),
+        inscription_id: inscription_id(2),
+      },
+    );
+  }
+
+  #[test]
+  fn recipient_outputs_must_not_be_dust() {
+    assert_eq!(
+      build(
+        vec![(satpoint(1, 0), recipient())],
+        BTreeMap::new(),
+        [
+          (outpoint(1), Amount::from_sat(100)),
+          (outpoint(2), Amount::from_sat(20_000)),
+        ]
+        .into_iter()
+        .collect(),
+      )
+      .unwrap_err()
+      .downcast::<Error>()
+      .unwrap(),
+      Error::Dust {
+        output_value: Amount::from_sat(100),
+        dust_value: Amount::from_sat(294),
+      },
+    );
+  }
+
+  #[test]
+  fn inscribed_outputs_are_not_used_to_pay_fee() {
+    assert_eq!(
+      build(
+        vec![(satpoint(1, 0), recipient())],
+        [(satpoint(2, 0), inscription_id(2))].into_iter().collect(),
+        [
+          (outpoint(1), Amount::from_sat(10_000)),
+          (outpoint(2), Amount::from_sat(20_000)),
+        ]
+        .into_iter()
+        .collect(),
+      )
+      .unwrap_err()
+      .downcast::<Error>()
+      .unwrap(),
+      Error::NotEnoughCardinalUtxos,
+    );
+  }
+
+  #[test]
+  fn one_satpoint_per_output() {
+    assert_eq!(
+      build(
+        vec![
+          (satpoint(1, 0), recipient()),
+          (satpoint(1, 5_000), recipient()),
+        ],
+        BTreeMap::new(),
+        [
+          (outpoint(1), Amount::from_sat(10_000)),
+          (outpoint(2), Amount::from_sat(20_000)),
+        ]
+        .into_iter()
+        .collect(),
+      )
+      .unwrap_err()
+      .to_string(),
+      format!(
+        "cannot send more than one satpoint from output {} in a batch",
+        outpoint(1)
+      ),
+    );
+  }
+}
@@ -0,0 +1,356 @@
+use {
+  super::*,
+  crate::wallet::Wallet,
+  bitcoin::{blockdata::locktime::absolute::LockTime, Witness},
+  std::collections::BTreeSet,
+};
+
+#[derive(Debug, Parser)]
+pub(crate) struct Split {
+  #[clap(long, help = "Create <COUNT> cardinal outputs.")]
+  count: usize,
+  #[clap(long, help = "Create cardinal outputs of <VALUE> each.")]
+  value: Amount,
+  #[clap(long, help = "Use fee rate of <FEE_RATE> sats/vB")]
+  fee_rate: FeeRate,
+  #[clap(
+    long,
+    help = "Use at most <MAX_INPUTS> inputs to build the transaction."
+  )]
+  max_inputs: Option<usize>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Output {
+  pub transaction: Txid,
+  pub outputs: Vec<OutPoint>,
+}
+
+impl Split {
+  pub(crate) fn run(self, options: Options) -> Result {
+    if self.count == 0 {
+      bail!("--count must be at least 1");
+    }
+
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let unspent_outputs = index.get_unspent_outputs(Wallet::load(&options)?)?;
+
+    let inscribed_utxos = index
+      .get_inscriptions(unspent_outputs.clone())?
+      .keys()
+      .map(|satpoint| satpoint.outpoint)
+      .collect::<BTreeSet<OutPoint>>();
+
+    let cardinal_utxos = unspent_outputs
+      .into_iter()
+      .filter(|(outpoint, _amount)| !inscribed_utxos.contains(outpoint))
+      .collect();
+
+    let mut destinations = Vec::new();
+    for _ in 0..self.count {
+      destinations.push(get_change_address(&client, &options)?);
+    }
+
+    let unsigned_transaction = Self::build_transaction(
+      cardinal_utxos,
+      destinations,
+      self.value,
+      get_change_address(&client, &options)?,
+      self.fee_rate,
+      self.max_inputs,
+    )?;
+
+    let signed_tx = client
+      .sign_raw_transaction_with_wallet(&unsigned_transaction, None, None)?
+      .hex;
+
+    let txid = client.send_raw_transaction(&signed_tx)?;
+
+    print_json(Output {
+      transaction: txid,
+      outputs: (0..self.count)
+        .map(|vout| OutPoint {
+          txid,
+          vout: vout.try_into().unwrap(),
+        })
+        .collect(),
+    })?;
+
+    Ok(())
+  }
+
+  /// Fund one output of `value` per destination from the largest cardinal
+  /// UTXOs, returning anything above dust to `change`.
+  fn build_transaction(
+    cardinal_utxos: BTreeMap<OutPoint, Amount>,
+    destinations: Vec<Address>,
+    value: Amount,
+    change: Address,
+    fee_rate: FeeRate,
+    max_inputs: Option<usize>,
+  ) -> Result<Transaction> {
+    for destination in &destinations {
+      let dust_value = destination.script_pubkey().dust_value();
+
+      if value < dust_value {
+        return Err(
+          transaction_builder::Error::Dust {
+            output_value: value,
+            dust_value,
+          }
+          .into(),
+        );
+      }
+    }
+
+    let target = value
+      .checked_mul(destinations.len().try_into().unwrap())
+      .ok_or(transaction_builder::Error::ValueOverflow)?;
+
+    let mut utxos = cardinal_utxos
+      .into_iter()
+      .collect::<Vec<(OutPoint, Amount)>>();
+
+    utxos.sort_by_key(|(_outpoint, amount)| cmp::Reverse(*amount));
+
+    let mut outputs = destinations
+      .into_iter()
+      .map(|destination| (destination, value))
+      .collect::<Vec<(Address, Amount)>>();
+
+    let mut inputs = Vec::new();
+    let mut total = Amount::ZERO;
+
+    for (outpoint, amount) in utxos {
+      if max_inputs == Some(inputs.len()) {
+        return Err(transaction_builder::Error::TooManyInputs(inputs.len()).into());
+      }
+
+      inputs.push(outpoint);
+      total += amount;
+
+      let addresses = outputs
+        .iter()
+        .map(|(address, _value)| address.clone())
+        .collect::<Vec<Address>>();
+
+      let fee_with_change = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+        inputs.len(),
+        addresses
+          .iter()
+          .cloned()
+          .chain(std::iter::once(change.clone()))
+          .collect(),
+      ));
+
+      if let Some(change_value) = total.checked_sub(target + fee_with_change) {
+        if change_value >= change.script_pubkey().dust_value() {
+          outputs.push((change, change_value));
+          return Ok(Self::transaction(inputs, outputs));
+        }
+      }
+
+      let fee = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+        inputs.len(),
+        addresses,
+      ));
+
+      if total >= target + fee {
+        return Ok(Self::transaction(inputs, outputs));
+      }
+    }
+
+    Err(transaction_builder::Error::NotEnoughCardinalUtxos.into())
+  }
+
+  fn transaction(inputs: Vec<OutPoint>, outputs: Vec<(Address, Amount)>) -> Transaction {
+    Transaction {
+      version: 1,
+      lock_time: LockTime::ZERO,
+      input: inputs
+        .into_iter()
+        .map(|outpoint| TxIn {
+          previous_output: outpoint,
+          script_sig: ScriptBuf::new(),
+          sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+          witness: Witness::new(),
+        })
+        .collect(),
+      output: outputs
+        .into_iter()
+        .map(|(address, amount)| TxOut {
+          value: amount.to_sat(),
+          script_pubkey: address.script_pubkey(),
+        })
+        .collect(),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn split_into_outputs_with_change() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let transaction = Split::build_transaction(
+      [
+        (outpoint(1), Amount::from_sat(5_000)),
+        (outpoint(2), Amount::from_sat(100_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![recipient(), recipient(), recipient()],
+      Amount::from_sat(10_000),
+      change(0),
+      fee_rate,
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(transaction.input.len(), 1);
+    assert_eq!(transaction.input[0].previous_output, outpoint(2));
+
+    assert_eq!(transaction.output.len(), 4);
+
+    for output in &transaction.output[..3] {
+      assert_eq!(output.value, 10_000);
+      assert_eq!(output.script_pubkey, recipient().script_pubkey());
+    }
+
+    let fee = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+      1,
+      vec![recipient(), recipient(), recipient(), change(0)],
+    ));
+
+    assert_eq!(transaction.output[3].value, 70_000 - fee.to_sat());
+    assert_eq!(
+      transaction.output[3].script_pubkey,
+      change(0).script_pubkey()
+    );
+  }
+
+  #[test]
+  fn split_uses_multiple_inputs() {
+    let transaction = Split::build_transaction(
+      [
+        (outpoint(1), Amount::from_sat(15_000)),
+        (outpoint(2), Amount::from_sat(20_000)),
+        (outpoint(3), Amount::from_sat(1_000)),
+      ]
+      .into_iter()
+      .collect(),
+      vec![recipient(), recipient()],
+      Amount::from_sat(10_000),
+      change(0),
+      FeeRate::try_from(1.0).unwrap(),
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(
+      transaction
+        .input
+        .iter()
+        .map(|input| input.previous_output)
+        .collect::<Vec<OutPoint>>(),
+      [outpoint(2), outpoint(1)],
+    );
+  }
+
+  #[test]
+  fn split_without_change_if_change_would_be_dust() {
+    let fee_rate = FeeRate::try_from(1.0).unwrap();
+
+    let fee = fee_rate.fee(TransactionBuilder::estimate_weight_with(
+      1,
+      vec![recipient(), recipient()],
+    ));
+
+    let transaction = Split::build_transaction(
+      [(outpoint(1), Amount::from_sat(20_100) + fee)]
+        .into_iter()
+        .collect(),
+      vec![recipient(), recipient()],
+      Amount::from_sat(10_000),
+      change(0),
+      fee_rate,
+      None,
+    )
+    .unwrap();
+
+    assert_eq!(transaction.output.len(), 2);
+  }
+
+  #[test]
+  fn split_fails_without_enough_cardinals() {
+    assert_eq!(
+      Split::build_transaction(
+        [(outpoint(1), Amount::from_sat(20_000))]
+          .into_iter()
+          .collect(),
+        vec![recipient(), recipient()],
+        Amount::from_sat(10_000),
+        change(0),
+        FeeRate::try_from(1.0).unwrap(),
+        None,
+      )
+      .unwrap_err()
+      .downcast::<transaction_builder::Error>()
+      .unwrap(),
+      transaction_builder::Error::NotEnoughCardinalUtxos,
+    );
+  }
+
+  #[test]
+  fn split_respects_max_inputs() {
+    assert_eq!(
+      Split::build_transaction(
+        [
+          (outpoint(1), Amount::from_sat(15_000)),
+          (outpoint(2), Amount::from_sat(15_000)),
+        ]
+        .into_iter()
+        .collect(),
+        vec![recipient(), recipient()],
+        Amount::from_sat(10_000),
+        change(0),
+        FeeRate::try_from(1.0).unwrap(),
+        Some(1),
+      )
+      .unwrap_err()
+      .downcast::<transaction_builder::Error>()
+      .unwrap(),
+      transaction_builder::Error::TooManyInputs(1),
+    );
+  }
+
+  #[test]
+  fn split_outputs_must_not_be_dust() {
+    assert_eq!(
+      Split::build_transaction(
+        [(outpoint(1), Amount::from_sat(15_000))]
+          .into_iter()
+          .collect(),
+        vec![recipient()],
+        Amount::from_sat(100),
+        change(0),
+        FeeRate::try_from(1.0).unwrap(),
+        None,
+      )
+      .unwrap_err()
+      .downcast::<transaction_builder::Error>()
+      .unwrap(),
+      transaction_builder::Error::Dust {
+        output_value: Amount::from_sat(100),
+        dust_value: Amount::from_sat(294),
+      },
+    );
+  }
+}
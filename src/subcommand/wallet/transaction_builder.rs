@@ -130,7 +130,7 @@ type Result<T> = std::result::Result<T, Error>;
 
 impl TransactionBuilder {
   #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
-  const ADDITIONAL_INPUT_WEIGHT: Weight = Weight::from_wu((57.5 * 4.0) as u64);
+  pub(crate) const ADDITIONAL_INPUT_WEIGHT: Weight = Weight::from_wu((57.5 * 4.0) as u64);
   const ADDITIONAL_OUTPUT_WEIGHT: Weight = Weight::from_wu(43 * 4);
   const SCHNORR_SIGNATURE_SIZE: usize = 64;
   pub(crate) const DEFAULT_MAX_POSTAGE: Amount = Amount::from_sat(2 * 10_000);
@@ -148,12 +148,7 @@ impl TransactionBuilder {
     target_postage: Amount,
     max_postage: Amount,
   ) -> Result<Transaction> {
-    if max_postage < target_postage {
-      return Err(Error::MaxPostageLessThanTarget {
-        max_postage,
-        target_postage,
-      });
-    }
+    Self::check_postage(target_postage, max_postage)?;
 
     Self::new(
       outgoing,
@@ -183,14 +178,7 @@ impl TransactionBuilder {
     max_inputs: Option<usize>,
     output_value: Amount,
   ) -> Result<Transaction> {
-    let dust_value = recipient.script_pubkey().dust_value();
-
-    if output_value < dust_value {
-      return Err(Error::Dust {
-        output_value,
-        dust_value,
-      });
-    }
+    Self::check_dust(&recipient, output_value)?;
 
     Self::new(
       outgoing,
@@ -222,14 +210,7 @@ impl TransactionBuilder {
     ignore_utxo_inscriptions: bool,
   ) -> Result<Transaction> {
     for (recipient, output_value) in recipient.iter().zip(output_value.clone()) {
-      let dust_value = recipient.script_pubkey().dust_value();
-
-      if output_value < dust_value {
-        return Err(Error::Dust {
-          output_value,
-          dust_value,
-        });
-      }
+      Self::check_dust(recipient, output_value)?;
     }
 
     Self::new(
@@ -252,6 +233,114 @@ impl TransactionBuilder {
     .build_transaction()
   }
 
+  pub(crate) fn check_postage(target_postage: Amount, max_postage: Amount) -> Result<()> {
+    if max_postage < target_postage {
+      return Err(Error::MaxPostageLessThanTarget {
+        max_postage,
+        target_postage,
+      });
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn check_dust(recipient: &Address, output_value: Amount) -> Result<()> {
+    let dust_value = recipient.script_pubkey().dust_value();
+
+    if output_value < dust_value {
+      return Err(Error::Dust {
+        output_value,
+        dust_value,
+      });
+    }
+
+    Ok(())
+  }
+
+  /// Checks that sending `outgoing` doesn't also send another inscription in
+  /// its output, either because the inscription follows it or because it
+  /// can't be split off into an alignment output above `dust_limit`.
+  pub(crate) fn check_additional_inscriptions(
+    outgoing: SatPoint,
+    inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+    dust_limit: Amount,
+  ) -> Result<()> {
+    for (inscribed_satpoint, inscription_id) in inscriptions.iter().rev() {
+      if outgoing.outpoint == inscribed_satpoint.outpoint
+        && outgoing.offset != inscribed_satpoint.offset
+        && (outgoing.offset < inscribed_satpoint.offset
+          || (outgoing.offset > 0 && outgoing.offset < dust_limit.to_sat()))
+      {
+        return Err(Error::UtxoContainsAdditionalInscription {
+          outgoing_satpoint: outgoing,
+          inscribed_satpoint: *inscribed_satpoint,
+          inscription_id: *inscription_id,
+        });
+      }
+    }
+
+    Ok(())
+  }
+
+  /// The value of the wallet output containing `outgoing`.
+  pub(crate) fn outgoing_value(
+    outgoing: SatPoint,
+    amounts: &BTreeMap<OutPoint, Amount>,
+  ) -> Result<Amount> {
+    let amount = *amounts
+      .get(&outgoing.outpoint)
+      .ok_or(Error::NotInWallet(outgoing))?;
+
+    if outgoing.offset >= amount.to_sat() {
+      return Err(Error::OutOfRange(outgoing, amount.to_sat() - 1));
+    }
+
+    Ok(amount)
+  }
+
+  /// Adds cardinal inputs chosen by `select` until an alignment output of
+  /// `value` is above `dust_limit`, returning the inputs in the order they
+  /// precede the outgoing input, and the padded value.
+  pub(crate) fn pad_alignment(
+    mut value: Amount,
+    dust_limit: Amount,
+    mut select: impl FnMut(Amount) -> Result<(OutPoint, Amount)>,
+  ) -> Result<(Vec<OutPoint>, Amount)> {
+    let mut inputs = Vec::new();
+
+    while value < dust_limit {
+      let (utxo, size) = select(dust_limit - value)?;
+
+      inputs.insert(0, utxo);
+      value += size;
+
+      tprintln!("padded alignment output to {value} with additional {size} sat input");
+    }
+
+    Ok((inputs, value))
+  }
+
+  /// The amount to move from outputs worth `value` into a change output,
+  /// leaving `target`, if more than `max` remains after paying `fee` and the
+  /// change output is worth more than `dust_limit` after paying `output_fee`
+  /// for itself.
+  pub(crate) fn excess_postage(
+    value: Amount,
+    fee: Amount,
+    output_fee: Amount,
+    target: Amount,
+    max: Amount,
+    dust_limit: Amount,
+  ) -> Option<Amount> {
+    let excess = value.checked_sub(fee)?;
+
+    if excess > max && value.checked_sub(target).unwrap() > dust_limit + output_fee {
+      Some(value - target)
+    } else {
+      None
+    }
+  }
+
   fn build_transaction(self) -> Result<Transaction> {
     self
       .select_outgoing()?
@@ -321,32 +410,13 @@ impl TransactionBuilder {
       .last()
       .unwrap()
       .script_pubkey()
-      .dust_value()
-      .to_sat();
+      .dust_value();
+
     if !self.ignore_utxo_inscriptions {
-      for (inscribed_satpoint, inscription_id) in self.inscriptions.iter().rev() {
-        if self.outgoing.outpoint == inscribed_satpoint.outpoint
-          && self.outgoing.offset != inscribed_satpoint.offset
-          && (self.outgoing.offset < inscribed_satpoint.offset
-            || (self.outgoing.offset > 0 && self.outgoing.offset < dust_limit))
-        {
-          return Err(Error::UtxoContainsAdditionalInscription {
-            outgoing_satpoint: self.outgoing,
-            inscribed_satpoint: *inscribed_satpoint,
-            inscription_id: *inscription_id,
-          });
-        }
-      }
+      Self::check_additional_inscriptions(self.outgoing, &self.inscriptions, dust_limit)?;
     }
 
-    let amount = *self
-      .amounts
-      .get(&self.outgoing.outpoint)
-      .ok_or(Error::NotInWallet(self.outgoing))?;
-
-    if self.outgoing.offset >= amount.to_sat() {
-      return Err(Error::OutOfRange(self.outgoing, amount.to_sat() - 1));
-    }
+    let amount = Self::outgoing_value(self.outgoing, &self.amounts)?;
 
     self.utxos.remove(&self.outgoing.outpoint);
     self.inputs.push(self.outgoing.outpoint);
@@ -439,17 +509,12 @@ impl TransactionBuilder {
       if self.outputs[0].1 >= dust_limit {
         tprintln!("no padding needed");
       } else {
-        while self.outputs[0].1 < dust_limit {
-          let (utxo, size) = self.select_cardinal_utxo(dust_limit - self.outputs[0].1, true)?;
-
-          self.inputs.insert(0, utxo);
-          self.outputs[0].1 += size;
+        let (inputs, value) = Self::pad_alignment(self.outputs[0].1, dust_limit, |target| {
+          self.select_cardinal_utxo(target, true)
+        })?;
 
-          tprintln!(
-            "padded alignment output to {} with additional {size} sat input",
-            self.outputs[0].1
-          );
-        }
+        self.inputs.splice(0..0, inputs);
+        self.outputs[0].1 = value;
       }
     }
 
@@ -555,35 +620,37 @@ impl TransactionBuilder {
     }
 
     let value = total_output_amount - Amount::from_sat(sat_offset);
-    if let Some(excess) = value.checked_sub(self.fee_rate.fee(self.estimate_weight())) {
-      let (max, target) = self
-        .target
-        .iter()
-        .map(|target| match target {
-          Target::Postage => (self.max_postage, self.target_postage),
-          Target::Value(value) => (*value, *value),
-        })
-        .reduce(|(a, b), (c, d)| (a + c, b + d))
-        .unwrap();
-
-      if excess > max
-        && value.checked_sub(target).unwrap()
-          > self
-            .unused_change_addresses
-            .last()
-            .unwrap()
-            .script_pubkey()
-            .dust_value()
-            + self
-              .fee_rate
-              .fee(self.estimate_weight() + Self::ADDITIONAL_OUTPUT_WEIGHT)
-      {
-        tprintln!("stripped {} sats", (value - target).to_sat());
-        self.outputs.last_mut().expect("no outputs found").1 -= value - target;
-        self
-          .outputs
-          .push((self.unused_change_addresses[1].clone(), value - target));
-      }
+
+    let (max, target) = self
+      .target
+      .iter()
+      .map(|target| match target {
+        Target::Postage => (self.max_postage, self.target_postage),
+        Target::Value(value) => (*value, *value),
+      })
+      .reduce(|(a, b), (c, d)| (a + c, b + d))
+      .unwrap();
+
+    if let Some(excess) = Self::excess_postage(
+      value,
+      self.fee_rate.fee(self.estimate_weight()),
+      self
+        .fee_rate
+        .fee(self.estimate_weight() + Self::ADDITIONAL_OUTPUT_WEIGHT),
+      target,
+      max,
+      self
+        .unused_change_addresses
+        .last()
+        .unwrap()
+        .script_pubkey()
+        .dust_value(),
+    ) {
+      tprintln!("stripped {} sats", excess.to_sat());
+      self.outputs.last_mut().expect("no outputs found").1 -= excess;
+      self
+        .outputs
+        .push((self.unused_change_addresses[1].clone(), excess));
     }
 
     self
@@ -638,7 +705,7 @@ impl TransactionBuilder {
     )
   }
 
-  fn estimate_weight_with(inputs: usize, outputs: Vec<Address>) -> Weight {
+  pub(crate) fn estimate_weight_with(inputs: usize, outputs: Vec<Address>) -> Weight {
     let t = Transaction {
       version: 1,
       lock_time: LockTime::ZERO,
@@ -886,24 +953,41 @@ impl TransactionBuilder {
       return Err(Error::TooManyInputs(self.max_inputs.unwrap()));
     }
 
+    Self::select_cardinal(
+      &mut self.utxos,
+      &self.amounts,
+      &self.inscriptions,
+      target_value,
+      prefer_under,
+    )
+  }
+
+  /// Removes and returns the cardinal UTXO in `utxos` whose value is closest
+  /// to `target_value`, preferring ones under or over it.
+  pub(crate) fn select_cardinal(
+    utxos: &mut BTreeSet<OutPoint>,
+    amounts: &BTreeMap<OutPoint, Amount>,
+    inscriptions: &BTreeMap<SatPoint, InscriptionId>,
+    target_value: Amount,
+    prefer_under: bool,
+  ) -> Result<(OutPoint, Amount)> {
     tprintln!(
       "looking for {} cardinal worth {target_value}",
       if prefer_under { "smaller" } else { "bigger" }
     );
 
-    let inscribed_utxos = self
-      .inscriptions
+    let inscribed_utxos = inscriptions
       .keys()
       .map(|satpoint| satpoint.outpoint)
       .collect::<BTreeSet<OutPoint>>();
 
     let mut best_match = None;
-    for utxo in &self.utxos {
+    for utxo in utxos.iter() {
       if inscribed_utxos.contains(utxo) {
         continue;
       }
 
-      let current_value = self.amounts[utxo];
+      let current_value = amounts[utxo];
 
       let (_, best_value) = match best_match {
         Some(prev) => prev,
@@ -935,7 +1019,7 @@ impl TransactionBuilder {
 
     let (utxo, value) = best_match.ok_or(Error::NotEnoughCardinalUtxos)?;
 
-    self.utxos.remove(&utxo);
+    utxos.remove(&utxo);
     tprintln!("found cardinal worth {}", value);
 
     Ok((utxo, value))
@@ -1,37 +1,591 @@
-use super::*;
+use {
+  super::*,
+  crate::wallet::Wallet,
+  bitcoincore_rpc::json::{GetTransactionResultDetail, GetTransactionResultDetailCategory},
+  std::collections::{BTreeSet, VecDeque},
+};
 
 #[derive(Debug, Parser)]
 pub(crate) struct Transactions {
   #[clap(long, help = "Fetch at most <LIMIT> transactions.")]
   limit: Option<u16>,
+  #[clap(
+    long,
+    conflicts_with = "csv",
+    help = "Print transactions as JSON. [default]"
+  )]
+  json: bool,
+  #[clap(long, help = "Print transactions as CSV.")]
+  csv: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct Output {
   pub transaction: Txid,
   pub confirmations: i32,
+  pub fee: Option<u64>,
+  pub counterparty: Option<Address<NetworkUnchecked>>,
+  pub inscriptions_received: Vec<InscriptionId>,
+  pub inscriptions_sent: Vec<InscriptionId>,
+  pub rare_sats_received: Vec<Sat>,
+  pub rare_sats_sent: Vec<Sat>,
+}
+
+impl Output {
+  const CSV_HEADER: &str = "transaction,confirmations,fee,counterparty,inscriptions_received,inscriptions_sent,rare_sats_received,rare_sats_sent";
+
+  fn csv_row(&self) -> String {
+    fn join<T: Display>(items: &[T]) -> String {
+      items
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<String>>()
+        .join(" ")
+    }
+
+    format!(
+      "{},{},{},{},{},{},{},{}",
+      self.transaction,
+      self.confirmations,
+      self.fee.map(|fee| fee.to_string()).unwrap_or_default(),
+      self
+        .counterparty
+        .as_ref()
+        .map(|address| address.clone().assume_checked().to_string())
+        .unwrap_or_default(),
+      join(&self.inscriptions_received),
+      join(&self.inscriptions_sent),
+      join(&self.rare_sats_received),
+      join(&self.rare_sats_sent),
+    )
+  }
+}
+
+/// Inscriptions and sat ranges known to be in an output, ranges only being
+/// known if the index has a sat index.
+#[derive(Clone, Default)]
+struct Contents {
+  inscriptions: Vec<(u64, InscriptionId)>,
+  ranges: Option<Vec<(u64, u64)>>,
 }
 
 impl Transactions {
   pub(crate) fn run(self, options: Options) -> Result {
-    let mut output = Vec::new();
-    for tx in options
-      .bitcoin_rpc_client_for_wallet_command(false)?
-      .list_transactions(
-        None,
-        Some(self.limit.unwrap_or(u16::MAX).into()),
-        None,
-        None,
-      )?
-    {
-      output.push(Output {
-        transaction: tx.info.txid,
-        confirmations: tx.info.confirmations,
-      });
+    let index = Index::open(&options)?;
+    index.update()?;
+
+    let client = options.bitcoin_rpc_client_for_wallet_command(false)?;
+
+    let mut order = Vec::new();
+    let mut entries = BTreeMap::<Txid, (i32, Vec<GetTransactionResultDetail>)>::new();
+
+    for entry in client.list_transactions(
+      None,
+      Some(self.limit.unwrap_or(u16::MAX).into()),
+      None,
+      None,
+    )? {
+      let txid = entry.info.txid;
+
+      entries
+        .entry(txid)
+        .or_insert_with(|| {
+          order.push(txid);
+          (entry.info.confirmations, Vec::new())
+        })
+        .1
+        .push(entry.detail);
+    }
+
+    let mut transactions = BTreeMap::new();
+    for txid in &order {
+      transactions.insert(
+        *txid,
+        index
+          .get_transaction(*txid)?
+          .ok_or_else(|| anyhow!("transaction {txid} not found"))?,
+      );
     }
 
-    print_json(output)?;
+    let mut annotator = Annotator {
+      chain: options.chain(),
+      client: &client,
+      contents: BTreeMap::new(),
+      has_sat_index: index.has_sat_index()?,
+      index: &index,
+      transactions: &transactions,
+      wallet_outputs: index
+        .get_unspent_outputs(Wallet::load(&options)?)?
+        .into_keys()
+        .collect(),
+    };
+
+    let mut output = BTreeMap::new();
+    for txid in topological_order(&order, &transactions) {
+      let (confirmations, details) = &entries[&txid];
+      output.insert(txid, annotator.annotate(txid, *confirmations, details)?);
+    }
+
+    let output = order
+      .iter()
+      .map(|txid| output.remove(txid).unwrap())
+      .collect::<Vec<Output>>();
+
+    if self.csv {
+      println!("{}", Output::CSV_HEADER);
+      for transaction in &output {
+        println!("{}", transaction.csv_row());
+      }
+    } else {
+      print_json(output)?;
+    }
 
     Ok(())
   }
 }
+
+/// Order transactions so that parents are annotated before their children.
+fn topological_order(order: &[Txid], transactions: &BTreeMap<Txid, Transaction>) -> Vec<Txid> {
+  let mut children = BTreeMap::<Txid, Vec<Txid>>::new();
+  let mut parents = BTreeMap::<Txid, usize>::new();
+
+  for txid in order {
+    let listed_parents = transactions[txid]
+      .input
+      .iter()
+      .map(|input| input.previous_output.txid)
+      .filter(|parent| parent != txid && transactions.contains_key(parent))
+      .collect::<BTreeSet<Txid>>();
+
+    for parent in &listed_parents {
+      children.entry(*parent).or_default().push(*txid);
+    }
+
+    parents.insert(*txid, listed_parents.len());
+  }
+
+  let mut queue = order
+    .iter()
+    .filter(|txid| parents[*txid] == 0)
+    .copied()
+    .collect::<VecDeque<Txid>>();
+
+  let mut sorted = Vec::new();
+
+  while let Some(txid) = queue.pop_front() {
+    sorted.push(txid);
+
+    for child in children.remove(&txid).unwrap_or_default() {
+      let count = parents.get_mut(&child).unwrap();
+      *count -= 1;
+      if *count == 0 {
+        queue.push_back(child);
+      }
+    }
+  }
+
+  sorted
+}
+
+struct Annotator<'a> {
+  chain: Chain,
+  client: &'a Client,
+  contents: BTreeMap<OutPoint, Contents>,
+  has_sat_index: bool,
+  index: &'a Index,
+  transactions: &'a BTreeMap<Txid, Transaction>,
+  wallet_outputs: BTreeSet<OutPoint>,
+}
+
+impl<'a> Annotator<'a> {
+  fn annotate(
+    &mut self,
+    txid: Txid,
+    confirmations: i32,
+    details: &[GetTransactionResultDetail],
+  ) -> Result<Output> {
+    let tx = &self.transactions[&txid];
+
+    let sends = details
+      .iter()
+      .filter(|detail| detail.category == GetTransactionResultDetailCategory::Send)
+      .collect::<Vec<&GetTransactionResultDetail>>();
+
+    let received = details
+      .iter()
+      .filter(|detail| detail.category != GetTransactionResultDetailCategory::Send)
+      .map(|detail| detail.vout)
+      .collect::<BTreeSet<u32>>();
+
+    let sent = sends
+      .iter()
+      .map(|detail| detail.vout)
+      .filter(|vout| !received.contains(vout))
+      .collect::<BTreeSet<u32>>();
+
+    let is_wallet_output = |vout: u32| {
+      if sends.is_empty() {
+        received.contains(&vout)
+      } else {
+        !sent.contains(&vout)
+      }
+    };
+
+    let mut inscriptions = Vec::new();
+    let mut ranges = Some(Vec::new());
+    let mut input_offsets = Vec::new();
+    let mut wallet_inscriptions = HashSet::new();
+    let mut wallet_sats = BTreeSet::new();
+    let mut spends_wallet_outputs = false;
+    let mut offset = 0;
+
+    if !tx.is_coin_base() {
+      for input in &tx.input {
+        let outpoint = input.previous_output;
+        let contents = self.contents(outpoint)?;
+        let value = self.value(outpoint)?;
+        let from_wallet = self.is_wallet_output(outpoint)?;
+
+        spends_wallet_outputs |= from_wallet;
+
+        for (inscription_offset, inscription_id) in contents.inscriptions {
+          if from_wallet {
+            wallet_inscriptions.insert(inscription_id);
+          }
+          inscriptions.push((offset + inscription_offset, inscription_id));
+        }
+
+        match (&mut ranges, contents.ranges) {
+          (Some(ranges), Some(input_ranges)) => {
+            if from_wallet {
+              wallet_sats.extend(rare_sats(&input_ranges));
+            }
+            ranges.extend(input_ranges);
+          }
+          _ => ranges = None,
+        }
+
+        input_offsets.push(offset);
+        offset += value;
+      }
+    }
+
+    for (i, inscription) in Inscription::from_transaction(tx).into_iter().enumerate() {
+      let inscription_id = InscriptionId {
+        txid,
+        index: i.try_into().unwrap(),
+      };
+
+      if spends_wallet_outputs || !sends.is_empty() {
+        wallet_inscriptions.insert(inscription_id);
+      }
+
+      inscriptions.push((
+        input_offsets[usize::try_from(inscription.tx_in_index).unwrap()],
+        inscription_id,
+      ));
+    }
+
+    let mut output = Output {
+      transaction: txid,
+      confirmations,
+      fee: sends
+        .first()
+        .and_then(|detail| detail.fee)
+        .map(|fee| fee.to_sat().unsigned_abs()),
+      counterparty: match sends.first() {
+        Some(detail) => detail.address.clone(),
+        None => self.sender(tx)?,
+      },
+      inscriptions_received: Vec::new(),
+      inscriptions_sent: Vec::new(),
+      rare_sats_received: Vec::new(),
+      rare_sats_sent: Vec::new(),
+    };
+
+    let mut ranges = ranges.map(VecDeque::from);
+    let mut start = 0;
+
+    for (vout, tx_out) in tx.output.iter().enumerate() {
+      let end = start + tx_out.value;
+      let vout = u32::try_from(vout).unwrap();
+      let outpoint = OutPoint { txid, vout };
+
+      let mut contents = Contents {
+        inscriptions: inscriptions
+          .iter()
+          .filter(|(offset, _id)| (start..end).contains(offset))
+          .map(|(offset, id)| (offset - start, *id))
+          .collect(),
+        ranges: ranges
+          .as_mut()
+          .map(|ranges| take_ranges(ranges, tx_out.value)),
+      };
+
+      let indexed = self.indexed_contents(outpoint)?;
+
+      for inscription in indexed.inscriptions {
+        if !contents.inscriptions.contains(&inscription) {
+          contents.inscriptions.push(inscription);
+        }
+      }
+
+      if indexed.ranges.is_some() {
+        contents.ranges = indexed.ranges;
+      }
+
+      let sats = contents
+        .ranges
+        .as_deref()
+        .map(rare_sats)
+        .unwrap_or_default();
+
+      if is_wallet_output(vout) {
+        self.wallet_outputs.insert(outpoint);
+
+        for (_offset, inscription_id) in &contents.inscriptions {
+          if !wallet_inscriptions.contains(inscription_id) {
+            output.inscriptions_received.push(*inscription_id);
+          }
+        }
+
+        for sat in sats {
+          if !wallet_sats.contains(&sat) {
+            output.rare_sats_received.push(sat);
+          }
+        }
+      } else {
+        for (_offset, inscription_id) in &contents.inscriptions {
+          if wallet_inscriptions.contains(inscription_id) {
+            output.inscriptions_sent.push(*inscription_id);
+          }
+        }
+
+        for sat in sats {
+          if wallet_sats.contains(&sat) {
+            output.rare_sats_sent.push(sat);
+          }
+        }
+      }
+
+      self.contents.insert(outpoint, contents);
+
+      start = end;
+    }
+
+    Ok(output)
+  }
+
+  /// Contents of an output, either traced through an earlier wallet
+  /// transaction or looked up in the index.
+  fn contents(&self, outpoint: OutPoint) -> Result<Contents> {
+    match self.contents.get(&outpoint) {
+      Some(contents) => Ok(contents.clone()),
+      None => self.indexed_contents(outpoint),
+    }
+  }
+
+  fn indexed_contents(&self, outpoint: OutPoint) -> Result<Contents> {
+    Ok(Contents {
+      inscriptions: self
+        .index
+        .get_inscriptions_on_output_with_satpoints(outpoint)?
+        .into_iter()
+        .map(|(satpoint, inscription_id)| (satpoint.offset, inscription_id))
+        .collect(),
+      ranges: if self.has_sat_index {
+        match self.index.list(outpoint)? {
+          Some(List::Unspent(ranges)) => Some(ranges),
+          _ => None,
+        }
+      } else {
+        None
+      },
+    })
+  }
+
+  /// Whether `outpoint` belongs to the wallet. Outputs of listed transactions
+  /// are known from annotating them, and unspent outputs from the wallet's
+  /// UTXOs, but outputs of unlisted transactions, for example those older
+  /// than `--limit`, must be looked up by address.
+  fn is_wallet_output(&mut self, outpoint: OutPoint) -> Result<bool> {
+    if self.wallet_outputs.contains(&outpoint) {
+      return Ok(true);
+    }
+
+    if self.transactions.contains_key(&outpoint.txid) {
+      return Ok(false);
+    }
+
+    let is_mine = match self
+      .chain
+      .address_from_script(&self.previous_output(outpoint)?.script_pubkey)
+    {
+      Ok(address) => self
+        .client
+        .get_address_info(&address)?
+        .is_mine
+        .unwrap_or_default(),
+      Err(_) => false,
+    };
+
+    if is_mine {
+      self.wallet_outputs.insert(outpoint);
+    }
+
+    Ok(is_mine)
+  }
+
+  fn previous_output(&self, outpoint: OutPoint) -> Result<TxOut> {
+    let tx = match self.transactions.get(&outpoint.txid) {
+      Some(tx) => tx.clone(),
+      None => self.client.get_raw_transaction(&outpoint.txid, None)?,
+    };
+
+    Ok(tx.output[usize::try_from(outpoint.vout).unwrap()].clone())
+  }
+
+  fn value(&self, outpoint: OutPoint) -> Result<u64> {
+    Ok(self.previous_output(outpoint)?.value)
+  }
+
+  /// Address of the first input of a transaction the wallet didn't send.
+  fn sender(&self, tx: &Transaction) -> Result<Option<Address<NetworkUnchecked>>> {
+    if tx.is_coin_base() {
+      return Ok(None);
+    }
+
+    let script_pubkey = self
+      .previous_output(tx.input[0].previous_output)?
+      .script_pubkey;
+
+    Ok(
+      self
+        .chain
+        .address_from_script(&script_pubkey)
+        .ok()
+        .map(|address| Address::new(address.network, address.payload)),
+    )
+  }
+}
+
+/// Remove the first `value` sats from the front of `ranges`.
+fn take_ranges(ranges: &mut VecDeque<(u64, u64)>, value: u64) -> Vec<(u64, u64)> {
+  let mut taken = Vec::new();
+  let mut remaining = value;
+
+  while remaining > 0 {
+    let Some((start, end)) = ranges.pop_front() else {
+      break;
+    };
+
+    let size = end - start;
+
+    if size > remaining {
+      taken.push((start, start + remaining));
+      ranges.push_front((start + remaining, end));
+      break;
+    }
+
+    taken.push((start, end));
+    remaining -= size;
+  }
+
+  taken
+}
+
+fn rare_sats(ranges: &[(u64, u64)]) -> Vec<Sat> {
+  ranges
+    .iter()
+    .map(|(start, _end)| Sat(*start))
+    .filter(|sat| sat.rarity() > Rarity::Common)
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn take_ranges_splits_ranges() {
+    let mut ranges = VecDeque::from(vec![(0, 10), (20, 30)]);
+
+    assert_eq!(take_ranges(&mut ranges, 5), [(0, 5)]);
+    assert_eq!(take_ranges(&mut ranges, 10), [(5, 10), (20, 25)]);
+    assert_eq!(take_ranges(&mut ranges, 10), [(25, 30)]);
+    assert!(ranges.is_empty());
+  }
+
+  #[test]
+  fn rare_sats_are_found_at_range_starts() {
+    assert_eq!(
+      rare_sats(&[(0, 10), (5, 10), (50 * COIN_VALUE, 51 * COIN_VALUE)]),
+      [Sat(0), Sat(50 * COIN_VALUE)],
+    );
+  }
+
+  #[test]
+  fn parents_are_ordered_before_children() {
+    let parent = Transaction {
+      version: 1,
+      lock_time: bitcoin::blockdata::locktime::absolute::LockTime::ZERO,
+      input: vec![TxIn {
+        previous_output: outpoint(1),
+        script_sig: ScriptBuf::new(),
+        sequence: Sequence::MAX,
+        witness: bitcoin::Witness::new(),
+      }],
+      output: vec![TxOut {
+        value: 1000,
+        script_pubkey: ScriptBuf::new(),
+      }],
+    };
+
+    let child = Transaction {
+      input: vec![TxIn {
+        previous_output: OutPoint {
+          txid: parent.txid(),
+          vout: 0,
+        },
+        ..parent.input[0].clone()
+      }],
+      ..parent.clone()
+    };
+
+    let transactions = [
+      (parent.txid(), parent.clone()),
+      (child.txid(), child.clone()),
+    ]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+      topological_order(&[child.txid(), parent.txid()], &transactions),
+      [parent.txid(), child.txid()],
+    );
+  }
+
+  #[test]
+  fn csv_row() {
+    assert_eq!(
+      Output {
+        transaction: txid(1),
+        confirmations: 3,
+        fee: Some(200),
+        counterparty: Some(recipient().to_string().parse().unwrap()),
+        inscriptions_received: Vec::new(),
+        inscriptions_sent: vec![inscription_id(1), inscription_id(2)],
+        rare_sats_received: vec![Sat(0)],
+        rare_sats_sent: Vec::new(),
+      }
+      .csv_row(),
+      format!(
+        "{},3,200,{},,{} {},0,",
+        txid(1),
+        recipient(),
+        inscription_id(1),
+        inscription_id(2)
+      ),
+    );
+  }
+}
@@ -1,37 +1,49 @@
 use {super::*, boilerplate::Boilerplate};
 
 pub(crate) use {
+  address::{AddressHtml, AddressJson},
   block::BlockHtml,
   clock::ClockSvg,
+  content_hash::{ContentHashHtml, ContentHashJson},
+  envelope::{Envelope, ErrorJson},
   home::HomeHtml,
   iframe::Iframe,
   input::InputHtml,
   inscription::{InscriptionHtml, InscriptionJson},
+  inscription_query::{InscriptionQueryHtml, InscriptionQueryJson},
   inscriptions::{InscriptionsHtml, InscriptionsJson},
   output::{OutputHtml, OutputJson},
   page_config::PageConfig,
   preview::{
     PreviewAudioHtml, PreviewImageHtml, PreviewPdfHtml, PreviewTextHtml, PreviewUnknownHtml,
-    PreviewVideoHtml,
+    PreviewVideoHtml, ThumbnailSvg,
   },
   range::RangeHtml,
   rare::RareTxt,
   sat::{SatHtml, SatJson},
+  sat_history::{SatHistoryHtml, SatHistoryJson},
+  sats::{SatsHtml, SatsJson},
   transaction::TransactionHtml,
 };
 
+pub mod address;
 mod block;
 mod clock;
+pub mod content_hash;
+pub mod envelope;
 mod home;
 mod iframe;
 mod input;
 pub mod inscription;
+pub mod inscription_query;
 pub mod inscriptions;
 pub mod output;
 mod preview;
 mod range;
 mod rare;
 pub mod sat;
+pub mod sat_history;
+pub mod sats;
 mod transaction;
 
 #[derive(Boilerplate)]
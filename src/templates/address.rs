@@ -0,0 +1,103 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct AddressHtml {
+  pub(crate) address: Address,
+  pub(crate) outputs: Vec<(OutPoint, u64)>,
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) cardinal_balance: u64,
+  pub(crate) rare_sats: Option<Vec<(Sat, SatPoint)>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct AddressJson {
+  pub address: String,
+  pub outputs: Vec<OutPoint>,
+  pub inscriptions: Vec<InscriptionId>,
+  pub cardinal_balance: u64,
+  pub rare_sats: Option<Vec<(Sat, SatPoint)>>,
+}
+
+impl AddressJson {
+  pub(crate) fn new(html: AddressHtml) -> Self {
+    Self {
+      address: html.address.to_string(),
+      outputs: html
+        .outputs
+        .into_iter()
+        .map(|(outpoint, _value)| outpoint)
+        .collect(),
+      inscriptions: html.inscriptions,
+      cardinal_balance: html.cardinal_balance,
+      rare_sats: html.rare_sats,
+    }
+  }
+}
+
+impl PageContent for AddressHtml {
+  fn title(&self) -> String {
+    format!("Address {}", self.address)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn address() {
+    assert_regex_match!(
+      AddressHtml {
+        address: recipient(),
+        outputs: vec![(outpoint(1), 1000), (outpoint(2), 5000)],
+        inscriptions: vec![inscription_id(1)],
+        cardinal_balance: 5000,
+        rare_sats: None,
+      },
+      "
+        <h1>Address <span class=monospace>tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz</span></h1>
+        <dl>
+          <dt>inscriptions</dt>
+          <dd class=thumbnails>
+            <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          </dd>
+          <dt>cardinal balance</dt><dd>5000</dd>
+        </dl>
+        <h2>2 Outputs</h2>
+        <ul class=monospace>
+          <li><a href=/output/1{64}:1>1{64}:1</a> 1000</li>
+          <li><a href=/output/2{64}:2>2{64}:2</a> 5000</li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn address_with_rare_sats() {
+    assert_regex_match!(
+      AddressHtml {
+        address: recipient(),
+        outputs: vec![(outpoint(1), 1000)],
+        inscriptions: Vec::new(),
+        cardinal_balance: 1000,
+        rare_sats: Some(vec![(Sat(0), satpoint(1, 0))]),
+      },
+      "
+        <h1>Address <span class=monospace>tb1q6en7qjxgw4ev8xwx94pzdry6a6ky7wlfeqzunz</span></h1>
+        <dl>
+          <dt>cardinal balance</dt><dd>1000</dd>
+        </dl>
+        <h2>1 Output</h2>
+        <ul class=monospace>
+          <li><a href=/output/1{64}:1>1{64}:1</a> 1000</li>
+        </ul>
+        <h2>1 Rare Sat</h2>
+        <ul class=monospace>
+          <li><a href=/sat/0 class=mythic>0</a> at 1{64}:1:0</li>
+        </ul>
+      "
+      .unindent()
+    );
+  }
+}
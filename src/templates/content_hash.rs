@@ -0,0 +1,60 @@
+use {super::*, bitcoin::hashes::sha256};
+
+#[derive(Boilerplate)]
+pub(crate) struct ContentHashHtml {
+  pub(crate) content_hash: sha256::Hash,
+  pub(crate) inscriptions: Vec<(InscriptionId, i64)>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ContentHashJson {
+  pub content_hash: String,
+  pub inscriptions: Vec<InscriptionId>,
+}
+
+impl ContentHashJson {
+  pub(crate) fn new(html: ContentHashHtml) -> Self {
+    Self {
+      content_hash: html.content_hash.to_string(),
+      inscriptions: html
+        .inscriptions
+        .into_iter()
+        .map(|(inscription_id, _number)| inscription_id)
+        .collect(),
+    }
+  }
+}
+
+impl PageContent for ContentHashHtml {
+  fn title(&self) -> String {
+    format!("Content {}", self.content_hash)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn content_hash() {
+    assert_regex_match!(
+      ContentHashHtml {
+        content_hash: sha256::Hash::hash(b"hello"),
+        inscriptions: vec![(inscription_id(1), 0), (inscription_id(2), -1)],
+      },
+      "
+        <h1>Content <span class=monospace>2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824</span></h1>
+        <h2>2 Inscriptions</h2>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <ol>
+          <li><a href=/inscription/1{64}i1>#0</a> first inscribed</li>
+          <li><a href=/inscription/2{64}i2>#-1</a></li>
+        </ol>
+      "
+      .unindent()
+    );
+  }
+}
@@ -0,0 +1,17 @@
+use super::*;
+
+/// Wrapper for every JSON API response. `next` is the path of the following
+/// page, if any, and `height` is the height of the index when the response
+/// was generated.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct Envelope<T> {
+  pub data: T,
+  pub next: Option<String>,
+  pub height: Option<u64>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct ErrorJson {
+  pub status: u16,
+  pub message: String,
+}
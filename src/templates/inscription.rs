@@ -1,8 +1,11 @@
-use super::*;
+use {super::*, bitcoin::hashes::sha256};
 
 #[derive(Boilerplate)]
 pub(crate) struct InscriptionHtml {
   pub(crate) chain: Chain,
+  pub(crate) content_hash: Option<sha256::Hash>,
+  pub(crate) duplicates: usize,
+  pub(crate) first_inscribed: Option<(InscriptionId, i64)>,
   pub(crate) genesis_fee: u64,
   pub(crate) genesis_height: u64,
   pub(crate) inscription: Inscription,
@@ -31,11 +34,17 @@ pub struct InscriptionJson {
   pub timestamp: i64,
   pub previous: Option<InscriptionId>,
   pub next: Option<InscriptionId>,
+  pub content_hash: Option<String>,
+  pub first_inscribed: Option<InscriptionId>,
+  pub duplicates: usize,
 }
 
 impl InscriptionJson {
   pub fn new(
     chain: Chain,
+    content_hash: Option<sha256::Hash>,
+    duplicates: usize,
+    first_inscribed: Option<(InscriptionId, i64)>,
     genesis_fee: u64,
     genesis_height: u64,
     inscription: Inscription,
@@ -65,6 +74,9 @@ impl InscriptionJson {
       timestamp: timestamp.timestamp(),
       previous,
       next,
+      content_hash: content_hash.map(|hash| hash.to_string()),
+      first_inscribed: first_inscribed.map(|(inscription_id, _number)| inscription_id),
+      duplicates,
     }
   }
 }
@@ -88,6 +100,9 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        content_hash: None,
+        duplicates: 0,
+        first_inscribed: None,
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -143,6 +158,9 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        content_hash: None,
+        duplicates: 0,
+        first_inscribed: None,
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -180,6 +198,9 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        content_hash: None,
+        duplicates: 0,
+        first_inscribed: None,
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -212,6 +233,9 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        content_hash: None,
+        duplicates: 0,
+        first_inscribed: None,
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -242,6 +266,9 @@ mod tests {
     assert_regex_match!(
       InscriptionHtml {
         chain: Chain::Mainnet,
+        content_hash: None,
+        duplicates: 0,
+        first_inscribed: None,
         genesis_fee: 1,
         genesis_height: 0,
         inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
@@ -272,4 +299,42 @@ mod tests {
       .unindent()
     );
   }
+
+  #[test]
+  fn with_duplicates() {
+    assert_regex_match!(
+      InscriptionHtml {
+        chain: Chain::Mainnet,
+        content_hash: Some(sha256::Hash::hash(b"HELLOWORLD")),
+        duplicates: 2,
+        first_inscribed: Some((inscription_id(1), 0)),
+        genesis_fee: 1,
+        genesis_height: 0,
+        inscription: inscription("text/plain;charset=utf-8", "HELLOWORLD"),
+        inscription_id: inscription_id(2),
+        next: None,
+        number: 1,
+        output: None,
+        previous: None,
+        sat: None,
+        satpoint: satpoint(1, 0),
+        timestamp: timestamp(0),
+      },
+      "
+        <h1>Inscription 1</h1>
+        .*
+          <dt>content type</dt>
+          <dd>text/plain;charset=utf-8</dd>
+          <dt>content hash</dt>
+          <dd><a class=monospace href=/content-hash/[[:xdigit:]]{64}>[[:xdigit:]]{64}</a></dd>
+          <dt>first inscribed as</dt>
+          <dd><a href=/inscription/1{64}i1>#0</a></dd>
+          <dt>duplicates</dt>
+          <dd><a href=/content-hash/[[:xdigit:]]{64}>2</a></dd>
+          <dt>timestamp</dt>
+        .*
+      "
+      .unindent()
+    );
+  }
 }
@@ -0,0 +1,59 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct InscriptionQueryHtml {
+  pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) next: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct InscriptionQueryJson {
+  pub inscriptions: Vec<InscriptionId>,
+}
+
+impl PageContent for InscriptionQueryHtml {
+  fn title(&self) -> String {
+    "Inscriptions".into()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn with_next() {
+    assert_regex_match!(
+      InscriptionQueryHtml {
+        inscriptions: vec![inscription_id(1), inscription_id(2)],
+        next: Some("/inscriptions/query?cursed=true&from=5".into()),
+      },
+      "
+        <h1>Inscriptions</h1>
+        <div class=thumbnails>
+          <a href=/inscription/1{64}i1><iframe .* src=/preview/1{64}i1></iframe></a>
+          <a href=/inscription/2{64}i2><iframe .* src=/preview/2{64}i2></iframe></a>
+        </div>
+        <div class=center>
+        <a class=next href=/inscriptions/query\\?cursed=true&amp;from=5>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn without_results() {
+    assert_regex_match!(
+      InscriptionQueryHtml {
+        inscriptions: Vec::new(),
+        next: None,
+      },
+      "
+        <h1>Inscriptions</h1>
+        <p>No matching inscriptions.</p>
+      "
+      .unindent()
+    );
+  }
+}
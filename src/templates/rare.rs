@@ -1,4 +1,4 @@
 use super::*;
 
 #[derive(Boilerplate)]
-pub(crate) struct RareTxt(pub(crate) Vec<(Sat, SatPoint)>);
+pub(crate) struct RareTxt(pub(crate) Vec<(Sat, SatPoint, Vec<SatTrait>)>);
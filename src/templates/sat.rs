@@ -6,6 +6,7 @@ pub(crate) struct SatHtml {
   pub(crate) satpoint: Option<SatPoint>,
   pub(crate) blocktime: Blocktime,
   pub(crate) inscriptions: Vec<InscriptionId>,
+  pub(crate) traits: Vec<SatTrait>,
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -20,6 +21,7 @@ pub struct SatJson {
   pub period: u64,
   pub offset: u64,
   pub rarity: Rarity,
+  pub traits: Vec<SatTrait>,
   pub percentile: String,
   pub satpoint: Option<SatPoint>,
   pub timestamp: i64,
@@ -44,6 +46,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: Vec::new(),
+        traits: vec![SatTrait::Alpha, SatTrait::Vintage],
       },
       "
         <h1>Sat 0</h1>
@@ -58,6 +61,11 @@ mod tests {
           <dt>block</dt><dd><a href=/block/0>0</a></dd>
           <dt>offset</dt><dd>0</dd>
           <dt>rarity</dt><dd><span class=mythic>mythic</span></dd>
+          <dt>traits</dt>
+          <dd>
+            <span class=trait>alpha</span>
+            <span class=trait>vintage</span>
+          </dd>
           <dt>timestamp</dt><dd><time>1970-01-01 00:00:00 UTC</time></dd>
         </dl>
         .*
@@ -77,6 +85,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: Vec::new(),
+        traits: Vec::new(),
       },
       "
         <h1>Sat 2099999997689999</h1>
@@ -110,6 +119,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: Vec::new(),
+        traits: Vec::new(),
       },
       r"<h1>Sat 1</h1>.*<a class=prev href=/sat/0>prev</a>\n<a class=next href=/sat/2>next</a>.*",
     );
@@ -123,6 +133,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: vec![inscription_id(1)],
+        traits: Vec::new(),
       },
       "
         <h1>Sat 0</h1>
@@ -144,6 +155,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: vec![inscription_id(1), inscription_id(2)],
+        traits: Vec::new(),
       },
       "
         <h1>Sat 0</h1>
@@ -166,6 +178,7 @@ mod tests {
         satpoint: None,
         blocktime: Blocktime::confirmed(0),
         inscriptions: Vec::new(),
+        traits: Vec::new(),
       },
       r"<h1>Sat 2099999997689999</h1>.*<a class=prev href=/sat/2099999997689998>prev</a>\nnext.*",
     );
@@ -179,6 +192,7 @@ mod tests {
         satpoint: Some(satpoint(1, 0)),
         blocktime: Blocktime::confirmed(0),
         inscriptions: Vec::new(),
+        traits: Vec::new(),
       },
       "<h1>Sat 0</h1>.*<dt>location</dt><dd class=monospace>1{64}:1:0</dd>.*",
     );
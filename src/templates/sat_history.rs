@@ -0,0 +1,88 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct SatHistoryHtml {
+  pub(crate) sat: Sat,
+  pub(crate) history: Vec<(Height, SatPoint)>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SatHistoryJson {
+  pub sat: u64,
+  pub history: Vec<SatHistoryEntryJson>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SatHistoryEntryJson {
+  pub height: u64,
+  pub satpoint: SatPoint,
+}
+
+impl SatHistoryJson {
+  pub(crate) fn new(html: SatHistoryHtml) -> Self {
+    Self {
+      sat: html.sat.n(),
+      history: html
+        .history
+        .into_iter()
+        .map(|(height, satpoint)| SatHistoryEntryJson {
+          height: height.n(),
+          satpoint,
+        })
+        .collect(),
+    }
+  }
+}
+
+impl PageContent for SatHistoryHtml {
+  fn title(&self) -> String {
+    format!("Sat {} History", self.sat)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn history() {
+    assert_regex_match!(
+      SatHistoryHtml {
+        sat: Sat(0),
+        history: vec![
+          (Height(0), satpoint(1, 0)),
+          (
+            Height(2),
+            SatPoint {
+              outpoint: OutPoint::null(),
+              offset: 7,
+            }
+          ),
+        ],
+      },
+      "
+        <h1>Sat <a href=/sat/0>0</a> History</h1>
+        <ol>
+          <li>block <a href=/block/0>0</a>: <a class=monospace href=/output/1{64}:1>1{64}:1:0</a></li>
+          <li>block <a href=/block/2>2</a>: lost</li>
+        </ol>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn without_history() {
+    assert_regex_match!(
+      SatHistoryHtml {
+        sat: Sat(0),
+        history: Vec::new(),
+      },
+      "
+        <h1>Sat <a href=/sat/0>0</a> History</h1>
+        <p>Not mined yet.</p>
+      "
+      .unindent()
+    );
+  }
+}
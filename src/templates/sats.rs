@@ -0,0 +1,82 @@
+use super::*;
+
+#[derive(Boilerplate)]
+pub(crate) struct SatsHtml {
+  pub(crate) sat_trait: SatTrait,
+  pub(crate) sats: Vec<(Sat, u64, SatPoint)>,
+  pub(crate) next: Option<Sat>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub struct SatsJson {
+  pub sat_trait: SatTrait,
+  pub sats: Vec<(Sat, u64, SatPoint)>,
+  pub next: Option<Sat>,
+}
+
+impl SatsHtml {
+  pub(crate) fn next_url(&self) -> Option<String> {
+    self
+      .next
+      .map(|next| format!("/sats?trait={}&from={next}", self.sat_trait))
+  }
+}
+
+impl SatsJson {
+  pub(crate) fn new(html: SatsHtml) -> Self {
+    Self {
+      sat_trait: html.sat_trait,
+      sats: html.sats,
+      next: html.next,
+    }
+  }
+}
+
+impl PageContent for SatsHtml {
+  fn title(&self) -> String {
+    format!("Sats with trait {}", self.sat_trait)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn with_next() {
+    assert_regex_match!(
+      SatsHtml {
+        sat_trait: SatTrait::FirstInBlock,
+        sats: vec![(Sat(0), 1, satpoint(1, 0)), (Sat(10), 5, satpoint(2, 3))],
+        next: Some(Sat(20)),
+      },
+      "
+        <h1>Sats with trait first-in-block</h1>
+        <ul class=monospace>
+          <li><a href=/sat/0>0</a> at <a href=/output/1{64}:1>1{64}:1:0</a></li>
+          <li><a href=/range/10/15>10–15</a> at <a href=/output/2{64}:2>2{64}:2:3</a></li>
+        </ul>
+        <div class=center>
+        <a class=next href=/sats\\?trait=first-in-block&amp;from=20>next</a>
+        </div>
+      "
+      .unindent()
+    );
+  }
+
+  #[test]
+  fn without_sats() {
+    assert_regex_match!(
+      SatsHtml {
+        sat_trait: SatTrait::Pizza,
+        sats: Vec::new(),
+        next: None,
+      },
+      "
+        <h1>Sats with trait pizza</h1>
+        <p>No unspent sats with this trait.</p>
+      "
+      .unindent()
+    );
+  }
+}
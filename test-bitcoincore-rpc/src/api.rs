@@ -105,6 +105,12 @@ pub trait Api {
   #[rpc(name = "listlockunspent")]
   fn list_lock_unspent(&self) -> Result<Vec<JsonOutPoint>, jsonrpc_core::Error>;
 
+  #[rpc(name = "gettxspendingprevout")]
+  fn get_tx_spending_prevout(
+    &self,
+    outputs: Vec<JsonOutPoint>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error>;
+
   #[rpc(name = "getrawchangeaddress")]
   fn get_raw_change_address(
     &self,
@@ -130,6 +136,12 @@ pub trait Api {
     address_type: Option<bitcoincore_rpc::json::AddressType>,
   ) -> Result<Address, jsonrpc_core::Error>;
 
+  #[rpc(name = "getaddressinfo")]
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error>;
+
   #[rpc(name = "listtransactions")]
   fn list_transactions(
     &self,
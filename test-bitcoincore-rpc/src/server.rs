@@ -264,7 +264,20 @@ impl Api for Server {
 
   fn send_raw_transaction(&self, tx: String) -> Result<String, jsonrpc_core::Error> {
     let tx: Transaction = deserialize(&hex::decode(tx).unwrap()).unwrap();
-    self.state.lock().unwrap().mempool.push(tx.clone());
+
+    let mut state = self.state.lock().unwrap();
+
+    state.wallet_transactions.insert(tx.txid());
+
+    state.mempool.retain(|mempool_tx| {
+      !mempool_tx.input.iter().any(|mempool_input| {
+        tx.input
+          .iter()
+          .any(|input| input.previous_output == mempool_input.previous_output)
+      })
+    });
+
+    state.mempool.push(tx.clone());
 
     Ok(tx.txid().to_string())
   }
@@ -348,34 +361,39 @@ impl Api for Server {
     blockhash: Option<BlockHash>,
   ) -> Result<Value, jsonrpc_core::Error> {
     assert_eq!(blockhash, None, "Blockhash param is unsupported");
+
+    let state = self.state();
+
+    let (tx, confirmations) = match state.transactions.get(&txid) {
+      Some(tx) => (tx, Some(1)),
+      None => match state.mempool.iter().find(|tx| tx.txid() == txid) {
+        Some(tx) => (tx, None),
+        None => return Err(Self::not_found()),
+      },
+    };
+
     if verbose.unwrap_or(false) {
-      match self.state().transactions.get(&txid) {
-        Some(_) => Ok(
-          serde_json::to_value(GetRawTransactionResult {
-            in_active_chain: Some(true),
-            hex: Vec::new(),
-            txid: Txid::all_zeros(),
-            hash: Wtxid::all_zeros(),
-            size: 0,
-            vsize: 0,
-            version: 0,
-            locktime: 0,
-            vin: Vec::new(),
-            vout: Vec::new(),
-            blockhash: None,
-            confirmations: Some(1),
-            time: None,
-            blocktime: None,
-          })
-          .unwrap(),
-        ),
-        None => Err(Self::not_found()),
-      }
+      Ok(
+        serde_json::to_value(GetRawTransactionResult {
+          in_active_chain: Some(confirmations.is_some()),
+          hex: serialize(tx),
+          txid,
+          hash: Wtxid::all_zeros(),
+          size: 0,
+          vsize: 0,
+          version: 0,
+          locktime: 0,
+          vin: Vec::new(),
+          vout: Vec::new(),
+          blockhash: None,
+          confirmations,
+          time: None,
+          blocktime: None,
+        })
+        .unwrap(),
+      )
     } else {
-      match self.state().transactions.get(&txid) {
-        Some(tx) => Ok(Value::String(hex::encode(serialize(tx)))),
-        None => Err(Self::not_found()),
-      }
+      Ok(Value::String(hex::encode(serialize(tx))))
     }
   }
 
@@ -430,6 +448,38 @@ impl Api for Server {
     )
   }
 
+  fn get_tx_spending_prevout(
+    &self,
+    outputs: Vec<JsonOutPoint>,
+  ) -> Result<Vec<Value>, jsonrpc_core::Error> {
+    let state = self.state();
+
+    Ok(
+      outputs
+        .into_iter()
+        .map(|output| {
+          let outpoint = OutPoint::new(output.txid, output.vout);
+
+          match state.mempool.iter().find(|tx| {
+            tx.input
+              .iter()
+              .any(|input| input.previous_output == outpoint)
+          }) {
+            Some(tx) => serde_json::json!({
+              "txid": outpoint.txid,
+              "vout": outpoint.vout,
+              "spendingtxid": tx.txid(),
+            }),
+            None => serde_json::json!({
+              "txid": outpoint.txid,
+              "vout": outpoint.vout,
+            }),
+          }
+        })
+        .collect(),
+    )
+  }
+
   fn get_raw_change_address(
     &self,
     _address_type: Option<bitcoincore_rpc::json::AddressType>,
@@ -439,6 +489,8 @@ impl Api for Server {
     let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
     let address = Address::p2tr(&secp256k1, public_key, None, self.network);
 
+    self.state().addresses.insert(address.clone());
+
     Ok(address)
   }
 
@@ -481,9 +533,25 @@ impl Api for Server {
     let (public_key, _parity) = XOnlyPublicKey::from_keypair(&key_pair);
     let address = Address::p2tr(&secp256k1, public_key, None, self.network);
 
+    self.state().addresses.insert(address.clone());
+
     Ok(address)
   }
 
+  fn get_address_info(
+    &self,
+    address: Address<NetworkUnchecked>,
+  ) -> Result<Value, jsonrpc_core::Error> {
+    let address = address.assume_checked();
+
+    Ok(serde_json::json!({
+      "address": address.to_string(),
+      "scriptPubKey": address.script_pubkey().to_hex_string(),
+      "ismine": self.state().addresses.contains(&address),
+      "labels": [],
+    }))
+  }
+
   fn list_transactions(
     &self,
     _label: Option<String>,
@@ -499,8 +567,8 @@ impl Api for Server {
         .take(count.unwrap_or(u16::MAX).into())
         .map(|(txid, tx)| (*txid, tx))
         .chain(state.mempool.iter().map(|tx| (tx.txid(), tx)))
-        .map(|(txid, tx)| ListTransactionResult {
-          info: WalletTxInfo {
+        .flat_map(|(txid, tx)| {
+          let info = WalletTxInfo {
             confirmations: state.get_confirmations(tx),
             blockhash: None,
             blockindex: None,
@@ -511,18 +579,30 @@ impl Api for Server {
             timereceived: 0,
             bip125_replaceable: Bip125Replaceable::Unknown,
             wallet_conflicts: Vec::new(),
-          },
-          detail: GetTransactionResultDetail {
-            address: None,
-            category: GetTransactionResultDetailCategory::Immature,
-            amount: SignedAmount::from_sat(0),
-            label: None,
-            vout: 0,
-            fee: Some(SignedAmount::from_sat(0)),
-            abandoned: None,
-          },
-          trusted: None,
-          comment: None,
+          };
+
+          let mut details = state.details(tx);
+
+          if details.is_empty() {
+            details.push(GetTransactionResultDetail {
+              address: None,
+              category: GetTransactionResultDetailCategory::Immature,
+              amount: SignedAmount::from_sat(0),
+              label: None,
+              vout: 0,
+              fee: Some(SignedAmount::from_sat(0)),
+              abandoned: None,
+            });
+          }
+
+          details
+            .into_iter()
+            .map(move |detail| ListTransactionResult {
+              info: info.clone(),
+              detail,
+              trusted: None,
+              comment: None,
+            })
         })
         .collect(),
     )
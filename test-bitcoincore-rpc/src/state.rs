@@ -1,6 +1,7 @@
 use super::*;
 
 pub(crate) struct State {
+  pub(crate) addresses: BTreeSet<Address>,
   pub(crate) blocks: BTreeMap<BlockHash, Block>,
   pub(crate) descriptors: Vec<String>,
   pub(crate) fail_lock_unspent: bool,
@@ -14,6 +15,7 @@ pub(crate) struct State {
   pub(crate) transactions: BTreeMap<Txid, Transaction>,
   pub(crate) utxos: BTreeMap<OutPoint, Amount>,
   pub(crate) version: usize,
+  pub(crate) wallet_transactions: BTreeSet<Txid>,
   pub(crate) wallets: BTreeSet<String>,
 }
 
@@ -28,6 +30,7 @@ impl State {
     blocks.insert(genesis_block_hash, genesis_block);
 
     Self {
+      addresses: BTreeSet::new(),
       blocks,
       descriptors: Vec::new(),
       fail_lock_unspent,
@@ -40,6 +43,7 @@ impl State {
       transactions: BTreeMap::new(),
       utxos: BTreeMap::new(),
       version,
+      wallet_transactions: BTreeSet::new(),
       wallets: BTreeSet::new(),
       loaded_wallets: BTreeSet::new(),
     }
@@ -181,4 +185,66 @@ impl State {
 
     0
   }
+
+  pub(crate) fn details(&self, tx: &Transaction) -> Vec<GetTransactionResultDetail> {
+    let fee = if self.wallet_transactions.contains(&tx.txid()) {
+      let input_value = tx
+        .input
+        .iter()
+        .map(|input| {
+          let outpoint = input.previous_output;
+          self
+            .transactions
+            .get(&outpoint.txid)
+            .or_else(|| self.mempool.iter().find(|tx| tx.txid() == outpoint.txid))
+            .unwrap()
+            .output[outpoint.vout as usize]
+            .value
+        })
+        .sum::<u64>();
+
+      Some(input_value - tx.output.iter().map(|output| output.value).sum::<u64>())
+    } else {
+      None
+    };
+
+    let mut details = Vec::new();
+
+    for (vout, output) in tx.output.iter().enumerate() {
+      let address = Address::from_script(&output.script_pubkey, self.network).ok();
+
+      let mine = address
+        .as_ref()
+        .is_some_and(|address| self.addresses.contains(address));
+
+      let value = i64::try_from(output.value).unwrap();
+
+      let (category, amount, fee) = match (mine, fee) {
+        (true, _) => (
+          GetTransactionResultDetailCategory::Receive,
+          SignedAmount::from_sat(value),
+          // the client can't deserialize a null fee
+          Some(SignedAmount::ZERO),
+        ),
+        (false, Some(fee)) => (
+          GetTransactionResultDetailCategory::Send,
+          SignedAmount::from_sat(-value),
+          Some(SignedAmount::from_sat(-i64::try_from(fee).unwrap())),
+        ),
+        (false, None) => continue,
+      };
+
+      details.push(GetTransactionResultDetail {
+        address: address.map(|address| address.to_string().parse().unwrap()),
+        category,
+        amount,
+        label: None,
+        vout: vout.try_into().unwrap(),
+        fee,
+        abandoned: None,
+      });
+    }
+
+    details
+  }
 }
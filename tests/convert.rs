@@ -0,0 +1,63 @@
+use {
+  super::*,
+  ord::{subcommand::convert::Output, Rarity},
+};
+
+#[test]
+fn table() {
+  assert_eq!(
+    CommandBuilder::new("convert sats.txt")
+      .write("sats.txt", "# genesis\n0\n\n1.0\n")
+      .run_and_check_output::<Vec<Output>>(),
+    [
+      Output {
+        input: "0".into(),
+        number: 0,
+        decimal: "0.0".into(),
+        degree: "0°0′0″0‴".into(),
+        name: "nvtdijuwxlp".into(),
+        percentile: "0%".into(),
+        rarity: Rarity::Mythic,
+        epoch: 0,
+        cycle: 0,
+        block: 0,
+        offset: 0,
+      },
+      Output {
+        input: "1.0".into(),
+        number: 5000000000,
+        decimal: "1.0".into(),
+        degree: "0°1′1″0‴".into(),
+        name: "nvtcsezkbth".into(),
+        percentile: "0.00023809523835714296%".into(),
+        rarity: Rarity::Uncommon,
+        epoch: 0,
+        cycle: 0,
+        block: 1,
+        offset: 0,
+      },
+    ]
+  );
+}
+
+#[test]
+fn to_notation() {
+  CommandBuilder::new("convert --to degree sats.txt")
+    .write("sats.txt", "0\nnvtcsezkbth\n")
+    .stdout_regex("0°0′0″0‴\n0°1′1″0‴\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn invalid_sats_are_all_reported() {
+  CommandBuilder::new("convert sats.txt")
+    .write("sats.txt", "0\nfoo1\n0°1′0″0‴\n")
+    .expected_stderr(
+      "error: 2 invalid sats:
+line 2: `foo1`: invalid character `1` in sat name `foo1`
+line 3: `0°1′0″0‴`: relationship between epoch offset 1 and period offset 0 must be multiple of 336
+",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
@@ -1,4 +1,4 @@
-use {super::*, ord::subcommand::find::Output};
+use {super::*, ord::subcommand::find::Output, test_bitcoincore_rpc::TransactionTemplate};
 
 #[test]
 fn find_command_returns_satpoint_for_sat() {
@@ -43,3 +43,106 @@ fn no_satoshi_index() {
     .expected_exit_code(1)
     .run_and_extract_stdout();
 }
+
+#[test]
+fn find_sats_by_trait() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  assert_eq!(
+    CommandBuilder::new("--index-traits find --trait first-in-block")
+      .rpc_server(&rpc_server)
+      .run_and_check_output::<Vec<Output>>(),
+    [
+      (
+        0,
+        "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0:0"
+          .parse()
+          .unwrap()
+      ),
+      (50 * COIN_VALUE, format!("{coinbase}:0:0").parse().unwrap()),
+    ]
+    .into_iter()
+    .map(|(start, satpoint)| Output {
+      start,
+      size: 1,
+      satpoint,
+      address: None,
+      blockhash: None,
+      date: None,
+      height: None,
+      name: None,
+      timestamp: None,
+      value: None,
+    })
+    .collect::<Vec<Output>>()
+  );
+}
+
+#[test]
+fn find_by_trait_requires_trait_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  CommandBuilder::new("--index-sats find --trait vintage")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: find --trait requires index created with `--index-traits` flag\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn find_sat_at_height() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    fee: 0,
+    ..Default::default()
+  });
+  rpc_server.mine_blocks(1);
+
+  for (height, satpoint) in [(1, format!("{coinbase}:0:5")), (2, format!("{txid}:0:5"))] {
+    assert_eq!(
+      CommandBuilder::new(format!(
+        "--index-sat-history find 5000000005 --at-height {height}"
+      ))
+      .rpc_server(&rpc_server)
+      .run_and_check_output::<Vec<Output>>(),
+      [Output {
+        start: 5000000005,
+        size: 1,
+        satpoint: satpoint.parse().unwrap(),
+        address: None,
+        blockhash: None,
+        date: None,
+        height: None,
+        name: None,
+        timestamp: None,
+        value: None,
+      }]
+    );
+  }
+
+  CommandBuilder::new("--index-sat-history find 5000000005 --at-height 0")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: sat 5000000005 had not been mined as of height 0\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+
+  CommandBuilder::new("--index-sat-history find 5000000005 --at-height 3")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: index has not reached height 3\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn find_at_height_requires_history_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  CommandBuilder::new("--index-sats find 0 --at-height 0")
+    .rpc_server(&rpc_server)
+    .expected_stderr(
+      "error: find --at-height requires index created with `--index-sat-history` flag\n",
+    )
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
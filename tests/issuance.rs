@@ -0,0 +1,65 @@
+use {
+  super::*,
+  ord::subcommand::issuance::{BlockRangeIssuance, Output, RarityCounts},
+};
+
+#[test]
+fn issuance() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new("issuance --interval 1")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  assert_eq!(output.height, Some(1));
+  assert_eq!(output.supply, 2099999997690000);
+  assert_eq!(output.issued, 10000000000);
+  assert_eq!(output.remaining, 2099989997690000);
+  assert_eq!(output.next_halving.unwrap().height, 210000);
+
+  assert_eq!(output.epochs.len(), 33);
+  assert_eq!(output.epochs[1].subsidy, 2500000000);
+  assert_eq!(output.epochs[1].blocks.sats, 525000000000000);
+  assert_eq!(output.epochs[1].blocks.rarities.epic, 1);
+  assert_eq!(output.epochs[32].subsidy, 1);
+
+  assert_eq!(
+    output.ranges,
+    [
+      BlockRangeIssuance {
+        start: 0,
+        end: 1,
+        first_sat: 0,
+        sats: 5000000000,
+        rarities: RarityCounts {
+          common: 4999999999,
+          mythic: 1,
+          ..Default::default()
+        },
+      },
+      BlockRangeIssuance {
+        start: 1,
+        end: 2,
+        first_sat: 5000000000,
+        sats: 5000000000,
+        rarities: RarityCounts {
+          common: 4999999999,
+          uncommon: 1,
+          ..Default::default()
+        },
+      },
+    ]
+  );
+}
+
+#[test]
+fn interval_must_be_positive() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("issuance --interval 0")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: interval must be greater than zero\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
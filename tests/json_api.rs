@@ -1,7 +1,14 @@
 use {
-  super::*, ord::inscription_id::InscriptionId, ord::rarity::Rarity,
-  ord::templates::inscription::InscriptionJson, ord::templates::inscriptions::InscriptionsJson,
-  ord::templates::output::OutputJson, ord::templates::sat::SatJson, ord::SatPoint,
+  super::*,
+  ord::inscription_id::InscriptionId,
+  ord::rarity::Rarity,
+  ord::templates::envelope::{Envelope, ErrorJson},
+  ord::templates::inscription::InscriptionJson,
+  ord::templates::inscription_query::InscriptionQueryJson,
+  ord::templates::inscriptions::InscriptionsJson,
+  ord::templates::output::OutputJson,
+  ord::templates::sat::SatJson,
+  ord::{SatPoint, SatTrait},
   test_bitcoincore_rpc::TransactionTemplate,
 };
 
@@ -14,7 +21,9 @@ fn get_sat_without_sat_index() {
 
   assert_eq!(response.status(), StatusCode::OK);
 
-  let mut sat_json: SatJson = serde_json::from_str(&response.text().unwrap()).unwrap();
+  let mut sat_json: SatJson = serde_json::from_str::<Envelope<SatJson>>(&response.text().unwrap())
+    .unwrap()
+    .data;
 
   // this is a hack to ignore the timestamp, since it changes for every request
   sat_json.timestamp = 0;
@@ -32,6 +41,7 @@ fn get_sat_without_sat_index() {
       period: 3437,
       offset: 0,
       rarity: Rarity::Uncommon,
+      traits: vec![SatTrait::FirstInBlock, SatTrait::LastInBlock],
       percentile: "100%".into(),
       satpoint: None,
       timestamp: 0,
@@ -46,15 +56,17 @@ fn get_sat_with_inscription_and_sat_index() {
 
   create_wallet(&rpc_server);
 
-  let Inscribe { reveal, .. } = inscribe(&rpc_server);
-  let inscription_id = InscriptionId::from(reveal);
+  let Inscribe { reveals, .. } = inscribe(&rpc_server);
+  let inscription_id = InscriptionId::from(reveals[0]);
 
   let response = TestServer::spawn_with_args(&rpc_server, &["--index-sats", "--enable-json-api"])
     .json_request(format!("/sat/{}", 50 * COIN_VALUE));
 
   assert_eq!(response.status(), StatusCode::OK);
 
-  let sat_json: SatJson = serde_json::from_str(&response.text().unwrap()).unwrap();
+  let sat_json: SatJson = serde_json::from_str::<Envelope<SatJson>>(&response.text().unwrap())
+    .unwrap()
+    .data;
 
   pretty_assert_eq!(
     sat_json,
@@ -69,8 +81,14 @@ fn get_sat_with_inscription_and_sat_index() {
       period: 0,
       offset: 0,
       rarity: Rarity::Uncommon,
+      traits: vec![
+        SatTrait::Alpha,
+        SatTrait::FirstInBlock,
+        SatTrait::RoundNumber,
+        SatTrait::Vintage,
+      ],
       percentile: "0.00023809523835714296%".into(),
-      satpoint: Some(SatPoint::from_str(&format!("{}:{}:{}", reveal, 0, 0)).unwrap()),
+      satpoint: Some(SatPoint::from_str(&format!("{}:{}:{}", reveals[0], 0, 0)).unwrap()),
       timestamp: 1,
       inscriptions: vec![inscription_id],
     }
@@ -87,7 +105,7 @@ fn get_sat_with_inscription_on_common_sat_and_more_inscriptions() {
 
   let txid = rpc_server.mine_blocks(1)[0].txdata[0].txid();
 
-  let Inscribe { reveal, .. } = CommandBuilder::new(format!(
+  let Inscribe { reveals, .. } = CommandBuilder::new(format!(
     "wallet inscribe --satpoint {}:0:1 --fee-rate 1 foo.txt",
     txid
   ))
@@ -96,14 +114,16 @@ fn get_sat_with_inscription_on_common_sat_and_more_inscriptions() {
   .run_and_check_output();
 
   rpc_server.mine_blocks(1);
-  let inscription_id = InscriptionId::from(reveal);
+  let inscription_id = InscriptionId::from(reveals[0]);
 
   let response = TestServer::spawn_with_args(&rpc_server, &["--index-sats", "--enable-json-api"])
     .json_request(format!("/sat/{}", 3 * 50 * COIN_VALUE + 1));
 
   assert_eq!(response.status(), StatusCode::OK);
 
-  let sat_json: SatJson = serde_json::from_str(&response.text().unwrap()).unwrap();
+  let sat_json: SatJson = serde_json::from_str::<Envelope<SatJson>>(&response.text().unwrap())
+    .unwrap()
+    .data;
 
   pretty_assert_eq!(
     sat_json,
@@ -118,8 +138,9 @@ fn get_sat_with_inscription_on_common_sat_and_more_inscriptions() {
       period: 0,
       offset: 1,
       rarity: Rarity::Common,
+      traits: vec![SatTrait::Vintage],
       percentile: "0.000714285715119048%".into(),
-      satpoint: Some(SatPoint::from_str(&format!("{}:{}:{}", reveal, 0, 0)).unwrap()),
+      satpoint: Some(SatPoint::from_str(&format!("{}:{}:{}", reveals[0], 0, 0)).unwrap()),
       timestamp: 3,
       inscriptions: vec![inscription_id],
     }
@@ -132,8 +153,8 @@ fn get_inscription() {
 
   create_wallet(&rpc_server);
 
-  let Inscribe { reveal, .. } = inscribe(&rpc_server);
-  let inscription_id = InscriptionId::from(reveal);
+  let Inscribe { reveals, .. } = inscribe(&rpc_server);
+  let inscription_id = InscriptionId::from(reveals[0]);
 
   let response = TestServer::spawn_with_args(&rpc_server, &["--index-sats", "--enable-json-api"])
     .json_request(format!("/inscription/{}", inscription_id));
@@ -141,7 +162,9 @@ fn get_inscription() {
   assert_eq!(response.status(), StatusCode::OK);
 
   let mut inscription_json: InscriptionJson =
-    serde_json::from_str(&response.text().unwrap()).unwrap();
+    serde_json::from_str::<Envelope<InscriptionJson>>(&response.text().unwrap())
+      .unwrap()
+      .data;
   assert_regex_match!(inscription_json.address.unwrap(), r"bc1p.*");
   inscription_json.address = None;
 
@@ -155,12 +178,15 @@ fn get_inscription() {
       output_value: Some(10000),
       address: None,
       sat: Some(ord::Sat(50 * COIN_VALUE)),
-      satpoint: SatPoint::from_str(&format!("{}:{}:{}", reveal, 0, 0)).unwrap(),
+      satpoint: SatPoint::from_str(&format!("{}:{}:{}", reveals[0], 0, 0)).unwrap(),
       content_type: Some("text/plain;charset=utf-8".to_string()),
       content_length: Some(3),
       timestamp: 2,
       previous: None,
-      next: None
+      next: None,
+      content_hash: Some("9520437ce8902eb379a7d8aaa98fc4c94eeb07b6684854868fa6f72bf34b0fd3".into()),
+      first_inscribed: None,
+      duplicates: 0,
     }
   )
 }
@@ -194,12 +220,12 @@ fn create_210_inscriptions(
 
   // Create another 60 non cursed
   for _ in 0..60 {
-    let Inscribe { reveal, .. } = CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+    let Inscribe { reveals, .. } = CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
       .write("foo.txt", "FOO")
       .rpc_server(rpc_server)
       .run_and_check_output();
     rpc_server.mine_blocks(1);
-    blessed_inscriptions.push(InscriptionId::from(reveal));
+    blessed_inscriptions.push(InscriptionId::from(reveals[0]));
   }
 
   rpc_server.mine_blocks(1);
@@ -219,7 +245,9 @@ fn get_inscriptions() {
   let response = server.json_request("/inscriptions");
   assert_eq!(response.status(), StatusCode::OK);
   let inscriptions_json: InscriptionsJson =
-    serde_json::from_str(&response.text().unwrap()).unwrap();
+    serde_json::from_str::<Envelope<InscriptionsJson>>(&response.text().unwrap())
+      .unwrap()
+      .data;
 
   // 100 latest (blessed) inscriptions
   assert_eq!(inscriptions_json.inscriptions.len(), 100);
@@ -243,7 +271,9 @@ fn get_inscriptions() {
   assert_eq!(response.status(), StatusCode::OK);
 
   let inscriptions_json: InscriptionsJson =
-    serde_json::from_str(&response.text().unwrap()).unwrap();
+    serde_json::from_str::<Envelope<InscriptionsJson>>(&response.text().unwrap())
+      .unwrap()
+      .data;
 
   assert_eq!(
     inscriptions_json.inscriptions.len(),
@@ -277,7 +307,9 @@ fn get_inscriptions() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let inscriptions_json: InscriptionsJson =
-      serde_json::from_str(&response.text().unwrap()).unwrap();
+      serde_json::from_str::<Envelope<InscriptionsJson>>(&response.text().unwrap())
+        .unwrap()
+        .data;
 
     assert_eq!(inscriptions_json.inscriptions.len(), 1);
     assert_eq!(
@@ -292,7 +324,9 @@ fn get_inscriptions() {
     assert_eq!(response.status(), StatusCode::OK);
 
     let inscription_json: InscriptionJson =
-      serde_json::from_str(&response.text().unwrap()).unwrap();
+      serde_json::from_str::<Envelope<InscriptionJson>>(&response.text().unwrap())
+        .unwrap()
+        .data;
 
     assert_eq!(
       inscription_json.inscription_id,
@@ -336,7 +370,9 @@ fn get_inscriptions_in_block() {
   assert_eq!(response.status(), StatusCode::OK);
 
   let inscriptions_json: InscriptionsJson =
-    serde_json::from_str(&response.text().unwrap()).unwrap();
+    serde_json::from_str::<Envelope<InscriptionsJson>>(&response.text().unwrap())
+      .unwrap()
+      .data;
 
   pretty_assert_eq!(
     inscriptions_json.inscriptions,
@@ -367,7 +403,10 @@ fn get_output() {
   let response = server.json_request(format!("/output/{}:0", txid));
   assert_eq!(response.status(), StatusCode::OK);
 
-  let output_json: OutputJson = serde_json::from_str(&response.text().unwrap()).unwrap();
+  let output_json: OutputJson =
+    serde_json::from_str::<Envelope<OutputJson>>(&response.text().unwrap())
+      .unwrap()
+      .data;
 
   pretty_assert_eq!(
     output_json,
@@ -390,6 +429,165 @@ fn get_output() {
   );
 }
 
+#[test]
+fn query_inscriptions() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let mut inscriptions = Vec::new();
+  for (i, content_type) in ["text/plain", "image/png", "text/plain"]
+    .into_iter()
+    .enumerate()
+  {
+    rpc_server.mine_blocks(1);
+    let txid = rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(i + 1, 0, 0)],
+      witness: envelope(&[b"ord", &[1], content_type.as_bytes(), &[], b"bar"]),
+      ..Default::default()
+    });
+    inscriptions.push(InscriptionId::from(txid));
+  }
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &["--enable-json-api"]);
+
+  let response = server.json_request("/inscriptions/query?content_type=text/plain&sort=oldest");
+  assert_eq!(response.status(), StatusCode::OK);
+
+  pretty_assert_eq!(
+    serde_json::from_str::<Envelope<InscriptionQueryJson>>(&response.text().unwrap()).unwrap(),
+    Envelope {
+      data: InscriptionQueryJson {
+        inscriptions: vec![inscriptions[0], inscriptions[2]],
+      },
+      next: None,
+      height: Some(4),
+    }
+  );
+
+  let response = server.json_request("/inscriptions/query?max_height=2");
+  assert_eq!(response.status(), StatusCode::OK);
+
+  pretty_assert_eq!(
+    serde_json::from_str::<Envelope<InscriptionQueryJson>>(&response.text().unwrap()).unwrap(),
+    Envelope {
+      data: InscriptionQueryJson {
+        inscriptions: vec![inscriptions[0]],
+      },
+      next: None,
+      height: Some(4),
+    }
+  );
+}
+
+#[test]
+fn paginated_responses_have_stable_next_cursors() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  for i in 0..3 {
+    rpc_server.mine_blocks(1);
+    rpc_server.broadcast_tx(TransactionTemplate {
+      inputs: &[(i + 1, 0, 0)],
+      witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"bar"]),
+      ..Default::default()
+    });
+  }
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &["--enable-json-api"]);
+
+  let response = server.json_request("/inscriptions/2/2");
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let envelope =
+    serde_json::from_str::<Envelope<InscriptionsJson>>(&response.text().unwrap()).unwrap();
+  assert_eq!(envelope.data.inscriptions.len(), 2);
+  assert_eq!(envelope.next, Some("/inscriptions/0/2".into()));
+  assert_eq!(envelope.height, Some(4));
+
+  let response = server.json_request("/inscriptions/query?sort=oldest&from=0");
+  let envelope =
+    serde_json::from_str::<Envelope<InscriptionQueryJson>>(&response.text().unwrap()).unwrap();
+  assert_eq!(envelope.data.inscriptions.len(), 3);
+  assert_eq!(envelope.next, None);
+}
+
+#[test]
+fn errors_are_json_objects() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  let server = TestServer::spawn_with_args(&rpc_server, &["--enable-json-api"]);
+
+  let inscription_id = "1111111111111111111111111111111111111111111111111111111111111111i0";
+
+  let response = server.json_request(format!("/inscription/{inscription_id}"));
+  assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+  pretty_assert_eq!(
+    serde_json::from_str::<ErrorJson>(&response.text().unwrap()).unwrap(),
+    ErrorJson {
+      status: 404,
+      message: format!("inscription {inscription_id} not found"),
+    }
+  );
+
+  let response = server.json_request("/inscriptions/query?rarity=foo");
+  assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+  let error = serde_json::from_str::<ErrorJson>(&response.text().unwrap()).unwrap();
+  assert_eq!(error.status, 400);
+  assert!(error.message.contains("invalid rarity: foo"));
+}
+
+#[test]
+fn stats_and_transfers_are_enveloped() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  rpc_server.mine_blocks(1);
+  rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"bar"]),
+    ..Default::default()
+  });
+  rpc_server.mine_blocks(1);
+
+  let server = TestServer::spawn_with_args(&rpc_server, &["--enable-json-api"]);
+
+  let response = server.json_request("/stats");
+  assert_eq!(response.status(), StatusCode::OK);
+
+  pretty_assert_eq!(
+    serde_json::from_str::<Envelope<serde_json::Value>>(&response.text().unwrap()).unwrap(),
+    Envelope {
+      data: serde_json::json!({
+        "highest_block_indexed": 2,
+        "lowest_inscription_number": 0,
+        "highest_inscription_number": 0,
+      }),
+      next: None,
+      height: Some(2),
+    }
+  );
+
+  let response = server.json_request("/transfers/2");
+  assert_eq!(response.status(), StatusCode::OK);
+
+  pretty_assert_eq!(
+    serde_json::from_str::<Envelope<serde_json::Value>>(&response.text().unwrap()).unwrap(),
+    Envelope {
+      data: serde_json::json!([]),
+      next: None,
+      height: Some(2),
+    }
+  );
+
+  let response = server.request("/stats");
+  assert_eq!(response.status(), StatusCode::OK);
+  assert!(response
+    .text()
+    .unwrap()
+    .contains("\"highest_inscription_number\": 0"));
+}
+
 #[test]
 fn json_request_fails_when_not_enabled() {
   let rpc_server = test_bitcoincore_rpc::spawn();
@@ -42,7 +42,6 @@ macro_rules! assert_regex_match {
 
 #[derive(Deserialize, Debug)]
 struct Inscribe {
-  #[allow(dead_code)]
   commit: Txid,
   inscriptions: Vec<String>,
   reveals: Vec<Txid>,
@@ -95,14 +94,18 @@ mod command_builder;
 mod expected;
 mod test_server;
 
+mod convert;
 mod core;
 mod epochs;
 mod find;
 mod index;
 mod info;
+mod issuance;
 mod json_api;
 mod list;
+mod lost_sats;
 mod parse;
+mod ranges;
 mod server;
 mod subsidy;
 mod supply;
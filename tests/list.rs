@@ -21,10 +21,25 @@ fn output_found() {
       offset: 0,
       rarity: "mythic".parse().unwrap(),
       name: "nvtdijuwxlp".into(),
+      address: None,
     }]
   );
 }
 
+#[test]
+fn show_address_with_utxo_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  let output = CommandBuilder::new(
+    "--index-utxos list --show-address 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0",
+  )
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Vec<Output>>();
+
+  // the genesis coinbase pays to a bare public key, which has no address
+  assert_eq!(output.len(), 1);
+  assert_eq!(output[0].address, None);
+}
+
 #[test]
 fn output_not_found() {
   let rpc_server = test_bitcoincore_rpc::spawn();
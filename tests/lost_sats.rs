@@ -0,0 +1,42 @@
+use {
+  super::*,
+  ord::{
+    subcommand::lost_sats::{LostSatRange, Output},
+    LostCause,
+  },
+};
+
+#[test]
+fn lost_sats() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  rpc_server.mine_blocks_with_subsidy(1, 0);
+
+  assert_eq!(
+    CommandBuilder::new("--index-sats lost-sats")
+      .rpc_server(&rpc_server)
+      .run_and_check_output::<Output>(),
+    Output {
+      lost_sats: 5000000000,
+      ranges: vec![LostSatRange {
+        start: 5000000000,
+        end: 10000000000,
+        size: 5000000000,
+        offset: 0,
+        height: 1,
+        cause: LostCause::Unclaimed,
+      }],
+      inscriptions: Vec::new(),
+    }
+  );
+}
+
+#[test]
+fn lost_sats_requires_sat_index() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  CommandBuilder::new("lost-sats")
+    .rpc_server(&rpc_server)
+    .expected_stderr("error: lost-sats requires index created with `--index-sats` flag\n")
+    .expected_exit_code(1)
+    .run_and_extract_stdout();
+}
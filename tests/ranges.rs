@@ -0,0 +1,93 @@
+use {
+  super::*,
+  ord::subcommand::ranges::{Output, OverlapOutput, SplitOutput},
+};
+
+fn output(start: u64, end: u64) -> Output {
+  Output {
+    start,
+    end,
+    size: end - start,
+  }
+}
+
+#[test]
+fn union() {
+  assert_eq!(
+    CommandBuilder::new("ranges union a.txt b.json")
+      .write("a.txt", "0-10\n30-40\n")
+      .write("b.json", r#"[{"start": 5, "end": 20}, {"sat": 40}]"#)
+      .run_and_check_output::<Vec<Output>>(),
+    [output(0, 20), output(30, 41)]
+  );
+}
+
+#[test]
+fn intersect() {
+  assert_eq!(
+    CommandBuilder::new("ranges intersect a.txt b.txt")
+      .write("a.txt", "0-10\n30-40\n")
+      .write("b.txt", "5-35\n")
+      .run_and_check_output::<Vec<Output>>(),
+    [output(5, 10), output(30, 35)]
+  );
+}
+
+#[test]
+fn subtract() {
+  assert_eq!(
+    CommandBuilder::new("ranges subtract a.txt b.txt c.txt")
+      .write("a.txt", "0-100\n")
+      .write("b.txt", "10-20\n")
+      .write("c.txt", "# spent\n90-110\n")
+      .run_and_check_output::<Vec<Output>>(),
+    [output(0, 10), output(20, 90)]
+  );
+}
+
+#[test]
+fn split() {
+  assert_eq!(
+    CommandBuilder::new("ranges split --offset 15 a.txt")
+      .write("a.txt", "100-110\n0-10\n")
+      .run_and_check_output::<SplitOutput>(),
+    SplitOutput {
+      before: vec![output(100, 110), output(0, 5)],
+      after: vec![output(5, 10)],
+    }
+  );
+}
+
+#[test]
+fn overlap() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+
+  assert_eq!(
+    CommandBuilder::new(
+      "--index-sats ranges overlap --output 4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0 a.txt"
+    )
+    .write("a.txt", "10-20\n5000000000-5000000010\n")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<OverlapOutput>>(),
+    [OverlapOutput {
+      output: "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b:0"
+        .parse()
+        .unwrap(),
+      offset: 10,
+      start: 10,
+      end: 20,
+      size: 10,
+    }]
+  );
+}
+
+#[test]
+fn invalid_range() {
+  CommandBuilder::new("ranges union a.txt")
+    .write("a.txt", "10-5\n")
+    .expected_exit_code(1)
+    .expected_stderr(
+      "error: reading ranges from `a.txt`\nbecause: line 1\nbecause: invalid sat range `10-5`: end before start\n",
+    )
+    .run_and_extract_stdout();
+}
@@ -390,3 +390,57 @@ fn missing_credentials() {
     .expected_stderr("error: no bitcoind rpc user specified\n")
     .run_and_extract_stdout();
 }
+
+#[test]
+fn address_page_lists_outputs_and_inscriptions() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(test_bitcoincore_rpc::TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    ..Default::default()
+  });
+  rpc_server.mine_blocks(1);
+
+  let address = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+  let inscription = format!("{txid}i0");
+
+  let send = CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write("batch.txt", format!("{inscription} {address}\n"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<ord::subcommand::wallet::send_batch::Output>();
+  rpc_server.mine_blocks(1);
+
+  let outpoint = send.outputs[0];
+
+  let server =
+    TestServer::spawn_with_args(&rpc_server, &["--index-addresses", "--enable-json-api"]);
+
+  server.assert_response_regex(
+    format!("/address/{address}"),
+    format!(
+      ".*<h1>Address <span class=monospace>{address}</span></h1>.*\
+       <a href=/inscription/{inscription}>.*\
+       <dt>cardinal balance</dt><dd>0</dd>.*\
+       <h2>1 Output</h2>.*<a href=/output/{outpoint}>{outpoint}</a> 10000.*"
+    ),
+  );
+
+  let response = server.json_request(format!("/address/{address}"));
+  assert_eq!(response.status(), StatusCode::OK);
+
+  let json = serde_json::from_str::<
+    ord::templates::envelope::Envelope<ord::templates::address::AddressJson>,
+  >(&response.text().unwrap())
+  .unwrap()
+  .data;
+
+  assert_eq!(json.address, address);
+  assert_eq!(json.outputs, [outpoint]);
+  assert_eq!(json.inscriptions.len(), 1);
+  assert_eq!(json.inscriptions[0].to_string(), inscription);
+  assert_eq!(json.cardinal_balance, 0);
+  assert_eq!(json.rare_sats, None);
+}
@@ -1,4 +1,7 @@
-use {super::*, ord::subcommand::traits::Output, ord::Rarity};
+use {
+  super::*,
+  ord::{subcommand::traits::Output, Rarity, SatTrait},
+};
 
 #[test]
 fn traits_command_prints_sat_traits() {
@@ -15,6 +18,12 @@ fn traits_command_prints_sat_traits() {
       period: 0,
       offset: 0,
       rarity: Rarity::Mythic,
+      traits: vec![
+        SatTrait::Alpha,
+        SatTrait::FirstInBlock,
+        SatTrait::Palindrome,
+        SatTrait::Vintage,
+      ],
     }
   );
 }
@@ -33,6 +42,28 @@ fn traits_command_for_last_sat() {
       period: 3437,
       offset: 0,
       rarity: Rarity::Uncommon,
+      traits: vec![SatTrait::FirstInBlock, SatTrait::LastInBlock],
     }
   );
 }
+
+#[test]
+fn traits_command_includes_configured_trait_ranges() {
+  let tempdir = TempDir::new().unwrap();
+
+  fs::write(
+    tempdir.path().join("ord.yaml"),
+    "hidden: []\ntrait_ranges:\n  pizza:\n  - [100000000000000, 100000000001000]\n",
+  )
+  .unwrap();
+
+  assert_eq!(
+    CommandBuilder::new(format!(
+      "--config {} traits 100000000000500",
+      tempdir.path().join("ord.yaml").display()
+    ))
+    .run_and_check_output::<Output>()
+    .traits,
+    vec![SatTrait::Pizza]
+  );
+}
@@ -1,8 +1,11 @@
 use super::*;
 
 mod balance;
+mod bump;
 mod cardinals;
+mod consolidate;
 mod create;
+mod extract;
 mod inscribe;
 mod inscriptions;
 mod outputs;
@@ -10,4 +13,6 @@ mod receive;
 mod restore;
 mod sats;
 mod send;
+mod send_batch;
+mod split;
 mod transactions;
@@ -0,0 +1,152 @@
+use {
+  super::*,
+  ord::subcommand::wallet::{bump, split},
+  test_bitcoincore_rpc::TransactionTemplate,
+};
+
+fn split(rpc_server: &test_bitcoincore_rpc::Handle, count: &str, value: &str) -> Txid {
+  CommandBuilder::new([
+    "wallet",
+    "split",
+    "--fee-rate",
+    "1",
+    "--count",
+    count,
+    "--value",
+    value,
+  ])
+  .rpc_server(rpc_server)
+  .run_and_check_output::<split::Output>()
+  .transaction
+}
+
+#[test]
+fn bump_replaces_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = split(&rpc_server, "2", "10000 sat");
+
+  let parent = rpc_server.mempool()[0].clone();
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 10 {txid}"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Rbf);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 1);
+
+  let replacement = &mempool[0];
+  assert_eq!(replacement.txid(), output.transaction);
+  assert_eq!(
+    replacement
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>(),
+    parent
+      .input
+      .iter()
+      .map(|input| input.previous_output)
+      .collect::<Vec<OutPoint>>(),
+  );
+  assert_eq!(replacement.output[..2], parent.output[..2]);
+  assert_eq!(
+    parent.output[2].value - replacement.output[2].value,
+    output.fee - (50 * COIN_VALUE - parent.output.iter().map(|o| o.value).sum::<u64>())
+  );
+}
+
+#[test]
+fn bump_falls_back_to_child_pays_for_parent() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = split(&rpc_server, "1", "49.99999 btc");
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 50 {txid}"))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Cpfp);
+
+  let mempool = rpc_server.mempool();
+  assert_eq!(mempool.len(), 2);
+  assert_eq!(mempool[0].txid(), txid);
+  assert_eq!(mempool[1].txid(), output.transaction);
+  assert_eq!(
+    mempool[1].input[0].previous_output,
+    OutPoint { txid, vout: 0 }
+  );
+}
+
+#[test]
+fn bump_confirmed_transaction() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = split(&rpc_server, "1", "10000 sat");
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new(format!("wallet bump --fee-rate 10 {txid}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!("error: transaction {txid} is already confirmed\n"))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_transaction_without_wallet_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    ..Default::default()
+  });
+
+  CommandBuilder::new(format!("wallet bump --fee-rate 10 {txid}"))
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr(format!(
+      "error: transaction {txid} cannot be replaced and has no outputs spendable by this wallet\n"
+    ))
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn bump_commit_transaction_keeps_reveal() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let inscribe = CommandBuilder::new("wallet inscribe --fee-rate 1 foo.txt")
+    .write("foo.txt", "FOO")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Inscribe>();
+
+  let output = CommandBuilder::new(format!("wallet bump --fee-rate 10 {}", inscribe.commit))
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<bump::Output>();
+
+  assert_eq!(output.method, bump::Method::Cpfp);
+
+  let mempool = rpc_server
+    .mempool()
+    .iter()
+    .map(|tx| tx.txid())
+    .collect::<Vec<Txid>>();
+
+  assert_eq!(mempool.len(), 3);
+  assert!(mempool.contains(&inscribe.commit));
+  assert!(mempool.contains(&inscribe.reveals[0]));
+  assert!(mempool.contains(&output.transaction));
+}
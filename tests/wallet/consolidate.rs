@@ -0,0 +1,73 @@
+use {
+  super::*, ord::subcommand::wallet::consolidate::Output, test_bitcoincore_rpc::TransactionTemplate,
+};
+
+#[test]
+fn consolidate_cardinal_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(3);
+
+  let output = CommandBuilder::new("wallet consolidate --fee-rate 1")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(output.transaction, tx.txid());
+  assert_eq!(output.inputs, 3);
+  assert_eq!(tx.input.len(), 3);
+  assert_eq!(tx.output.len(), 1);
+  assert_eq!(output.value, tx.output[0].value);
+}
+
+#[test]
+fn consolidate_respects_max_inputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(3);
+
+  let output = CommandBuilder::new("wallet consolidate --fee-rate 1 --max-inputs 2")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  assert_eq!(output.inputs, 2);
+  assert_eq!(rpc_server.mempool()[0].input.len(), 2);
+}
+
+#[test]
+fn consolidate_does_not_spend_inscribed_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"bar"]),
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(2);
+
+  CommandBuilder::new("wallet consolidate --fee-rate 1")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  assert!(rpc_server.mempool()[0]
+    .input
+    .iter()
+    .all(|input| input.previous_output != OutPoint { txid, vout: 0 }));
+}
+
+#[test]
+fn consolidate_requires_two_cardinal_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("wallet consolidate --fee-rate 1")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: wallet contains fewer than two cardinal outputs worth consolidating\n")
+    .run_and_extract_stdout();
+}
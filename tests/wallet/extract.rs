@@ -0,0 +1,76 @@
+use {super::*, ord::subcommand::wallet::extract::Output};
+
+#[test]
+fn extract_sat_into_its_own_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  let coinbase = rpc_server.mine_blocks(1)[0].txdata[0].txid();
+
+  let output = CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 {}",
+    50 * COIN_VALUE + 1000
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Output>();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(output.transaction, tx.txid());
+  assert_eq!(output.size, 1);
+  assert_eq!(
+    tx.input[0].previous_output,
+    OutPoint {
+      txid: coinbase,
+      vout: 0
+    }
+  );
+  assert_eq!(output.satpoint.to_string(), format!("{}:1:0", tx.txid()));
+  assert_eq!(tx.output[0].value, 1000);
+  assert_eq!(tx.output[1].value, 10_000);
+}
+
+#[test]
+fn extract_range_into_its_own_output() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new(format!(
+    "--index-sats wallet extract --fee-rate 1 {} {}",
+    50 * COIN_VALUE + 1000,
+    50 * COIN_VALUE + 101_000,
+  ))
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Output>();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(output.size, 100_000);
+  assert_eq!(tx.output[0].value, 1000);
+  assert_eq!(tx.output[1].value, 100_000);
+}
+
+#[test]
+fn extract_sat_not_in_wallet() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new("--index-sats wallet extract --fee-rate 1 0")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: sat 0 not found in wallet\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn extract_requires_non_empty_range() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("--index-sats wallet extract --fee-rate 1 10 10")
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: range is empty\n")
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,95 @@
+use {
+  super::*, ord::subcommand::wallet::send_batch::Output, test_bitcoincore_rpc::TransactionTemplate,
+};
+
+fn inscribe_in_block(rpc_server: &test_bitcoincore_rpc::Handle, block: usize) -> String {
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(block, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(1);
+
+  format!("{txid}i0")
+}
+
+#[test]
+fn send_batch_sends_each_inscription_to_its_recipient() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(3);
+
+  let first = inscribe_in_block(&rpc_server, 1);
+  let second = inscribe_in_block(&rpc_server, 2);
+
+  let output = CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write(
+      "batch.txt",
+      format!(
+        "{first} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n\
+         {second} bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv\n"
+      ),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Output>();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(output.transaction, tx.txid());
+  assert_eq!(output.outputs.len(), 2);
+
+  for (outpoint, address) in output.outputs.iter().zip([
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+    "bc1qcqgs2pps4u4yedfyl5pysdjjncs8et5utseepv",
+  ]) {
+    let output = &tx.output[usize::try_from(outpoint.vout).unwrap()];
+    assert_eq!(output.value, 10_000);
+    assert_eq!(
+      output.script_pubkey,
+      address
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked()
+        .script_pubkey()
+    );
+  }
+}
+
+#[test]
+fn send_batch_refuses_to_send_inscriptions_by_satpoint() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let inscription = inscribe_in_block(&rpc_server, 1);
+
+  CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write(
+      "batch.txt",
+      format!(
+        "{}:0:0 bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n",
+        &inscription[..64]
+      ),
+    )
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: inscriptions must be sent by inscription ID\n")
+    .run_and_extract_stdout();
+}
+
+#[test]
+fn send_batch_reports_malformed_lines() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+
+  CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write(
+      "batch.txt",
+      "# header\nbc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n",
+    )
+    .rpc_server(&rpc_server)
+    .expected_exit_code(1)
+    .expected_stderr("error: line 2: expected `<INSCRIPTION_ID|SATPOINT> <ADDRESS>`\n")
+    .run_and_extract_stdout();
+}
@@ -0,0 +1,93 @@
+use {super::*, ord::subcommand::wallet::split::Output, test_bitcoincore_rpc::TransactionTemplate};
+
+#[test]
+fn split_cardinal_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let output = CommandBuilder::new([
+    "wallet",
+    "split",
+    "--fee-rate",
+    "1",
+    "--count",
+    "3",
+    "--value",
+    "10000 sat",
+  ])
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Output>();
+
+  let tx = &rpc_server.mempool()[0];
+
+  assert_eq!(output.transaction, tx.txid());
+  assert_eq!(
+    output.outputs,
+    (0..3)
+      .map(|vout| OutPoint {
+        txid: tx.txid(),
+        vout
+      })
+      .collect::<Vec<OutPoint>>()
+  );
+  assert_eq!(tx.output.len(), 4);
+  assert!(tx.output[..3].iter().all(|output| output.value == 10_000));
+}
+
+#[test]
+fn split_does_not_spend_inscribed_outputs() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"bar"]),
+    ..Default::default()
+  });
+
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new([
+    "wallet",
+    "split",
+    "--fee-rate",
+    "1",
+    "--count",
+    "2",
+    "--value",
+    "10000 sat",
+  ])
+  .rpc_server(&rpc_server)
+  .run_and_check_output::<Output>();
+
+  assert!(rpc_server.mempool()[0]
+    .input
+    .iter()
+    .all(|input| input.previous_output != OutPoint { txid, vout: 0 }));
+}
+
+#[test]
+fn split_fails_without_enough_cardinals() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  CommandBuilder::new([
+    "wallet",
+    "split",
+    "--fee-rate",
+    "1",
+    "--count",
+    "2",
+    "--value",
+    "50 btc",
+  ])
+  .rpc_server(&rpc_server)
+  .expected_exit_code(1)
+  .expected_stderr(
+    "error: wallet does not contain enough cardinal UTXOs, please add additional funds to wallet.\n",
+  )
+  .run_and_extract_stdout();
+}
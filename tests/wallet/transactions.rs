@@ -1,4 +1,11 @@
-use {super::*, ord::subcommand::wallet::transactions::Output};
+use {
+  super::*,
+  ord::{
+    inscription_id::InscriptionId,
+    subcommand::wallet::{send_batch, transactions::Output},
+  },
+  test_bitcoincore_rpc::TransactionTemplate,
+};
 
 #[test]
 fn transactions() {
@@ -59,3 +66,110 @@ fn transactions_with_limit() {
   assert_regex_match!(output[0].transaction.to_string(), "[[:xdigit:]]{64}");
   assert_eq!(output[0].confirmations, 1);
 }
+
+#[test]
+fn transactions_are_annotated_with_inscriptions_sent() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    ..Default::default()
+  });
+  rpc_server.mine_blocks(1);
+
+  let inscription = InscriptionId { txid, index: 0 };
+
+  let send = CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write(
+      "batch.txt",
+      format!("{inscription} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<send_batch::Output>();
+
+  let output = CommandBuilder::new("wallet transactions")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>();
+
+  let transaction = output
+    .iter()
+    .find(|output| output.transaction == send.transaction)
+    .unwrap();
+
+  assert_eq!(transaction.inscriptions_sent, [inscription]);
+  assert!(transaction.inscriptions_received.is_empty());
+  assert_eq!(
+    transaction
+      .counterparty
+      .clone()
+      .unwrap()
+      .assume_checked()
+      .to_string(),
+    "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"
+  );
+
+  let tx = &rpc_server.mempool()[0];
+  assert!(transaction.fee.unwrap() >= u64::try_from(tx.vsize()).unwrap());
+}
+
+#[test]
+fn inscriptions_sent_from_unlisted_transactions_are_annotated() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let txid = rpc_server.broadcast_tx(TransactionTemplate {
+    inputs: &[(1, 0, 0)],
+    witness: envelope(&[b"ord", &[1], b"text/plain;charset=utf-8", &[], b"foo"]),
+    ..Default::default()
+  });
+  rpc_server.mine_blocks(1);
+
+  let inscription = InscriptionId { txid, index: 0 };
+
+  let send = CommandBuilder::new("wallet send-batch --fee-rate 1 batch.txt")
+    .write(
+      "batch.txt",
+      format!("{inscription} bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4\n"),
+    )
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<send_batch::Output>();
+
+  let output = CommandBuilder::new("wallet transactions --limit 1")
+    .rpc_server(&rpc_server)
+    .run_and_check_output::<Vec<Output>>();
+
+  assert!(!output.iter().any(|output| output.transaction == txid));
+
+  let transaction = output
+    .iter()
+    .find(|output| output.transaction == send.transaction)
+    .unwrap();
+
+  assert_eq!(transaction.inscriptions_sent, [inscription]);
+  assert!(transaction.inscriptions_received.is_empty());
+}
+
+#[test]
+fn transactions_as_csv() {
+  let rpc_server = test_bitcoincore_rpc::spawn();
+  create_wallet(&rpc_server);
+  rpc_server.mine_blocks(1);
+
+  let stdout = CommandBuilder::new("wallet transactions --csv")
+    .rpc_server(&rpc_server)
+    .stdout_regex(".*")
+    .run_and_extract_stdout();
+
+  let mut lines = stdout.lines();
+
+  assert_eq!(
+    lines.next().unwrap(),
+    "transaction,confirmations,fee,counterparty,inscriptions_received,inscriptions_sent,rare_sats_received,rare_sats_sent"
+  );
+  assert_regex_match!(lines.next().unwrap(), "[[:xdigit:]]{64},1,,,,,,");
+  assert!(lines.next().is_none());
+}